@@ -721,6 +721,7 @@ fn benchmark_remap(c: &mut Criterion) {
             .copy = .copy_from"#
                 .to_string(),
             drop_on_err: true,
+            ..Default::default()
         });
 
         b.iter(add_fields_runner(Box::new(tform.unwrap())))
@@ -771,6 +772,7 @@ fn benchmark_remap(c: &mut Criterion) {
         let tform = Remap::new(RemapConfig {
             mapping: ".bar = parse_json(.foo)".to_owned(),
             drop_on_err: false,
+            ..Default::default()
         });
 
         b.iter(json_parser_runner(Box::new(tform.unwrap())))
@@ -828,6 +830,7 @@ fn benchmark_remap(c: &mut Criterion) {
                 "#
             .to_owned(),
             drop_on_err: true,
+            ..Default::default()
         })
         .unwrap();
 
@@ -0,0 +1,4 @@
+fn main() {
+    println!("cargo:rerun-if-changed=proto/remote.proto");
+    prost_build::compile_protos(&["proto/remote.proto"], &["proto/"]).unwrap();
+}
@@ -3,6 +3,11 @@ use std::collections::BTreeMap;
 
 mod line;
 
+/// The messages of the Prometheus remote write protocol.
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
 pub use line::ErrorKind;
 use line::Line;
 use line::Metric;
@@ -0,0 +1,235 @@
+use crate::{
+    config::{self, Config},
+    topology::control::TopologyControl,
+};
+use async_graphql::{Enum, Interface, Object};
+use std::collections::BTreeMap;
+
+/// The type of events a component accepts or emits
+#[Enum]
+pub enum DataType {
+    /// Both log and metric events
+    Any,
+    /// Log events only
+    Log,
+    /// Metric events only
+    Metric,
+}
+
+impl From<config::DataType> for DataType {
+    fn from(data_type: config::DataType) -> Self {
+        match data_type {
+            config::DataType::Any => DataType::Any,
+            config::DataType::Log => DataType::Log,
+            config::DataType::Metric => DataType::Metric,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Source {
+    name: String,
+    source_type: String,
+    output_type: config::DataType,
+}
+
+#[Object]
+impl Source {
+    /// Source name
+    async fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Source type, i.e. the `type` field of its config
+    async fn component_type(&self) -> String {
+        self.source_type.clone()
+    }
+
+    /// Type of events the source emits
+    async fn output_type(&self) -> DataType {
+        self.output_type.into()
+    }
+}
+
+#[derive(Clone)]
+pub struct Transform {
+    name: String,
+    transform_type: String,
+    inputs: Vec<String>,
+    input_type: config::DataType,
+    output_type: config::DataType,
+}
+
+#[Object]
+impl Transform {
+    /// Transform name
+    async fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Transform type, i.e. the `type` field of its config
+    async fn component_type(&self) -> String {
+        self.transform_type.clone()
+    }
+
+    /// Names of the components the transform receives events from
+    async fn inputs(&self) -> Vec<String> {
+        self.inputs.clone()
+    }
+
+    /// Type of events the transform accepts
+    async fn input_type(&self) -> DataType {
+        self.input_type.into()
+    }
+
+    /// Type of events the transform emits
+    async fn output_type(&self) -> DataType {
+        self.output_type.into()
+    }
+}
+
+#[derive(Clone)]
+pub struct Sink {
+    name: String,
+    sink_type: String,
+    inputs: Vec<String>,
+    input_type: config::DataType,
+}
+
+#[Object]
+impl Sink {
+    /// Sink name
+    async fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Sink type, i.e. the `type` field of its config
+    async fn component_type(&self) -> String {
+        self.sink_type.clone()
+    }
+
+    /// Names of the components the sink receives events from
+    async fn inputs(&self) -> Vec<String> {
+        self.inputs.clone()
+    }
+
+    /// Type of events the sink accepts
+    async fn input_type(&self) -> DataType {
+        self.input_type.into()
+    }
+}
+
+#[Interface(
+    field(name = "name", type = "String"),
+    field(name = "component_type", type = "String")
+)]
+#[derive(Clone)]
+pub enum Component {
+    Source(Source),
+    Transform(Transform),
+    Sink(Sink),
+}
+
+pub struct ComponentsQuery {
+    control: TopologyControl,
+}
+
+impl ComponentsQuery {
+    pub fn new(control: TopologyControl) -> Self {
+        Self { control }
+    }
+
+    /// Returns a snapshot of the components in the running topology
+    fn snapshot(&self) -> BTreeMap<String, Component> {
+        components(&self.control.config())
+    }
+}
+
+#[Object]
+impl ComponentsQuery {
+    /// All sources, transforms and sinks in the running topology
+    async fn components(&self) -> Vec<Component> {
+        self.snapshot().into_iter().map(|(_, c)| c).collect()
+    }
+
+    /// Sources in the running topology
+    async fn sources(&self) -> Vec<Source> {
+        self.snapshot()
+            .into_iter()
+            .filter_map(|(_, c)| match c {
+                Component::Source(s) => Some(s),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Transforms in the running topology
+    async fn transforms(&self) -> Vec<Transform> {
+        self.snapshot()
+            .into_iter()
+            .filter_map(|(_, c)| match c {
+                Component::Transform(t) => Some(t),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Sinks in the running topology
+    async fn sinks(&self) -> Vec<Sink> {
+        self.snapshot()
+            .into_iter()
+            .filter_map(|(_, c)| match c {
+                Component::Sink(s) => Some(s),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Look up a single component in the running topology by name
+    async fn component_by_name(&self, name: String) -> Option<Component> {
+        self.snapshot().remove(&name)
+    }
+}
+
+/// Returns the components of the given config, ordered by name
+fn components(config: &Config) -> BTreeMap<String, Component> {
+    let mut components = BTreeMap::new();
+
+    for (name, source) in config.sources.iter() {
+        components.insert(
+            name.to_owned(),
+            Component::Source(Source {
+                name: name.to_owned(),
+                source_type: source.source_type().to_owned(),
+                output_type: source.output_type(),
+            }),
+        );
+    }
+
+    for (name, transform) in config.transforms.iter() {
+        components.insert(
+            name.to_owned(),
+            Component::Transform(Transform {
+                name: name.to_owned(),
+                transform_type: transform.inner.transform_type().to_owned(),
+                inputs: transform.inputs.clone(),
+                input_type: transform.inner.input_type(),
+                output_type: transform.inner.output_type(),
+            }),
+        );
+    }
+
+    for (name, sink) in config.sinks.iter() {
+        components.insert(
+            name.to_owned(),
+            Component::Sink(Sink {
+                name: name.to_owned(),
+                sink_type: sink.inner.sink_type().to_owned(),
+                inputs: sink.inputs.clone(),
+                input_type: sink.inner.input_type(),
+            }),
+        );
+    }
+
+    components
+}
@@ -0,0 +1,31 @@
+use crate::topology::control::TopologyControl;
+use async_graphql::Object;
+
+pub struct ControlMutation {
+    control: TopologyControl,
+}
+
+impl ControlMutation {
+    pub fn new(control: TopologyControl) -> Self {
+        Self { control }
+    }
+}
+
+#[Object]
+impl ControlMutation {
+    /// Reloads the config from disk, as on SIGHUP. Returns `false` if the new config failed to load
+    /// or validate, in which case the running config is kept
+    async fn reload_config(&self) -> bool {
+        self.control.reload().await
+    }
+
+    /// Stops a source from emitting events until resumed or changed by a reload. Returns `false` if there's no such source
+    async fn pause_source(&self, name: String) -> bool {
+        self.control.pause_source(&name)
+    }
+
+    /// Lets a paused source emit events again. Returns `false` if there's no such source
+    async fn resume_source(&self, name: String) -> bool {
+        self.control.resume_source(&name)
+    }
+}
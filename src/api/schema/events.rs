@@ -0,0 +1,106 @@
+use crate::{
+    event::Event,
+    topology::{control::TopologyControl, tap::Tap},
+};
+use async_graphql::{validators::IntRange, Object, Subscription};
+use async_stream::stream;
+use tokio::stream::Stream;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::time::Duration;
+
+pub struct OutputEvent {
+    component_name: String,
+    event: Event,
+}
+
+#[Object]
+impl OutputEvent {
+    /// Name of the component the event was emitted by
+    async fn component_name(&self) -> &str {
+        &self.component_name
+    }
+
+    /// Whether the event is a log or a metric
+    async fn event_type(&self) -> &str {
+        match self.event {
+            Event::Log(_) => "log",
+            Event::Metric(_) => "metric",
+        }
+    }
+
+    /// The event, encoded as JSON
+    async fn json(&self) -> String {
+        match &self.event {
+            Event::Log(log) => serde_json::to_string(log),
+            Event::Metric(metric) => serde_json::to_string(metric),
+        }
+        .unwrap_or_else(|_| "{}".into())
+    }
+}
+
+pub struct EventsSubscription {
+    control: TopologyControl,
+}
+
+impl EventsSubscription {
+    pub fn new(control: TopologyControl) -> Self {
+        Self { control }
+    }
+}
+
+#[Subscription]
+impl EventsSubscription {
+    /// Samples of the events emitted by sources and transforms, up to `limit` per component per interval.
+    /// Components rebuilt by a reload keep being sampled
+    async fn output_events(
+        &self,
+        component_names: Vec<String>,
+        #[arg(default = 100, validator(IntRange(min = "1", max = "10_000")))] limit: i32,
+        #[arg(default = 500, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = OutputEvent> {
+        let control = self.control.clone();
+        let limit = limit as usize;
+        let mut taps = component_names
+            .into_iter()
+            .filter_map(|name| Tap::new(&control, &name, limit).map(|tap| (name, Some(tap))))
+            .collect::<Vec<_>>();
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        stream! {
+            loop {
+                interval.tick().await;
+                for (component_name, tap) in taps.iter_mut() {
+                    // A component rebuilt by a reload has a new output, and the tap on the old
+                    // one is closed. Until the new one is connected, or if the component has
+                    // been removed, this is retried on every tick.
+                    if tap.is_none() {
+                        *tap = Tap::new(&control, component_name, limit);
+                    }
+
+                    let mut closed = false;
+                    if let Some((_tap, rx)) = tap {
+                        // Events keep arriving while these are sent, so count them
+                        for _ in 0..limit {
+                            match rx.try_recv() {
+                                Ok(event) => {
+                                    yield OutputEvent {
+                                        component_name: component_name.clone(),
+                                        event,
+                                    };
+                                }
+                                Err(TryRecvError::Empty) => break,
+                                Err(TryRecvError::Closed) => {
+                                    closed = true;
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    if closed {
+                        *tap = None;
+                    }
+                }
+            }
+        }
+    }
+}
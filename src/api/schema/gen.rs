@@ -102,7 +102,7 @@ fragment TypeRef on __Type {
 
 #[tokio::main]
 async fn main() {
-    let schema = build_schema().finish();
+    let schema = build_schema(Default::default()).finish();
     let res = schema.execute(INTROSPECTION_QUERY).await;
     let json = serde_json::to_string_pretty(&async_graphql::http::GQLResponse(res)).unwrap();
 
@@ -1,8 +1,9 @@
 use crate::event::{Event, Metric, MetricValue};
 use crate::metrics::{capture_metrics, get_controller};
-use async_graphql::{validators::IntRange, Interface, Object, Subscription};
+use async_graphql::{validators::IntRange, FieldResult, Interface, Object, Subscription};
 use async_stream::stream;
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 use tokio::stream::{Stream, StreamExt};
 use tokio::time::Duration;
 
@@ -78,6 +79,96 @@ impl From<Metric> for BytesProcessed {
     }
 }
 
+pub struct ComponentMetrics {
+    name: String,
+    timestamp: DateTime<Utc>,
+    events_in: f64,
+    events_out: f64,
+    events_processed: f64,
+    bytes_processed: f64,
+    errors: f64,
+    buffer_events: f64,
+    buffer_byte_size: f64,
+    buffer_oldest_event_age: f64,
+    buffer_discarded_events: f64,
+}
+
+impl ComponentMetrics {
+    fn new(name: String, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            name,
+            timestamp,
+            events_in: 0.0,
+            events_out: 0.0,
+            events_processed: 0.0,
+            bytes_processed: 0.0,
+            errors: 0.0,
+            buffer_events: 0.0,
+            buffer_byte_size: 0.0,
+            buffer_oldest_event_age: 0.0,
+            buffer_discarded_events: 0.0,
+        }
+    }
+}
+
+#[Object]
+impl ComponentMetrics {
+    /// Component name
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Time the metrics were captured at
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// Number of events that entered the component. Always zero for sources
+    async fn events_in(&self) -> f64 {
+        self.events_in
+    }
+
+    /// Number of events that left the component. Always zero for sinks
+    async fn events_out(&self) -> f64 {
+        self.events_out
+    }
+
+    /// Number of events processed, as reported by the component itself
+    async fn events_processed(&self) -> f64 {
+        self.events_processed
+    }
+
+    /// Number of bytes processed, as reported by the component itself
+    async fn bytes_processed(&self) -> f64 {
+        self.bytes_processed
+    }
+
+    /// Number of errors the component encountered while processing events
+    async fn errors(&self) -> f64 {
+        self.errors
+    }
+
+    /// Number of events waiting in the buffer of a sink
+    async fn buffer_events(&self) -> f64 {
+        self.buffer_events
+    }
+
+    /// Number of bytes taken up on disk by the buffer of a sink
+    async fn buffer_byte_size(&self) -> f64 {
+        self.buffer_byte_size
+    }
+
+    /// Seconds the oldest event in the buffer of a sink has been waiting for
+    async fn buffer_oldest_event_age_seconds(&self) -> f64 {
+        self.buffer_oldest_event_age
+    }
+
+    /// Number of events dropped because the buffer of a sink was full
+    async fn buffer_discarded_events(&self) -> f64 {
+        self.buffer_discarded_events
+    }
+}
+
 #[Interface(field(name = "timestamp", type = "Option<DateTime<Utc>>"))]
 pub enum MetricType {
     Uptime(Uptime),
@@ -85,6 +176,24 @@ pub enum MetricType {
     BytesProcessed(BytesProcessed),
 }
 
+#[derive(Default)]
+pub struct MetricsQuery;
+
+#[Object]
+impl MetricsQuery {
+    /// Current event, byte and error totals for each component
+    async fn component_metrics(
+        &self,
+        names: Option<Vec<String>>,
+    ) -> FieldResult<Vec<ComponentMetrics>> {
+        let controller = get_controller()?;
+        Ok(component_metrics(
+            capture_metrics(controller),
+            names.as_deref(),
+        ))
+    }
+}
+
 #[derive(Default)]
 pub struct MetricsSubscription;
 
@@ -94,55 +203,139 @@ impl MetricsSubscription {
     async fn uptime_metrics(
         &self,
         #[arg(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
-    ) -> impl Stream<Item = Uptime> {
-        get_metrics(interval).filter_map(|m| match m.name.as_str() {
-            "uptime_seconds" => Some(Uptime(m)),
-            _ => None,
-        })
+    ) -> FieldResult<impl Stream<Item = Uptime>> {
+        Ok(
+            get_metrics(interval)?.filter_map(|m| match m.name.as_str() {
+                "uptime_seconds" => Some(Uptime(m)),
+                _ => None,
+            }),
+        )
     }
 
     /// Events processed metrics
     async fn events_processed_metrics(
         &self,
         #[arg(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
-    ) -> impl Stream<Item = EventsProcessed> {
-        get_metrics(interval).filter_map(|m| match m.name.as_str() {
-            "events_processed" => Some(EventsProcessed(m)),
-            _ => None,
-        })
+    ) -> FieldResult<impl Stream<Item = EventsProcessed>> {
+        Ok(
+            get_metrics(interval)?.filter_map(|m| match m.name.as_str() {
+                "events_processed" => Some(EventsProcessed(m)),
+                _ => None,
+            }),
+        )
     }
 
     /// Bytes processed metrics
     async fn bytes_processed_metrics(
         &self,
         #[arg(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
-    ) -> impl Stream<Item = BytesProcessed> {
-        get_metrics(interval).filter_map(|m| match m.name.as_str() {
-            "bytes_processed" => Some(BytesProcessed(m)),
-            _ => None,
-        })
+    ) -> FieldResult<impl Stream<Item = BytesProcessed>> {
+        Ok(
+            get_metrics(interval)?.filter_map(|m| match m.name.as_str() {
+                "bytes_processed" => Some(BytesProcessed(m)),
+                _ => None,
+            }),
+        )
     }
 
     /// All metrics
     async fn metrics(
         &self,
         #[arg(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
-    ) -> impl Stream<Item = MetricType> {
-        get_metrics(interval).filter_map(|m| match m.name.as_str() {
-            "uptime_seconds" => Some(MetricType::Uptime(m.into())),
-            "events_processed" => Some(MetricType::EventsProcessed(m.into())),
-            "bytes_processed" => Some(MetricType::BytesProcessed(m.into())),
-            _ => None,
+    ) -> FieldResult<impl Stream<Item = MetricType>> {
+        Ok(
+            get_metrics(interval)?.filter_map(|m| match m.name.as_str() {
+                "uptime_seconds" => Some(MetricType::Uptime(m.into())),
+                "events_processed" => Some(MetricType::EventsProcessed(m.into())),
+                "bytes_processed" => Some(MetricType::BytesProcessed(m.into())),
+                _ => None,
+            }),
+        )
+    }
+
+    /// Event, byte and error totals for each component, one payload per component per interval
+    async fn component_metrics(
+        &self,
+        names: Option<Vec<String>>,
+        #[arg(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> FieldResult<impl Stream<Item = ComponentMetrics>> {
+        let controller = get_controller()?;
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        Ok(stream! {
+            loop {
+                interval.tick().await;
+                for m in component_metrics(capture_metrics(&controller), names.as_deref()) {
+                    yield m;
+                }
+            }
         })
     }
 }
 
-/// Returns a stream of `Metric`s, collected at the provided millisecond interval
-fn get_metrics(interval: i32) -> impl Stream<Item = Metric> {
-    let controller = get_controller().unwrap();
+/// Sums up the counters labelled with a component name into per-component totals,
+/// ordered by component name
+fn component_metrics(
+    events: impl Iterator<Item = Event>,
+    names: Option<&[String]>,
+) -> Vec<ComponentMetrics> {
+    let timestamp = Utc::now();
+    let mut components = BTreeMap::new();
+
+    for event in events {
+        let m = match event {
+            Event::Metric(m) => m,
+            _ => continue,
+        };
+
+        let name = match m
+            .tags
+            .as_ref()
+            .and_then(|tags| tags.get("topology_component_name"))
+        {
+            Some(name) => name,
+            None => continue,
+        };
+
+        if let Some(names) = names {
+            if !names.contains(name) {
+                continue;
+            }
+        }
+
+        let value = match m.value {
+            MetricValue::Counter { value } | MetricValue::Gauge { value } => value,
+            _ => continue,
+        };
+
+        let metrics = components
+            .entry(name.clone())
+            .or_insert_with(|| ComponentMetrics::new(name.clone(), timestamp));
+
+        match m.name.as_str() {
+            "events_in_total" => metrics.events_in += value,
+            "events_out_total" => metrics.events_out += value,
+            "events_processed" => metrics.events_processed += value,
+            "bytes_processed" => metrics.bytes_processed += value,
+            "processing_errors" => metrics.errors += value,
+            "buffer_events" => metrics.buffer_events += value,
+            "buffer_byte_size" => metrics.buffer_byte_size += value,
+            "buffer_oldest_event_age_seconds" => metrics.buffer_oldest_event_age += value,
+            "buffer_discarded_events_total" => metrics.buffer_discarded_events += value,
+            _ => {}
+        }
+    }
+
+    components.into_iter().map(|(_, m)| m).collect()
+}
+
+/// Returns a stream of `Metric`s, collected at the provided millisecond interval. Fails if the
+/// metrics system hasn't been initialized
+fn get_metrics(interval: i32) -> FieldResult<impl Stream<Item = Metric>> {
+    let controller = get_controller()?;
     let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
 
-    stream! {
+    Ok(stream! {
         loop {
             interval.tick().await;
             for ev in capture_metrics(&controller) {
@@ -151,5 +344,96 @@ fn get_metrics(interval: i32) -> impl Stream<Item = Metric> {
                 }
             }
         }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::MetricKind;
+
+    fn metric(name: &str, component: Option<&str>, value: MetricValue) -> Event {
+        Event::Metric(Metric {
+            name: name.into(),
+            timestamp: None,
+            tags: component.map(|component| {
+                vec![("topology_component_name".to_owned(), component.to_owned())]
+                    .into_iter()
+                    .collect()
+            }),
+            kind: MetricKind::Absolute,
+            value,
+        })
+    }
+
+    fn counter(name: &str, component: &str, value: f64) -> Event {
+        metric(name, Some(component), MetricValue::Counter { value })
+    }
+
+    #[test]
+    fn sums_counters_per_component() {
+        let events = vec![
+            counter("events_in_total", "parse", 10.0),
+            counter("events_out_total", "parse", 8.0),
+            counter("processing_errors", "parse", 1.0),
+            // Errors are reported per error type, and summed into a single total
+            counter("processing_errors", "parse", 2.0),
+            counter("events_processed", "in", 5.0),
+            counter("bytes_processed", "in", 100.0),
+            metric(
+                "buffer_events",
+                Some("out"),
+                MetricValue::Gauge { value: 3.0 },
+            ),
+        ];
+
+        let metrics = component_metrics(events.into_iter(), None);
+        assert_eq!(
+            metrics.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+            vec!["in", "out", "parse"]
+        );
+
+        let (source, sink, transform) = (&metrics[0], &metrics[1], &metrics[2]);
+        assert_eq!(source.events_processed, 5.0);
+        assert_eq!(source.bytes_processed, 100.0);
+        assert_eq!(source.errors, 0.0);
+        assert_eq!(sink.buffer_events, 3.0);
+        assert_eq!(transform.events_in, 10.0);
+        assert_eq!(transform.events_out, 8.0);
+        assert_eq!(transform.errors, 3.0);
+    }
+
+    #[test]
+    fn reports_zero_errors_without_error_metrics() {
+        // Components that never emit `processing_errors` report zero errors
+        let metrics = component_metrics(
+            vec![counter("events_in_total", "filter", 4.0)].into_iter(),
+            None,
+        );
+
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].errors, 0.0);
+        assert_eq!(metrics[0].events_in, 4.0);
+    }
+
+    #[test]
+    fn filters_by_name_and_ignores_untagged_metrics() {
+        let events = vec![
+            counter("events_in_total", "a", 1.0),
+            counter("events_in_total", "b", 2.0),
+            metric("events_in_total", None, MetricValue::Counter { value: 3.0 }),
+            metric(
+                "events_in_total",
+                Some("b"),
+                MetricValue::Set {
+                    values: vec!["x".to_owned()].into_iter().collect(),
+                },
+            ),
+        ];
+
+        let metrics = component_metrics(events.into_iter(), Some(&["b".to_owned()]));
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].name, "b");
+        assert_eq!(metrics[0].events_in, 2.0);
     }
 }
@@ -1,15 +1,43 @@
+pub mod components;
+mod control;
+mod events;
 mod health;
 mod metrics;
 
-use async_graphql::{EmptyMutation, GQLMergedObject, GQLMergedSubscription, Schema, SchemaBuilder};
+use crate::topology::control::TopologyControl;
+use async_graphql::{GQLMergedObject, GQLMergedSubscription, Schema, SchemaBuilder};
 
-#[derive(GQLMergedObject, Default)]
-pub struct Query(health::HealthQuery);
+#[derive(GQLMergedObject)]
+pub struct Query(
+    health::HealthQuery,
+    components::ComponentsQuery,
+    metrics::MetricsQuery,
+);
 
-#[derive(GQLMergedSubscription, Default)]
-pub struct Subscription(health::HealthSubscription, metrics::MetricsSubscription);
+#[derive(GQLMergedObject)]
+pub struct Mutation(control::ControlMutation);
 
-/// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+#[derive(GQLMergedSubscription)]
+pub struct Subscription(
+    health::HealthSubscription,
+    metrics::MetricsSubscription,
+    events::EventsSubscription,
+);
+
+/// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types, on
+/// top of the topology the given handle controls
+pub fn build_schema(control: TopologyControl) -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(
+        Query(
+            health::HealthQuery,
+            components::ComponentsQuery::new(control.clone()),
+            metrics::MetricsQuery,
+        ),
+        Mutation(control::ControlMutation::new(control.clone())),
+        Subscription(
+            health::HealthSubscription,
+            metrics::MetricsSubscription,
+            events::EventsSubscription::new(control),
+        ),
+    )
 }
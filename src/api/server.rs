@@ -1,5 +1,5 @@
 use super::{handler, schema};
-use crate::config::api::Options;
+use crate::{config::api::Options, topology::control::TopologyControl};
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     QueryBuilder,
@@ -16,10 +16,11 @@ pub struct Server {
 
 impl Server {
     /// Start the API server. This creates the routes and spawns a Warp server. The server is
-    /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing
-    pub fn start(config: Options) -> Self {
+    /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing.
+    /// The API exposes and controls the topology `control` is a handle on
+    pub fn start(config: Options, control: TopologyControl) -> Self {
         let bind = config.bind.expect("Invalid socket address");
-        let routes = make_routes(config.playground);
+        let routes = make_routes(config.playground, control);
 
         let (_shutdown, rx) = oneshot::channel();
         let (_, server) = warp::serve(routes).bind_with_graceful_shutdown(bind, async {
@@ -32,9 +33,9 @@ impl Server {
     }
 }
 
-fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
+fn make_routes(playground: bool, control: TopologyControl) -> BoxedFilter<(impl Reply,)> {
     // Build the GraphQL schema
-    let schema = schema::build_schema().finish();
+    let schema = schema::build_schema(control).finish();
 
     // Routes...
 
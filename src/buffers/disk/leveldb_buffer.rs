@@ -4,7 +4,7 @@ use futures01::{
     task::{self, AtomicTask, Task},
     Async, AsyncSink, Poll, Sink, Stream,
 };
-use leveldb::database::{
+use leveldb_lib::database::{
     batch::{Batch, Writebatch},
     compaction::Compaction,
     iterator::{Iterable, LevelDBIterator},
@@ -51,7 +51,8 @@ pub struct Writer {
     writebatch: Writebatch<Key>,
     batch_size: usize,
     max_size: usize,
-    current_size: Arc<AtomicUsize>,
+    pub(super) current_size: Arc<AtomicUsize>,
+    pub(super) initial_events: usize,
 }
 
 // Writebatch isn't Send, but the leveldb docs explicitly say that it's okay to share across threads
@@ -68,6 +69,7 @@ impl Clone for Writer {
             batch_size: 0,
             max_size: self.max_size,
             current_size: Arc::clone(&self.current_size),
+            initial_events: self.initial_events,
         }
     }
 }
@@ -295,6 +297,7 @@ impl super::DiskBuffer for Buffer {
             batch_size: 0,
             max_size,
             current_size: Arc::clone(&current_size),
+            initial_events: tail - head,
         };
 
         let reader = Reader {
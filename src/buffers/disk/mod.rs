@@ -1,12 +1,15 @@
-#![cfg(feature = "leveldb")]
+#![cfg(feature = "disk-buffer")]
 
 use crate::event::Event;
 use futures01::{Async, AsyncSink, Sink, Stream};
 use snafu::Snafu;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::{atomic::AtomicUsize, Arc};
 
+#[cfg(feature = "leveldb")]
 pub mod leveldb_buffer;
+pub mod segment_buffer;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -19,10 +22,20 @@ pub enum Error {
         data_dir: PathBuf,
         source: std::io::Error,
     },
+    #[cfg(feature = "leveldb")]
     #[snafu(display("Unable to open data_dir {:?}", data_dir))]
     DataDirOpenError {
         data_dir: PathBuf,
-        source: leveldb::database::error::Error,
+        source: leveldb_lib::database::error::Error,
+    },
+    #[snafu(display("The disk buffer {:?} was written by LevelDB, which this build of vector can't read. Drain it with a build including the `leveldb` feature, or remove it to discard the events in it", path))]
+    LevelDbBufferFound { path: PathBuf },
+    #[snafu(display("The disk buffer {:?} was written as segment files, which this build of vector can't read. Drain it with a build without the `leveldb` feature, or remove it to discard the events in it", path))]
+    SegmentBufferFound { path: PathBuf },
+    #[snafu(display("Unable to open the disk buffer in data_dir {:?}", data_dir))]
+    DataDirIoError {
+        data_dir: PathBuf,
+        source: std::io::Error,
     },
 }
 
@@ -36,9 +49,37 @@ pub trait DiskBuffer {
     ) -> Result<(Self::Writer, Self::Reader, super::Acker), Error>;
 }
 
+// Existing LevelDB buffers remain readable by builds which still include it.
+#[cfg(feature = "leveldb")]
+use leveldb_buffer as backend;
+#[cfg(not(feature = "leveldb"))]
+use segment_buffer as backend;
+
+/// Fails if the buffer at `path` was left by the other backend, rather than silently
+/// leaving the events in it behind.
+#[cfg(feature = "leveldb")]
+fn check_backend(path: &Path) -> Result<(), Error> {
+    match segment_buffer::segment_ids(path) {
+        Ok(ids) if !ids.is_empty() => Err(Error::SegmentBufferFound { path: path.into() }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the buffer at `path` was left by the other backend, rather than silently
+/// leaving the events in it behind.
+#[cfg(not(feature = "leveldb"))]
+fn check_backend(path: &Path) -> Result<(), Error> {
+    // Every LevelDB database has one, naming its current manifest.
+    if path.join("CURRENT").exists() {
+        Err(Error::LevelDbBufferFound { path: path.into() })
+    } else {
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct Writer {
-    inner: leveldb_buffer::Writer,
+    inner: backend::Writer,
 }
 
 impl Sink for Writer {
@@ -57,6 +98,19 @@ impl Sink for Writer {
     }
 }
 
+impl Writer {
+    /// Number of events left in the buffer when it was opened.
+    pub fn initial_events(&self) -> usize {
+        self.inner.initial_events
+    }
+
+    /// Number of bytes taken up by the events in the buffer, updated as they're written
+    /// and acknowledged.
+    pub fn current_size(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.inner.current_size)
+    }
+}
+
 pub fn open(
     data_dir: &Path,
     name: &str,
@@ -95,6 +149,53 @@ pub fn open(
             }
         })?;
 
-    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path, max_size)?;
+    check_backend(&path)?;
+
+    let (writer, reader, acker) = backend::Buffer::build(path, max_size)?;
     Ok((Writer { inner: writer }, Box::new(reader), acker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[cfg(not(feature = "leveldb"))]
+    #[test]
+    fn open_rejects_leveldb_buffer() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sink_buffer");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("CURRENT"), "MANIFEST-000002\n").unwrap();
+
+        match open(dir.path(), "sink_buffer", 1_000_000) {
+            Err(Error::LevelDbBufferFound { path: found }) => assert_eq!(found, path),
+            _ => panic!("LevelDB buffer wasn't rejected"),
+        }
+        // It's left as it was.
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 1);
+    }
+
+    #[cfg(feature = "leveldb")]
+    #[tokio::test]
+    async fn open_rejects_segment_buffer() {
+        use futures::compat::Future01CompatExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sink_buffer");
+        {
+            let (writer, _reader, _acker) =
+                <segment_buffer::Buffer as DiskBuffer>::build(path.clone(), 1_000_000).unwrap();
+            writer
+                .send(Event::from("left behind"))
+                .compat()
+                .await
+                .unwrap();
+        }
+
+        match open(dir.path(), "sink_buffer", 1_000_000) {
+            Err(Error::SegmentBufferFound { path: found }) => assert_eq!(found, path),
+            _ => panic!("segment buffer wasn't rejected"),
+        }
+    }
+}
@@ -0,0 +1,746 @@
+//! Disk buffer keeping events in append-only segment files, so that it doesn't depend on
+//! any native libraries.
+//!
+//! Events are appended to the newest segment as protobuf records, each prefixed with its
+//! length and a CRC-32 checksum so that corrupted ones can be skipped, and a new segment
+//! is started once it has grown past `SEGMENT_SIZE`. The position of the first
+//! unacknowledged event is kept in the `ack` file, which is atomically replaced whenever
+//! events are acknowledged, after which fully acknowledged segments are deleted.
+//! Segments are synced to disk whenever the writer is flushed, on a blocking thread as
+//! that can take a while, and the ack file whenever it's replaced. After a crash, reading
+//! resumes from that position, so events might be delivered twice but are never lost.
+
+use crate::{
+    buffers::Acker,
+    event::{proto, Event},
+    internal_events::BufferEventCorrupted,
+};
+use bytes::Bytes;
+use futures::compat::Compat;
+use futures01::{
+    task::{self, AtomicTask, Task},
+    try_ready, Async, AsyncSink, Future, Poll, Sink, Stream,
+};
+use prost::Message;
+use snafu::ResultExt;
+use std::{
+    collections::VecDeque,
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::task::{spawn_blocking, JoinHandle};
+
+use super::{DataDirIoError, Error};
+
+const SEGMENT_SIZE: u64 = 16 * 1024 * 1024;
+const SEGMENT_EXTENSION: &str = "seg";
+// Length and checksum of the record
+const HEADER_SIZE: usize = 8;
+const ACK_FILE: &str = "ack";
+const ACK_TMP_FILE: &str = "ack.tmp";
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", id, SEGMENT_EXTENSION))
+}
+
+/// Ids of the segments in the buffer directory, in order.
+pub(super) fn segment_ids(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Syncs the entries of a directory, so that files created in or renamed into it survive a
+/// crash. Windows can't open directories, and has no need to.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+fn remove_segment(dir: &Path, id: u64) -> io::Result<()> {
+    match fs::remove_file(segment_path(dir, id)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Fills `buf` from `reader`. Returns `false` if the end of the file was reached first.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<bool> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => return Ok(false),
+            Ok(n) => read += n,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(true)
+}
+
+struct Record {
+    data: Vec<u8>,
+    // Whether it still matches the checksum it was written with
+    intact: bool,
+}
+
+/// Reads the record at the current position of `reader`, or `None` if there's no
+/// complete one there (yet). `available` is the number of bytes left in the file from
+/// that position on.
+fn read_record(reader: &mut impl Read, available: u64) -> io::Result<Option<Record>> {
+    let mut header = [0; HEADER_SIZE];
+    if !read_full(reader, &mut header)? {
+        return Ok(None);
+    }
+
+    let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let checksum = u32::from_le_bytes(header[4..].try_into().unwrap());
+    // Events are never encoded as zero bytes, and no record runs past the end of the file,
+    // so either can only be a partially written or corrupted one.
+    if len == 0 || (HEADER_SIZE + len) as u64 > available {
+        return Ok(None);
+    }
+
+    let mut data = vec![0; len];
+    if !read_full(reader, &mut data)? {
+        return Ok(None);
+    }
+    let intact = crc32fast::hash(&data) == checksum;
+    Ok(Some(Record { data, intact }))
+}
+
+/// Position in the buffer, right before the record starting at `offset` in `segment`.
+#[derive(Copy, Clone, Debug)]
+struct Position {
+    segment: u64,
+    offset: u64,
+}
+
+impl Position {
+    /// Reads the position of the first unacknowledged event, if there's a valid one.
+    fn read_acked(dir: &Path) -> Option<Self> {
+        let bytes = fs::read(dir.join(ACK_FILE)).ok()?;
+        if bytes.len() != 16 {
+            return None;
+        }
+
+        Some(Self {
+            segment: u64::from_le_bytes(bytes[..8].try_into().unwrap()),
+            offset: u64::from_le_bytes(bytes[8..].try_into().unwrap()),
+        })
+    }
+
+    /// Replaces the position of the first unacknowledged event. The ack file is either
+    /// left as it was or fully replaced, even if the process crashes meanwhile.
+    fn write_acked(&self, dir: &Path) -> io::Result<()> {
+        let tmp_path = dir.join(ACK_TMP_FILE);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&self.segment.to_le_bytes())?;
+        file.write_all(&self.offset.to_le_bytes())?;
+        file.sync_data()?;
+        fs::rename(tmp_path, dir.join(ACK_FILE))?;
+        sync_dir(dir)
+    }
+
+    fn advance(&mut self, record_len: usize) {
+        self.offset += (HEADER_SIZE + record_len) as u64;
+    }
+}
+
+/// The segment being written to, shared by all writers.
+struct WriteSegment {
+    dir: PathBuf,
+    id: u64,
+    size: u64,
+    file: BufWriter<File>,
+    // Bytes appended to this and the previous segments since the buffer was opened, and
+    // how many of them are known to have been synced to disk
+    written: u64,
+    synced: u64,
+}
+
+impl WriteSegment {
+    fn open(dir: PathBuf, id: u64) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(segment_path(&dir, id))?;
+        let size = file.metadata()?.len();
+        if size == 0 {
+            sync_dir(&dir)?;
+        }
+
+        Ok(Self {
+            dir,
+            id,
+            size,
+            file: BufWriter::new(file),
+            written: 0,
+            synced: 0,
+        })
+    }
+
+    fn append(&mut self, record: &[u8]) -> io::Result<()> {
+        if self.size >= SEGMENT_SIZE {
+            self.roll()?;
+        }
+
+        self.file.write_all(&(record.len() as u32).to_le_bytes())?;
+        self.file
+            .write_all(&crc32fast::hash(record).to_le_bytes())?;
+        self.file.write_all(record)?;
+        self.size += (HEADER_SIZE + record.len()) as u64;
+        self.written += (HEADER_SIZE + record.len()) as u64;
+        Ok(())
+    }
+
+    /// Writes out everything appended so far and waits for it to reach the disk.
+    fn sync(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()?;
+        self.synced = self.written;
+        Ok(())
+    }
+
+    /// Moves on to a new segment. The reader relies on the current one being completely
+    /// written by the time `id` changes.
+    fn roll(&mut self) -> io::Result<()> {
+        self.sync()?;
+        let next = Self::open(self.dir.clone(), self.id + 1)?;
+        *self = Self {
+            written: self.written,
+            synced: self.synced,
+            ..next
+        };
+        Ok(())
+    }
+}
+
+pub struct Writer {
+    segment: Arc<Mutex<WriteSegment>>,
+    write_notifier: Arc<AtomicTask>,
+    blocked_write_tasks: Arc<Mutex<Vec<Task>>>,
+    max_size: usize,
+    pub(super) current_size: Arc<AtomicUsize>,
+    pub(super) initial_events: usize,
+    // Sync started by the last flush, along with what it covers
+    syncing: Option<(u64, Compat<JoinHandle<io::Result<()>>>)>,
+}
+
+impl Clone for Writer {
+    fn clone(&self) -> Self {
+        Self {
+            segment: Arc::clone(&self.segment),
+            write_notifier: Arc::clone(&self.write_notifier),
+            blocked_write_tasks: Arc::clone(&self.blocked_write_tasks),
+            max_size: self.max_size,
+            current_size: Arc::clone(&self.current_size),
+            initial_events: self.initial_events,
+            syncing: None,
+        }
+    }
+}
+
+impl Sink for Writer {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(
+        &mut self,
+        event: Self::SinkItem,
+    ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
+        let mut value = vec![];
+        proto::EventWrapper::from(event).encode(&mut value).unwrap(); // This will not error when writing to a Vec
+        let event_size = value.len();
+
+        if self.current_size.fetch_add(event_size, Ordering::Relaxed) + (event_size / 2)
+            > self.max_size
+        {
+            self.blocked_write_tasks
+                .lock()
+                .unwrap()
+                .push(task::current());
+
+            self.current_size.fetch_sub(event_size, Ordering::Relaxed);
+
+            self.poll_complete()?;
+
+            let buf = Bytes::from(value);
+            let event = proto::EventWrapper::decode(buf).unwrap().into();
+            return Ok(AsyncSink::NotReady(event));
+        }
+
+        self.segment
+            .lock()
+            .unwrap()
+            .append(&value)
+            .map_err(|error| error!(message = "Error writing to disk buffer.", %error))?;
+
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Result<Async<()>, Self::SinkError> {
+        loop {
+            if let Some((written, syncing)) = &mut self.syncing {
+                let synced = try_ready!(syncing
+                    .poll()
+                    .map_err(|error| error!(message = "Error syncing disk buffer.", %error)));
+                let written = *written;
+                self.syncing = None;
+                synced
+                    .map_err(|error| error!(message = "Error writing to disk buffer.", %error))?;
+
+                let mut segment = self.segment.lock().unwrap();
+                segment.synced = segment.synced.max(written);
+            }
+
+            // Everything appended is written out right away for the reader, and synced
+            // without holding up the other writers meanwhile.
+            let mut segment = self.segment.lock().unwrap();
+            segment
+                .file
+                .flush()
+                .map_err(|error| error!(message = "Error writing to disk buffer.", %error))?;
+            self.write_notifier.notify();
+
+            if segment.synced == segment.written {
+                return Ok(Async::Ready(()));
+            }
+            let file = segment
+                .file
+                .get_ref()
+                .try_clone()
+                .map_err(|error| error!(message = "Error writing to disk buffer.", %error))?;
+            let syncing = spawn_blocking(move || file.sync_data());
+            self.syncing = Some((segment.written, Compat::new(syncing)));
+        }
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if let Ok(mut segment) = self.segment.lock() {
+            if let Err(error) = segment.sync() {
+                error!(message = "Error writing to disk buffer.", %error);
+            }
+        }
+
+        // We need to wake up the reader so it can return None if there are no more writers
+        self.write_notifier.notify();
+    }
+}
+
+pub struct Reader {
+    dir: PathBuf,
+    segment: Arc<Mutex<WriteSegment>>,
+    file: BufReader<File>,
+    read_position: Position,
+    ack_position: Position,
+    write_notifier: Arc<AtomicTask>,
+    blocked_write_tasks: Arc<Mutex<Vec<Task>>>,
+    current_size: Arc<AtomicUsize>,
+    ack_counter: Arc<AtomicUsize>,
+    // Position right after, and size of, each event read but not yet acknowledged
+    unacked: VecDeque<(Position, usize)>,
+}
+
+impl Stream for Reader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.delete_acked()
+            .map_err(|error| error!(message = "Error acknowledging disk buffer events.", %error))?;
+
+        // If there's no complete record at read_position, we return NotReady and rely on
+        // Writer using write_notifier to wake this task up after the next write.
+        self.write_notifier.register();
+
+        loop {
+            // Both are checked before reading, as whatever has been written by then is
+            // visible to the read.
+            let write_segment = self.segment.lock().unwrap().id;
+            let writers_left = Arc::strong_count(&self.segment) > 1;
+
+            let record = self
+                .file
+                .get_ref()
+                .metadata()
+                .and_then(|metadata| {
+                    let available = metadata.len().saturating_sub(self.read_position.offset);
+                    read_record(&mut self.file, available)
+                })
+                .map_err(|error| error!(message = "Error reading from disk buffer.", %error))?;
+
+            match record {
+                Some(record) => {
+                    let size = record.data.len();
+                    self.read_position.advance(size);
+
+                    let event = if record.intact {
+                        proto::EventWrapper::decode(Bytes::from(record.data))
+                            .map_err(|error| error.to_string())
+                    } else {
+                        Err("checksum mismatch".to_owned())
+                    };
+                    match event {
+                        Ok(event) => {
+                            self.unacked.push_back((self.read_position, size));
+                            return Ok(Async::Ready(Some(Event::from(event))));
+                        }
+                        Err(reason) => {
+                            emit!(BufferEventCorrupted { reason });
+                            // It'll never be acknowledged, so don't count it against the buffer.
+                            self.current_size.fetch_sub(size, Ordering::Relaxed);
+                        }
+                    }
+                }
+                None if write_segment > self.read_position.segment => self
+                    .next_segment()
+                    .map_err(|error| error!(message = "Error reading from disk buffer.", %error))?,
+                // There are no writers left
+                None if !writers_left => return Ok(Async::Ready(None)),
+                None => {
+                    // Go back to the start of the partially written record, if any.
+                    self.file
+                        .seek(SeekFrom::Start(self.read_position.offset))
+                        .map_err(
+                            |error| error!(message = "Error reading from disk buffer.", %error),
+                        )?;
+                    return Ok(Async::NotReady);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        if let Err(error) = self.delete_acked() {
+            error!(message = "Error acknowledging disk buffer events.", %error);
+        }
+    }
+}
+
+impl Reader {
+    fn next_segment(&mut self) -> io::Result<()> {
+        self.read_position = Position {
+            segment: self.read_position.segment + 1,
+            offset: 0,
+        };
+        self.file = BufReader::new(File::open(segment_path(
+            &self.dir,
+            self.read_position.segment,
+        ))?);
+        Ok(())
+    }
+
+    fn delete_acked(&mut self) -> io::Result<()> {
+        let num_to_delete = self.ack_counter.swap(0, Ordering::Relaxed);
+
+        if num_to_delete > 0 {
+            assert!(
+                num_to_delete <= self.unacked.len(),
+                "Tried to ack beyond read offset"
+            );
+
+            let mut new_position = self.ack_position;
+            let mut size_deleted = 0;
+            for (position, size) in self.unacked.drain(..num_to_delete) {
+                new_position = position;
+                size_deleted += size;
+            }
+
+            new_position.write_acked(&self.dir)?;
+            for id in self.ack_position.segment..new_position.segment {
+                remove_segment(&self.dir, id)?;
+            }
+            self.ack_position = new_position;
+
+            self.current_size.fetch_sub(size_deleted, Ordering::Relaxed);
+        }
+
+        for task in self.blocked_write_tasks.lock().unwrap().drain(..) {
+            task.notify();
+        }
+
+        Ok(())
+    }
+}
+
+/// Sums up the sizes and counts the complete records in a segment from `offset` on, and
+/// truncates whatever follows them, i.e. what was being written when the process last
+/// stopped.
+fn recover_segment(dir: &Path, id: u64, offset: u64) -> io::Result<(usize, usize)> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(segment_path(dir, id))?;
+    let len = file.metadata()?.len();
+
+    let mut position = Position {
+        segment: id,
+        offset: offset.min(len),
+    };
+    let mut reader = BufReader::new(&file);
+    reader.seek(SeekFrom::Start(position.offset))?;
+
+    let mut size = 0;
+    let mut count = 0;
+    while let Some(record) = read_record(&mut reader, len - position.offset)? {
+        position.advance(record.data.len());
+        size += record.data.len();
+        // Corrupted ones are skipped as they're read
+        if record.intact {
+            count += 1;
+        }
+    }
+
+    if position.offset < len {
+        warn!(
+            message = "Truncating incomplete events in disk buffer.",
+            segment = %segment_path(dir, id).display(),
+            bytes = len - position.offset
+        );
+        file.set_len(position.offset)?;
+    }
+
+    Ok((size, count))
+}
+
+pub struct Buffer;
+
+impl Buffer {
+    fn open(path: &Path, max_size: usize) -> io::Result<(Writer, Reader, Acker)> {
+        fs::create_dir_all(path)?;
+
+        let ids = segment_ids(path)?;
+        let first = ids.first().copied().unwrap_or(0);
+        let last = ids.last().copied().unwrap_or(0);
+
+        let mut ack_position = Position::read_acked(path).unwrap_or(Position {
+            segment: first,
+            offset: 0,
+        });
+        // Segments before the first left over have been fully acknowledged.
+        if ack_position.segment < first {
+            ack_position = Position {
+                segment: first,
+                offset: 0,
+            };
+        }
+
+        let mut initial_size = 0;
+        let mut initial_events = 0;
+        for &id in &ids {
+            if id < ack_position.segment {
+                remove_segment(path, id)?;
+                continue;
+            }
+
+            let offset = if id == ack_position.segment {
+                ack_position.offset
+            } else {
+                0
+            };
+            let (size, count) = recover_segment(path, id, offset)?;
+            initial_size += size;
+            initial_events += count;
+        }
+        let current_size = Arc::new(AtomicUsize::new(initial_size));
+
+        let segment = WriteSegment::open(path.to_owned(), last.max(ack_position.segment))?;
+        let segment = Arc::new(Mutex::new(segment));
+
+        let mut file = File::open(segment_path(path, ack_position.segment))?;
+        ack_position.offset = ack_position.offset.min(file.metadata()?.len());
+        file.seek(SeekFrom::Start(ack_position.offset))?;
+
+        let write_notifier = Arc::new(AtomicTask::new());
+
+        let blocked_write_tasks = Arc::new(Mutex::new(Vec::new()));
+
+        let ack_counter = Arc::new(AtomicUsize::new(0));
+        let acker = Acker::Disk(Arc::clone(&ack_counter), Arc::clone(&write_notifier));
+
+        let writer = Writer {
+            segment: Arc::clone(&segment),
+            write_notifier: Arc::clone(&write_notifier),
+            blocked_write_tasks: Arc::clone(&blocked_write_tasks),
+            max_size,
+            current_size: Arc::clone(&current_size),
+            initial_events,
+            syncing: None,
+        };
+
+        let reader = Reader {
+            dir: path.to_owned(),
+            segment,
+            file: BufReader::new(file),
+            read_position: ack_position,
+            ack_position,
+            write_notifier,
+            blocked_write_tasks,
+            current_size,
+            ack_counter,
+            unacked: VecDeque::new(),
+        };
+
+        Ok((writer, reader, acker))
+    }
+}
+
+impl super::DiskBuffer for Buffer {
+    type Writer = Writer;
+    type Reader = Reader;
+
+    fn build(path: PathBuf, max_size: usize) -> Result<(Self::Writer, Self::Reader, Acker), Error> {
+        Self::open(&path, max_size).with_context(|| DataDirIoError {
+            data_dir: path.parent().expect("always a parent"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{buffers::disk::DiskBuffer, test_util::random_lines};
+    use futures::compat::Future01CompatExt;
+    use futures01::stream;
+    use tempfile::tempdir;
+
+    fn events(count: usize) -> Vec<Event> {
+        random_lines(100).take(count).map(Event::from).collect()
+    }
+
+    async fn write(writer: Writer, events: Vec<Event>) {
+        let (writer, _) = writer
+            .send_all(stream::iter_ok(events))
+            .compat()
+            .await
+            .unwrap();
+        drop(writer);
+    }
+
+    async fn read(reader: &mut Reader, count: usize) -> Vec<Event> {
+        reader.take(count as u64).collect().compat().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn segment_buffer_reads_what_was_written() {
+        let dir = tempdir().unwrap();
+        let (writer, reader, _acker) = Buffer::build(dir.path().join("buffer"), 1_000_000).unwrap();
+
+        let sent = events(10);
+        write(writer, sent.clone()).await;
+
+        let mut reader = reader;
+        assert_eq!(read(&mut reader, 10).await, sent);
+        // There are no writers left
+        assert_eq!(reader.collect().compat().await.unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn segment_buffer_redelivers_unacked_events_after_restart() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffer");
+
+        let sent = events(10);
+        {
+            let (writer, reader, acker) = Buffer::build(path.clone(), 1_000_000).unwrap();
+            write(writer, sent.clone()).await;
+
+            let mut reader = reader;
+            read(&mut reader, 10).await;
+            acker.ack(4);
+            // Acks are processed as the reader is polled or dropped.
+            drop(reader);
+        }
+
+        let (writer, reader, _acker) = Buffer::build(path, 1_000_000).unwrap();
+        drop(writer);
+        assert_eq!(reader.collect().compat().await.unwrap(), sent[4..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn segment_buffer_truncates_incomplete_events() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffer");
+
+        let sent = events(3);
+        {
+            let (writer, _reader, _acker) = Buffer::build(path.clone(), 1_000_000).unwrap();
+            write(writer, sent.clone()).await;
+        }
+
+        // Simulate a crash in the middle of writing an event
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(segment_path(&path, 0))
+            .unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(b"partial").unwrap();
+        drop(file);
+
+        let (writer, reader, _acker) = Buffer::build(path, 1_000_000).unwrap();
+        let more = events(1);
+        write(writer, more.clone()).await;
+
+        let expected = sent.into_iter().chain(more).collect::<Vec<_>>();
+        assert_eq!(reader.collect().compat().await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn segment_buffer_skips_corrupted_events() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffer");
+
+        let sent = events(3);
+        {
+            let (writer, _reader, _acker) = Buffer::build(path.clone(), 1_000_000).unwrap();
+            write(writer, sent.clone()).await;
+        }
+
+        // Flip a bit in the first event
+        let mut bytes = fs::read(segment_path(&path, 0)).unwrap();
+        bytes[HEADER_SIZE] ^= 1;
+        fs::write(segment_path(&path, 0), bytes).unwrap();
+
+        let (writer, reader, _acker) = Buffer::build(path, 1_000_000).unwrap();
+        assert_eq!(writer.initial_events, 2);
+        drop(writer);
+        assert_eq!(reader.collect().compat().await.unwrap(), sent[1..].to_vec());
+    }
+
+    #[test]
+    fn segment_buffer_bounds_record_lengths() {
+        // A corrupted length isn't trusted with more than what's left of the file.
+        let mut bytes = u32::MAX.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0; 12]);
+        assert!(read_record(&mut &bytes[..], bytes.len() as u64)
+            .unwrap()
+            .is_none());
+    }
+}
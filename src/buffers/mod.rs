@@ -7,8 +7,16 @@ use std::sync::{
     Arc,
 };
 
-#[cfg(feature = "leveldb")]
+#[cfg(feature = "disk-buffer")]
 pub mod disk;
+#[cfg(feature = "disk-buffer")]
+pub mod overflow;
+mod usage;
+
+#[cfg(feature = "disk-buffer")]
+use usage::AckTrackedReader;
+pub use usage::BufferUsage;
+use usage::{TrackedReader, TrackedWriter};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type")]
@@ -20,12 +28,22 @@ pub enum BufferConfig {
         #[serde(default)]
         when_full: WhenFull,
     },
-    #[cfg(feature = "leveldb")]
+    #[cfg(feature = "disk-buffer")]
     Disk {
         max_size: usize,
         #[serde(default)]
         when_full: WhenFull,
     },
+    /// Events are kept in memory, and only spill to disk while `max_events` are waiting
+    /// there. `when_full` applies once the disk buffer is full too.
+    #[cfg(feature = "disk-buffer")]
+    Overflow {
+        #[serde(default = "BufferConfig::memory_max_events")]
+        max_events: usize,
+        max_size: usize,
+        #[serde(default)]
+        when_full: WhenFull,
+    },
 }
 
 impl Default for BufferConfig {
@@ -51,33 +69,63 @@ impl Default for WhenFull {
 }
 
 pub enum BufferInputCloner {
-    Memory(mpsc::Sender<Event>, WhenFull),
-    #[cfg(feature = "leveldb")]
-    Disk(disk::Writer, WhenFull),
+    Memory(mpsc::Sender<Event>, WhenFull, BufferUsage),
+    #[cfg(feature = "disk-buffer")]
+    Disk(disk::Writer, WhenFull, BufferUsage),
+    #[cfg(feature = "disk-buffer")]
+    Overflow(overflow::Writer, WhenFull, BufferUsage),
 }
 
 impl BufferInputCloner {
     pub fn get(&self) -> Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> {
         match self {
-            BufferInputCloner::Memory(tx, when_full) => {
+            BufferInputCloner::Memory(tx, when_full, usage) => {
                 let inner = tx.clone().sink_map_err(|e| error!("sender error: {:?}", e));
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull { inner })
-                } else {
-                    Box::new(inner)
-                }
+                Self::wrap(inner, *when_full, usage)
             }
 
-            #[cfg(feature = "leveldb")]
-            BufferInputCloner::Disk(writer, when_full) => {
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull {
-                        inner: writer.clone(),
-                    })
-                } else {
-                    Box::new(writer.clone())
-                }
+            #[cfg(feature = "disk-buffer")]
+            BufferInputCloner::Disk(writer, when_full, usage) => {
+                Self::wrap(writer.clone(), *when_full, usage)
             }
+
+            #[cfg(feature = "disk-buffer")]
+            BufferInputCloner::Overflow(writer, when_full, usage) => {
+                Self::wrap(writer.clone(), *when_full, usage)
+            }
+        }
+    }
+
+    /// Usage of the buffer this writes into.
+    pub fn usage(&self) -> &BufferUsage {
+        match self {
+            BufferInputCloner::Memory(_, _, usage) => usage,
+            #[cfg(feature = "disk-buffer")]
+            BufferInputCloner::Disk(_, _, usage) => usage,
+            #[cfg(feature = "disk-buffer")]
+            BufferInputCloner::Overflow(_, _, usage) => usage,
+        }
+    }
+
+    fn wrap<S>(
+        inner: S,
+        when_full: WhenFull,
+        usage: &BufferUsage,
+    ) -> Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send>
+    where
+        S: Sink<SinkItem = Event, SinkError = ()> + Send + 'static,
+    {
+        let inner = TrackedWriter {
+            inner,
+            usage: usage.clone(),
+        };
+        if when_full == WhenFull::DropNewest {
+            Box::new(DropWhenFull {
+                inner,
+                usage: usage.clone(),
+            })
+        } else {
+            Box::new(inner)
         }
     }
 }
@@ -88,7 +136,7 @@ impl BufferConfig {
         500
     }
 
-    #[cfg_attr(not(feature = "leveldb"), allow(unused))]
+    #[cfg_attr(not(feature = "disk-buffer"), allow(unused))]
     pub fn build(
         &self,
         data_dir: &Option<PathBuf>,
@@ -107,29 +155,65 @@ impl BufferConfig {
                 when_full,
             } => {
                 let (tx, rx) = mpsc::channel(*max_events);
-                let tx = BufferInputCloner::Memory(tx, *when_full);
-                let rx = Box::new(rx);
+                let usage = BufferUsage::new(0, None);
+                let tx = BufferInputCloner::Memory(tx, *when_full, usage.clone());
+                let rx = Box::new(TrackedReader { inner: rx, usage });
                 Ok((tx, rx, Acker::Null))
             }
 
-            #[cfg(feature = "leveldb")]
+            #[cfg(feature = "disk-buffer")]
             BufferConfig::Disk {
                 max_size,
                 when_full,
             } => {
-                let data_dir = data_dir
-                    .as_ref()
-                    .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
-                let buffer_dir = format!("{}_buffer", sink_name);
-
-                let (tx, rx, acker) = disk::open(&data_dir, buffer_dir.as_ref(), *max_size)
-                    .map_err(|err| err.to_string())?;
-                let tx = BufferInputCloner::Disk(tx, *when_full);
-                let rx = Box::new(rx);
-                Ok((tx, rx, acker))
+                let (tx, rx, acker) = Self::open_disk(data_dir, sink_name, *max_size)?;
+                let usage = BufferUsage::new(tx.initial_events(), Some(tx.current_size()));
+                let tx = BufferInputCloner::Disk(tx, *when_full, usage.clone());
+                let (rx, acker) = AckTrackedReader::new(rx, usage, acker);
+                Ok((tx, Box::new(rx), acker))
+            }
+
+            #[cfg(feature = "disk-buffer")]
+            BufferConfig::Overflow {
+                max_events,
+                max_size,
+                when_full,
+            } => {
+                let (memory_tx, memory_rx) = mpsc::channel(*max_events);
+                let (disk_tx, disk_rx, disk_acker) =
+                    Self::open_disk(data_dir, sink_name, *max_size)?;
+                let usage =
+                    BufferUsage::new(disk_tx.initial_events(), Some(disk_tx.current_size()));
+                let tx = overflow::Writer::new(memory_tx, disk_tx);
+                let (rx, acker) =
+                    overflow::Reader::new(memory_rx, disk_rx, disk_acker, tx.spilled());
+                let tx = BufferInputCloner::Overflow(tx, *when_full, usage.clone());
+                let (rx, acker) = AckTrackedReader::new(rx, usage, acker);
+                Ok((tx, Box::new(rx), acker))
             }
         }
     }
+
+    #[cfg(feature = "disk-buffer")]
+    fn open_disk(
+        data_dir: &Option<PathBuf>,
+        sink_name: &str,
+        max_size: usize,
+    ) -> Result<
+        (
+            disk::Writer,
+            Box<dyn Stream<Item = Event, Error = ()> + Send>,
+            Acker,
+        ),
+        String,
+    > {
+        let data_dir = data_dir
+            .as_ref()
+            .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
+        let buffer_dir = format!("{}_buffer", sink_name);
+
+        disk::open(&data_dir, buffer_dir.as_ref(), max_size).map_err(|err| err.to_string())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -169,6 +253,7 @@ impl Acker {
 
 pub struct DropWhenFull<S> {
     inner: S,
+    usage: BufferUsage,
 }
 
 impl<S: Sink> Sink for DropWhenFull<S> {
@@ -178,6 +263,7 @@ impl<S: Sink> Sink for DropWhenFull<S> {
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
         match self.inner.start_send(item) {
             Ok(AsyncSink::NotReady(_)) => {
+                self.usage.dropped();
                 debug!(
                     message = "Shedding load; dropping event.",
                     rate_limit_secs = 10
@@ -195,7 +281,7 @@ impl<S: Sink> Sink for DropWhenFull<S> {
 
 #[cfg(test)]
 mod test {
-    use super::{Acker, BufferConfig, DropWhenFull, WhenFull};
+    use super::{Acker, BufferConfig, BufferUsage, DropWhenFull, WhenFull};
     use futures::compat::Future01CompatExt;
     use futures01::{future, sync::mpsc, task::AtomicTask, Async, AsyncSink, Sink, Stream};
     use std::sync::{atomic::AtomicUsize, Arc};
@@ -206,7 +292,10 @@ mod test {
         future::lazy(|| {
             let (tx, mut rx) = mpsc::channel(2);
 
-            let mut tx = DropWhenFull { inner: tx };
+            let mut tx = DropWhenFull {
+                inner: tx,
+                usage: BufferUsage::untracked(),
+            };
 
             assert_eq!(tx.start_send(1), Ok(AsyncSink::Ready));
             assert_eq!(tx.start_send(2), Ok(AsyncSink::Ready));
@@ -281,7 +370,7 @@ mod test {
             },
         );
 
-        #[cfg(feature = "leveldb")]
+        #[cfg(feature = "disk-buffer")]
         check(
             r#"
           type = "disk"
@@ -292,5 +381,18 @@ mod test {
                 when_full: WhenFull::Block,
             },
         );
+
+        #[cfg(feature = "disk-buffer")]
+        check(
+            r#"
+          type = "overflow"
+          max_size = 1024
+          "#,
+            BufferConfig::Overflow {
+                max_events: 500,
+                max_size: 1024,
+                when_full: WhenFull::Block,
+            },
+        );
     }
 }
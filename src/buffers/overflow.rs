@@ -0,0 +1,248 @@
+//! Buffer keeping events in memory, which only spills them to a disk buffer while the
+//! memory buffer is full. Events which spilled to disk may be delivered out of order
+//! with those kept in memory.
+
+use super::{disk, Acker};
+use crate::Event;
+use futures01::{sync::mpsc, task::AtomicTask, Async, AsyncSink, Poll, Sink, StartSend, Stream};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Clone)]
+pub struct Writer {
+    memory: mpsc::Sender<Event>,
+    disk: disk::Writer,
+    // Number of events on disk which haven't been read yet
+    spilled: Arc<AtomicUsize>,
+}
+
+impl Writer {
+    pub fn new(memory: mpsc::Sender<Event>, disk: disk::Writer) -> Self {
+        let spilled = Arc::new(AtomicUsize::new(disk.initial_events()));
+        Self {
+            memory,
+            disk,
+            spilled,
+        }
+    }
+
+    /// Number of events on disk which haven't been read yet, to be shared with the reader.
+    pub fn spilled(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.spilled)
+    }
+}
+
+impl Sink for Writer {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        match self.memory.start_send(item) {
+            Ok(AsyncSink::NotReady(item)) => {
+                // Counted beforehand, so the reader never sees more events than were counted.
+                self.spilled.fetch_add(1, Ordering::AcqRel);
+                let result = self.disk.start_send(item);
+                if !matches!(result, Ok(AsyncSink::Ready)) {
+                    self.spilled.fetch_sub(1, Ordering::AcqRel);
+                }
+                result
+            }
+            Ok(AsyncSink::Ready) => Ok(AsyncSink::Ready),
+            Err(e) => {
+                error!("sender error: {:?}", e);
+                Err(())
+            }
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        let memory = self
+            .memory
+            .poll_complete()
+            .map_err(|e| error!("sender error: {:?}", e))?;
+        let disk = self.disk.poll_complete()?;
+
+        match (memory, disk) {
+            (Async::Ready(()), Async::Ready(())) => Ok(Async::Ready(())),
+            _ => Ok(Async::NotReady),
+        }
+    }
+}
+
+pub struct Reader {
+    memory: Option<mpsc::Receiver<Event>>,
+    disk: Option<Box<dyn Stream<Item = Event, Error = ()> + Send>>,
+    disk_acker: Acker,
+    spilled: Arc<AtomicUsize>,
+    ack_counter: Arc<AtomicUsize>,
+    ack_notifier: Arc<AtomicTask>,
+    // Whether each event read but not yet acknowledged came from disk
+    unacked: VecDeque<bool>,
+}
+
+impl Reader {
+    /// Merges the memory and disk buffers, returning the reader along with the acker the
+    /// sink should use for the events it reads. Acknowledgements are passed on to the
+    /// disk buffer for events read from it. The disk buffer is only read from while
+    /// `spilled`, as shared by the writer, counts events on it.
+    pub fn new(
+        memory: mpsc::Receiver<Event>,
+        disk: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+        disk_acker: Acker,
+        spilled: Arc<AtomicUsize>,
+    ) -> (Self, Acker) {
+        let ack_counter = Arc::new(AtomicUsize::new(0));
+        let ack_notifier = Arc::new(AtomicTask::new());
+        let acker = Acker::Disk(Arc::clone(&ack_counter), Arc::clone(&ack_notifier));
+
+        let reader = Self {
+            memory: Some(memory),
+            disk: Some(disk),
+            disk_acker,
+            spilled,
+            ack_counter,
+            ack_notifier,
+            unacked: VecDeque::new(),
+        };
+
+        (reader, acker)
+    }
+
+    /// Passes acknowledgements on to the disk buffer. Returns whether any were for events
+    /// read from it, which it only processes once polled.
+    fn forward_acks(&mut self) -> bool {
+        let num_acked = self.ack_counter.swap(0, Ordering::Relaxed);
+
+        if num_acked > 0 {
+            assert!(
+                num_acked <= self.unacked.len(),
+                "Tried to ack beyond read offset"
+            );
+
+            let from_disk = self.unacked.drain(..num_acked).filter(|&disk| disk).count();
+            self.disk_acker.ack(from_disk);
+            from_disk > 0
+        } else {
+            false
+        }
+    }
+}
+
+impl Stream for Reader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.ack_notifier.register();
+        let disk_acked = self.forward_acks();
+
+        // Events spill to disk while the memory buffer is full, so those on disk are
+        // usually the older ones. Reading from disk is skipped while nothing has spilled,
+        // unless it has acknowledgements to process, or the writers are gone and it only
+        // remains to see the disk buffer end.
+        let disk_pending =
+            self.spilled.load(Ordering::Acquire) > 0 || disk_acked || self.memory.is_none();
+        if let (Some(disk), true) = (&mut self.disk, disk_pending) {
+            match disk.poll()? {
+                Async::Ready(Some(event)) => {
+                    self.spilled.fetch_sub(1, Ordering::AcqRel);
+                    self.unacked.push_back(true);
+                    return Ok(Async::Ready(Some(event)));
+                }
+                Async::Ready(None) => self.disk = None,
+                Async::NotReady => {}
+            }
+        }
+
+        if let Some(memory) = &mut self.memory {
+            match memory.poll()? {
+                Async::Ready(Some(event)) => {
+                    self.unacked.push_back(false);
+                    return Ok(Async::Ready(Some(event)));
+                }
+                Async::Ready(None) => self.memory = None,
+                Async::NotReady => {}
+            }
+        }
+
+        if self.disk.is_none() && self.memory.is_none() {
+            Ok(Async::Ready(None))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        self.forward_acks();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random_lines;
+    use futures::compat::Future01CompatExt;
+    use futures01::stream;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn overflow_spills_to_disk_when_memory_is_full() {
+        let dir = tempdir().unwrap();
+        let (memory_tx, memory_rx) = mpsc::channel(2);
+        let (disk_tx, disk_rx, disk_acker) = disk::open(dir.path(), "buffer", 1_000_000).unwrap();
+
+        let writer = Writer::new(memory_tx, disk_tx);
+        let (reader, _acker) = Reader::new(memory_rx, disk_rx, disk_acker, writer.spilled());
+
+        let events = random_lines(100)
+            .take(10)
+            .map(Event::from)
+            .collect::<Vec<_>>();
+        let (writer, _) = writer
+            .send_all(stream::iter_ok(events.clone()))
+            .compat()
+            .await
+            .unwrap();
+        drop(writer);
+
+        let mut received = reader.collect().compat().await.unwrap();
+
+        // Only the first events fit in memory, the rest are read back from disk first.
+        let in_memory = received.split_off(received.len() - 3);
+        assert_eq!(in_memory, events[..3].to_vec());
+        assert_eq!(received, events[3..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn overflow_only_reads_disk_after_spilling() {
+        let dir = tempdir().unwrap();
+        let (memory_tx, memory_rx) = mpsc::channel(10);
+        let (disk_tx, _disk_rx, disk_acker) = disk::open(dir.path(), "buffer", 1_000_000).unwrap();
+
+        let writer = Writer::new(memory_tx, disk_tx);
+        let disk_rx = Box::new(stream::poll_fn(|| -> Poll<Option<Event>, ()> {
+            panic!("Read from disk without spilling")
+        }));
+        let (reader, _acker) = Reader::new(memory_rx, disk_rx, disk_acker, writer.spilled());
+
+        let events = random_lines(100)
+            .take(5)
+            .map(Event::from)
+            .collect::<Vec<_>>();
+        let (_writer, _) = writer
+            .send_all(stream::iter_ok(events.clone()))
+            .compat()
+            .await
+            .unwrap();
+
+        let received = reader.take(5).collect().compat().await.unwrap();
+        assert_eq!(received, events);
+    }
+}
@@ -0,0 +1,321 @@
+//! Tracks how full the buffer of a sink is, by counting the events going in and out of
+//! it, and periodically reports that as internal metrics from within the sink's task.
+
+#[cfg(feature = "disk-buffer")]
+use super::Acker;
+use crate::{internal_events::BufferUsageReported, Event};
+#[cfg(feature = "disk-buffer")]
+use futures01::task::AtomicTask;
+use futures01::{Async, Poll, Sink, StartSend, Stream};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Events received within this long of each other are assumed to have been received
+/// together, which bounds the memory needed to work out the age of the oldest event, and
+/// how often receiving an event takes a lock.
+const RECEIVED_GRANULARITY: Duration = Duration::from_millis(100);
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct Inner {
+    // Events ever received and sent, so that the events in the buffer are those in between
+    received: AtomicUsize,
+    sent: AtomicUsize,
+    dropped: AtomicUsize,
+    // Bytes held by the disk buffer, if any
+    byte_size: Option<Arc<AtomicUsize>>,
+    // When tracking started, and how long after that the next batch of received events
+    // may start, in nanoseconds
+    started: Instant,
+    next_batch: AtomicU64,
+    // When each batch of events received at around the same time started, along with
+    // the number of events received before it, oldest first
+    batches: Mutex<VecDeque<(Instant, usize)>>,
+}
+
+impl Inner {
+    fn events(&self) -> usize {
+        let sent = self.sent.load(Ordering::Acquire);
+        self.received.load(Ordering::Acquire).saturating_sub(sent)
+    }
+
+    /// Starts a new batch with the event received after `received` others, unless one was
+    /// started too recently.
+    fn received(&self, received: usize) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.started).as_nanos() as u64;
+        if elapsed < self.next_batch.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut batches = self.batches.lock().unwrap();
+        match batches.back() {
+            // Another writer started one meanwhile
+            Some(&(at, first))
+                if now.duration_since(at) < RECEIVED_GRANULARITY || received < first => {}
+            _ => {
+                batches.push_back((now, received));
+                let next = elapsed + RECEIVED_GRANULARITY.as_nanos() as u64;
+                self.next_batch.store(next, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn oldest_event_age(&self) -> Option<Duration> {
+        let sent = self.sent.load(Ordering::Acquire);
+        let events = self.events();
+
+        let mut batches = self.batches.lock().unwrap();
+        // Batches are done with once the next one starts with an event that's been sent.
+        while batches.len() > 1 && batches[1].1 <= sent {
+            batches.pop_front();
+        }
+
+        if events == 0 {
+            None
+        } else {
+            batches.front().map(|(at, _)| at.elapsed())
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferUsage(Option<Arc<Inner>>);
+
+impl BufferUsage {
+    /// Usage of a buffer nobody needs to know about, like the input of a transform.
+    pub fn untracked() -> Self {
+        Self(None)
+    }
+
+    /// Usage of a buffer starting out with `events` already in it, e.g. left on disk by
+    /// a previous run.
+    pub fn new(events: usize, byte_size: Option<Arc<AtomicUsize>>) -> Self {
+        let started = Instant::now();
+        let mut batches = VecDeque::new();
+        let mut next_batch = 0;
+        if events > 0 {
+            batches.push_back((started, 0));
+            next_batch = RECEIVED_GRANULARITY.as_nanos() as u64;
+        }
+
+        Self(Some(Arc::new(Inner {
+            received: AtomicUsize::new(events),
+            sent: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+            byte_size,
+            started,
+            next_batch: AtomicU64::new(next_batch),
+            batches: Mutex::new(batches),
+        })))
+    }
+
+    fn received(&self) {
+        if let Some(inner) = &self.0 {
+            let received = inner.received.fetch_add(1, Ordering::AcqRel);
+            inner.received(received);
+        }
+    }
+
+    fn sent(&self, count: usize) {
+        if let Some(inner) = &self.0 {
+            inner.sent.fetch_add(count, Ordering::AcqRel);
+        }
+    }
+
+    pub fn dropped(&self) {
+        if let Some(inner) = &self.0 {
+            inner.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Emits the usage of the buffer every second. Meant to run alongside the sink, so
+    /// that the metrics are labelled with it.
+    pub async fn report(self) {
+        let inner = match &self.0 {
+            Some(inner) => Arc::clone(inner),
+            None => return,
+        };
+
+        let mut interval = tokio::time::interval(REPORT_INTERVAL);
+        loop {
+            interval.tick().await;
+            emit!(BufferUsageReported {
+                events: inner.events(),
+                byte_size: inner
+                    .byte_size
+                    .as_ref()
+                    .map(|size| size.load(Ordering::Relaxed)),
+                oldest_event_age: inner.oldest_event_age(),
+                dropped: inner.dropped.swap(0, Ordering::Relaxed),
+            });
+        }
+    }
+}
+
+/// Counts the events written into a buffer.
+pub struct TrackedWriter<S> {
+    pub inner: S,
+    pub usage: BufferUsage,
+}
+
+impl<S: Sink<SinkItem = Event>> Sink for TrackedWriter<S> {
+    type SinkItem = Event;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let result = self.inner.start_send(item)?;
+        if result.is_ready() {
+            self.usage.received();
+        }
+        Ok(result)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.inner.poll_complete()
+    }
+}
+
+/// Counts the events read out of a buffer.
+pub struct TrackedReader<S> {
+    pub inner: S,
+    pub usage: BufferUsage,
+}
+
+impl<S: Stream<Item = Event>> Stream for TrackedReader<S> {
+    type Item = Event;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let result = self.inner.poll()?;
+        if let Async::Ready(Some(_)) = result {
+            self.usage.sent(1);
+        }
+        Ok(result)
+    }
+}
+
+/// Counts the events acknowledged by the sink reading a buffer, for buffers which keep
+/// events until then, passing the acknowledgements on to the buffer.
+#[cfg(feature = "disk-buffer")]
+pub struct AckTrackedReader<S> {
+    inner: S,
+    usage: BufferUsage,
+    inner_acker: Acker,
+    ack_counter: Arc<AtomicUsize>,
+    ack_notifier: Arc<AtomicTask>,
+}
+
+#[cfg(feature = "disk-buffer")]
+impl<S> AckTrackedReader<S> {
+    /// Wraps the reader of a buffer, returning it along with the acker the sink should use
+    /// instead of `acker`.
+    pub fn new(inner: S, usage: BufferUsage, acker: Acker) -> (Self, Acker) {
+        let ack_counter = Arc::new(AtomicUsize::new(0));
+        let ack_notifier = Arc::new(AtomicTask::new());
+        let tracked_acker = Acker::Disk(Arc::clone(&ack_counter), Arc::clone(&ack_notifier));
+
+        let reader = Self {
+            inner,
+            usage,
+            inner_acker: acker,
+            ack_counter,
+            ack_notifier,
+        };
+
+        (reader, tracked_acker)
+    }
+
+    fn forward_acks(&self) {
+        let num_acked = self.ack_counter.swap(0, Ordering::Relaxed);
+        if num_acked > 0 {
+            self.usage.sent(num_acked);
+            self.inner_acker.ack(num_acked);
+        }
+    }
+}
+
+#[cfg(feature = "disk-buffer")]
+impl<S: Stream<Item = Event>> Stream for AckTrackedReader<S> {
+    type Item = Event;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.ack_notifier.register();
+        self.forward_acks();
+        self.inner.poll()
+    }
+}
+
+#[cfg(feature = "disk-buffer")]
+impl<S> Drop for AckTrackedReader<S> {
+    fn drop(&mut self) {
+        self.forward_acks();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_tracks_events_and_their_age() {
+        let usage = BufferUsage::new(2, None);
+        let inner = Arc::clone(usage.0.as_ref().unwrap());
+
+        usage.received();
+        assert_eq!(inner.events(), 3);
+        assert_eq!(inner.batches.lock().unwrap().len(), 1);
+
+        std::thread::sleep(RECEIVED_GRANULARITY);
+        usage.received();
+        assert_eq!(inner.batches.lock().unwrap().len(), 2);
+
+        usage.sent(3);
+        assert_eq!(inner.events(), 1);
+        assert!(inner.oldest_event_age().unwrap() < RECEIVED_GRANULARITY);
+        assert_eq!(inner.batches.lock().unwrap().len(), 1);
+
+        usage.sent(1);
+        assert_eq!(inner.oldest_event_age(), None);
+    }
+
+    #[test]
+    fn usage_tracks_age_from_first_event() {
+        let usage = BufferUsage::new(0, None);
+        let inner = Arc::clone(usage.0.as_ref().unwrap());
+        assert_eq!(inner.oldest_event_age(), None);
+
+        usage.received();
+        assert!(inner.oldest_event_age().is_some());
+    }
+
+    #[cfg(feature = "disk-buffer")]
+    #[test]
+    fn ack_tracked_reader_counts_acknowledged_events() {
+        let usage = BufferUsage::new(3, None);
+        let inner = Arc::clone(usage.0.as_ref().unwrap());
+        let (inner_acker, inner_acks) = Acker::new_for_testing();
+
+        let events = futures01::stream::iter_ok::<_, ()>(vec![Event::from("a"), Event::from("b")]);
+        let (reader, acker) = AckTrackedReader::new(events, usage, inner_acker);
+        let mut reader = reader.wait();
+
+        // Reading alone leaves the events in the buffer
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert_eq!(inner.events(), 3);
+
+        acker.ack(2);
+        assert!(reader.next().is_none());
+        assert_eq!(inner.events(), 1);
+        assert_eq!(inner_acks.load(Ordering::Relaxed), 2);
+    }
+}
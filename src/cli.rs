@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
-use vector::{generate, list, unit_test, validate};
+#[cfg(feature = "api")]
+use vector::top;
+use vector::{config, generate, list, remap, unit_test, validate};
 
 #[derive(StructOpt, Debug)]
 #[structopt(rename_all = "kebab-case")]
@@ -27,13 +29,17 @@ impl Opts {
         let (quiet_level, verbose_level) = match self.sub_command {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Generate(_))
-            | Some(SubCommand::List(_)) => {
+            | Some(SubCommand::List(_))
+            | Some(SubCommand::Remap(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
                 } else {
                     (self.root.quiet, self.root.verbose - 1)
                 }
             }
+            // Logs would scroll the table off screen
+            #[cfg(feature = "api")]
+            Some(SubCommand::Top(_)) => (self.root.quiet + 2, self.root.verbose),
             _ => (self.root.quiet, self.root.verbose),
         };
         match quiet_level {
@@ -53,11 +59,34 @@ impl Opts {
 #[structopt(rename_all = "kebab-case")]
 pub struct RootOpts {
     /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
     /// If zero files are specified the default config path
     /// `/etc/vector/vector.toml` will be targeted.
     #[structopt(name = "config", short, long, env = "VECTOR_CONFIG")]
     pub config_paths: Vec<PathBuf>,
 
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// TOML file format is expected.
+    #[structopt(name = "config-toml", long, env = "VECTOR_CONFIG_TOML")]
+    pub config_paths_toml: Vec<PathBuf>,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// JSON file format is expected.
+    #[structopt(name = "config-json", long, env = "VECTOR_CONFIG_JSON")]
+    pub config_paths_json: Vec<PathBuf>,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// YAML file format is expected.
+    #[structopt(name = "config-yaml", long, env = "VECTOR_CONFIG_YAML")]
+    pub config_paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories. Wildcard paths
+    /// are supported. Files at the top level of a directory are read as regular
+    /// config files, while each file in its `sources`, `transforms`, `sinks`
+    /// and `tests` subdirectories holds a single component named after the file.
+    #[structopt(name = "config-dir", short = "C", long, env = "VECTOR_CONFIG_DIR")]
+    pub config_dirs: Vec<PathBuf>,
+
     /// Exit on startup if any sinks fail healthchecks
     #[structopt(short, long, env = "VECTOR_REQUIRE_HEALTHY")]
     pub require_healthy: bool,
@@ -93,6 +122,19 @@ pub struct RootOpts {
     pub watch_config: bool,
 }
 
+impl RootOpts {
+    /// Return a list of config paths with the associated formats.
+    pub fn config_paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::paths_with_formats(
+            &self.config_paths,
+            &self.config_paths_toml,
+            &self.config_paths_json,
+            &self.config_paths_yaml,
+            &self.config_dirs,
+        )
+    }
+}
+
 #[derive(StructOpt, Debug)]
 #[structopt(rename_all = "kebab-case")]
 pub enum SubCommand {
@@ -108,6 +150,14 @@ pub enum SubCommand {
     /// Run Vector config unit tests, then exit. This command is experimental and therefore subject to change.
     /// For guidance on how to write unit tests check out: https://vector.dev/docs/setup/guides/unit-testing/
     Test(unit_test::Opts),
+
+    /// Run a remap mapping against sample events read as JSON, printing the mapped
+    /// events. Without a mapping, statements are read interactively instead.
+    Remap(remap::Opts),
+
+    /// Display a live table of the components of a running Vector instance, using its API.
+    #[cfg(feature = "api")]
+    Top(top::Opts),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 pub mod check_fields;
 pub mod is_log;
 pub mod is_metric;
+pub mod remap;
 
 pub use check_fields::CheckFieldsConfig;
 
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conditions::{Condition, ConditionConfig, ConditionDescription},
+    mapping::{parser::parse_predicate, Predicate},
+    Event,
+};
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemapConfig {
+    source: String,
+}
+
+inventory::submit! {
+    ConditionDescription::new::<RemapConfig>("remap")
+}
+
+#[typetag::serde(name = "remap")]
+impl ConditionConfig for RemapConfig {
+    fn build(&self) -> crate::Result<Box<dyn Condition>> {
+        Ok(Box::new(Remap {
+            predicate: parse_predicate(&self.source)?,
+        }))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct Remap {
+    predicate: Predicate,
+}
+
+impl Condition for Remap {
+    fn check(&self, e: &Event) -> bool {
+        self.predicate.check(e).unwrap_or(false)
+    }
+
+    fn check_with_context(&self, e: &Event) -> Result<(), String> {
+        match self.predicate.check(e) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("source evaluated to false".to_string()),
+            Err(err) => Err(format!("source failed to evaluate: {}", err)),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::metric::{Metric, MetricKind, MetricValue};
+
+    fn build(source: &str) -> Box<dyn Condition> {
+        RemapConfig {
+            source: source.to_owned(),
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn remap_log() {
+        let cond = build(r#".status >= 500 || (.status == 404 && !contains(.path, "favicon"))"#);
+
+        let mut event = Event::from("server error");
+        event.as_mut_log().insert("status", 503);
+        event.as_mut_log().insert("path", "/");
+        assert_eq!(cond.check(&event), true);
+        assert_eq!(cond.check_with_context(&event), Ok(()));
+
+        event.as_mut_log().insert("status", 404);
+        assert_eq!(cond.check(&event), true);
+
+        event.as_mut_log().insert("path", "/favicon.ico");
+        assert_eq!(cond.check(&event), false);
+        assert_eq!(
+            cond.check_with_context(&event),
+            Err("source evaluated to false".to_string())
+        );
+
+        let event = Event::from("no status");
+        assert_eq!(cond.check(&event), false);
+        assert_eq!(
+            cond.check_with_context(&event),
+            Err("source failed to evaluate: path .status not found in event".to_string())
+        );
+    }
+
+    #[test]
+    fn remap_metric() {
+        let cond = build(r#".name == "requests" && .tags.host == "foo""#);
+
+        let metric = Metric {
+            name: "requests".to_string(),
+            timestamp: None,
+            tags: Some(
+                vec![("host".to_owned(), "foo".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+        assert_eq!(cond.check(&Event::from(metric)), true);
+    }
+
+    #[test]
+    fn remap_not_boolean() {
+        let cond = build(".message");
+
+        assert_eq!(
+            cond.check_with_context(&Event::from("foo")),
+            Err("source failed to evaluate: query returned non-boolean value".to_string())
+        );
+    }
+
+    #[test]
+    fn remap_invalid_source() {
+        assert!(RemapConfig {
+            source: ".foo = true".to_owned()
+        }
+        .build()
+        .is_err());
+    }
+}
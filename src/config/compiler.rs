@@ -31,6 +31,10 @@ pub fn compile(raw: ConfigBuilder) -> Result<Config, Vec<String>> {
         errors.extend(type_errors);
     }
 
+    if let Err(check_errors) = validation::check_transforms(&config) {
+        errors.extend(check_errors);
+    }
+
     if errors.is_empty() {
         Ok(config)
     } else {
@@ -53,17 +57,32 @@ pub(super) fn expand_macros(config: &mut Config) -> Result<(), Vec<String>> {
                 continue;
             }
         } {
+            let child_name = |name: &str| {
+                if name.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", k, name)
+                }
+            };
+
             let mut children = Vec::new();
             for (name, child) in expanded {
-                let full_name = format!("{}.{}", k, name);
+                let full_name = child_name(&name);
+                // Children fed by a sibling don't take the inputs of the transform.
+                let inputs = match child.sibling_input() {
+                    Some(sibling) => vec![child_name(sibling)],
+                    None => {
+                        children.push(full_name.clone());
+                        t.inputs.clone()
+                    }
+                };
                 expanded_transforms.insert(
-                    full_name.clone(),
+                    full_name,
                     TransformOuter {
-                        inputs: t.inputs.clone(),
+                        inputs,
                         inner: child,
                     },
                 );
-                children.push(full_name);
             }
             expansions.insert(k.clone(), children);
         } else {
@@ -0,0 +1,219 @@
+//! Support for loading configs from multiple formats.
+
+#![deny(missing_docs, missing_debug_implementations)]
+
+use serde::{de, Deserialize, Serialize};
+use std::path::Path;
+
+/// A type alias to better capture the semantics.
+pub type FormatHint = Option<Format>;
+
+/// The format used to represent the configuration data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// TOML format is used.
+    TOML,
+    /// JSON format is used.
+    JSON,
+    /// YAML format is used.
+    YAML,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::TOML
+    }
+}
+
+impl Format {
+    /// Obtain the format from the file path using extension as a hint.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> Result<Self, T> {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(Format::TOML),
+            Some("yaml") | Some("yml") => Ok(Format::YAML),
+            Some("json") => Ok(Format::JSON),
+            _ => Err(path),
+        }
+    }
+}
+
+/// Parse the string represented in the specified format.
+/// If the format is unknown - fallback to the default format and attempt
+/// parsing using that.
+pub fn deserialize<T>(content: &str, format: FormatHint) -> Result<T, Vec<String>>
+where
+    T: de::DeserializeOwned,
+{
+    match format.unwrap_or_default() {
+        Format::TOML => toml::from_str(content).map_err(|e| vec![e.to_string()]),
+        Format::YAML => serde_yaml::from_str(content).map_err(|e| vec![e.to_string()]),
+        Format::JSON => serde_json::from_str(content).map_err(|e| vec![e.to_string()]),
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "sources-socket",
+    feature = "transforms-sampler",
+    feature = "sinks-socket"
+))]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+
+    /// This test ensures the logic to guess file format from the file path
+    /// works correctly.
+    /// Like all other tests, it also demonstrates various cases and how our
+    /// code behaves when it encounters them.
+    #[test]
+    fn test_from_path() {
+        let cases = vec![
+            // Unknown - odd variants.
+            ("", None),
+            (".", None),
+            // Unknown - no ext.
+            ("myfile", None),
+            ("mydir/myfile", None),
+            ("/mydir/myfile", None),
+            // Unknown - some unknown ext.
+            ("myfile.myext", None),
+            ("mydir/myfile.myext", None),
+            ("/mydir/myfile.myext", None),
+            // Unknown - some unknown ext after known ext.
+            ("myfile.toml.myext", None),
+            ("myfile.yaml.myext", None),
+            ("myfile.yml.myext", None),
+            ("myfile.json.myext", None),
+            // Known - TOML.
+            ("myfile.toml", Some(Format::TOML)),
+            ("mydir/myfile.toml", Some(Format::TOML)),
+            ("/mydir/myfile.toml", Some(Format::TOML)),
+            // Known - JSON.
+            ("myfile.json", Some(Format::JSON)),
+            ("mydir/myfile.json", Some(Format::JSON)),
+            ("/mydir/myfile.json", Some(Format::JSON)),
+            // Known - YAML.
+            ("myfile.yaml", Some(Format::YAML)),
+            ("mydir/myfile.yaml", Some(Format::YAML)),
+            ("/mydir/myfile.yaml", Some(Format::YAML)),
+            ("myfile.yml", Some(Format::YAML)),
+            ("mydir/myfile.yml", Some(Format::YAML)),
+            ("/mydir/myfile.yml", Some(Format::YAML)),
+        ];
+
+        for (input, expected) in cases {
+            let output = Format::from_path(std::path::PathBuf::from(input));
+            assert_eq!(expected, output.ok(), "{}", input)
+        }
+    }
+
+    // Here we test that the deserializations from various formats match
+    // the TOML format.
+    #[test]
+    fn test_deserialize_matches_toml() {
+        const SAMPLE_TOML: &str = r#"
+            [sources.in]
+            type = "socket"
+            mode = "tcp"
+            address = "127.0.0.1:1235"
+            [transforms.sampler]
+            type = "sampler"
+            inputs = ["in"]
+            rate = 10
+            [sinks.out]
+            type = "socket"
+            mode = "tcp"
+            inputs = ["sampler"]
+            encoding = "text"
+            address = "127.0.0.1:9999"
+        "#;
+
+        let cases = vec![
+            // Valid empty inputs should resolve to an empty, default value.
+            ("", None, Ok("")),
+            ("", Some(Format::TOML), Ok("")),
+            ("{}", Some(Format::YAML), Ok("")),
+            ("{}", Some(Format::JSON), Ok("")),
+            // Invalid "empty" inputs should resolve to an error.
+            ("", Some(Format::YAML), Err(())),
+            ("", Some(Format::JSON), Err(())),
+            // Sample config.
+            (SAMPLE_TOML, None, Ok(SAMPLE_TOML)),
+            (SAMPLE_TOML, Some(Format::TOML), Ok(SAMPLE_TOML)),
+            (
+                r#"
+                sources:
+                  in:
+                    type: socket
+                    mode: tcp
+                    address: "127.0.0.1:1235"
+                transforms:
+                  sampler:
+                    type: sampler
+                    inputs: ["in"]
+                    rate: 10
+                sinks:
+                  out:
+                    type: socket
+                    mode: tcp
+                    inputs: ["sampler"]
+                    encoding: "text"
+                    address: "127.0.0.1:9999"
+                "#,
+                Some(Format::YAML),
+                Ok(SAMPLE_TOML),
+            ),
+            (
+                r#"
+                {
+                    "sources": {
+                        "in": {
+                            "type": "socket",
+                            "mode": "tcp",
+                            "address": "127.0.0.1:1235"
+                        }
+                    },
+                    "transforms": {
+                        "sampler": {
+                            "type": "sampler",
+                            "inputs": ["in"],
+                            "rate": 10
+                        }
+                    },
+                    "sinks": {
+                        "out": {
+                            "type": "socket",
+                            "mode": "tcp",
+                            "inputs": ["sampler"],
+                            "encoding": "text",
+                            "address": "127.0.0.1:9999"
+                        }
+                    }
+                }
+                "#,
+                Some(Format::JSON),
+                Ok(SAMPLE_TOML),
+            ),
+        ];
+
+        for (input, format, expected) in cases {
+            // Here we use the same trick as at ConfigBuilder::clone impl to
+            // compare the results.
+
+            let output = deserialize::<ConfigBuilder>(input, format);
+            match expected {
+                Ok(expected) if expected.is_empty() => {
+                    assert!(output.is_ok(), "{:?}", output.err());
+                }
+                Ok(expected) => {
+                    let output = serde_json::to_value(output.unwrap()).unwrap();
+                    let expected: ConfigBuilder = toml::from_str(expected).unwrap();
+                    let expected = serde_json::to_value(expected).unwrap();
+                    assert_eq!(expected, output, "{}", input);
+                }
+                Err(()) => assert!(output.is_err(), "{}", input),
+            }
+        }
+    }
+}
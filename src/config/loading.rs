@@ -1,22 +1,83 @@
-use super::{builder::ConfigBuilder, vars, Config};
+use super::{builder::ConfigBuilder, format, vars, Config, Format, FormatHint};
 use glob::glob;
 use lazy_static::lazy_static;
 use once_cell::sync::OnceCell;
+use serde_json::{json, Value};
 use std::{
     collections::HashMap,
-    fs::File,
+    fs::{self, File},
     path::{Path, PathBuf},
 };
 
 lazy_static! {
-    pub static ref DEFAULT_CONFIG_PATHS: Vec<PathBuf> = vec!["/etc/vector/vector.toml".into()];
+    pub static ref DEFAULT_CONFIG_PATHS: Vec<ConfigPath> =
+        vec![ConfigPath::File("/etc/vector/vector.toml".into(), None)];
 }
 
-pub static CONFIG_PATHS: OnceCell<Vec<PathBuf>> = OnceCell::new();
+pub static CONFIG_PATHS: OnceCell<Vec<ConfigPath>> = OnceCell::new();
+
+/// Subdirectories of a config directory holding one component per file. Each
+/// is named after the config section the components belong to.
+const COMPONENT_DIRS: &[&str] = &["sources", "transforms", "sinks", "tests"];
+
+/// A config file path along with the format it should be parsed as. When the
+/// format is not specified explicitly it is inferred from the file extension.
+///
+/// A config directory holds regular config files at its top level, and
+/// `sources`, `transforms`, `sinks` and `tests` subdirectories where each file
+/// contains a single component named after the file.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ConfigPath {
+    File(PathBuf, FormatHint),
+    Dir(PathBuf),
+}
+
+impl ConfigPath {
+    pub fn as_path(&self) -> &PathBuf {
+        match self {
+            ConfigPath::File(path, _) => path,
+            ConfigPath::Dir(path) => path,
+        }
+    }
+}
+
+/// Merge the paths coming from different cli flags with different formats into
+/// a unified list of paths with formats.
+pub fn merge_path_lists(path_lists: Vec<(&[PathBuf], FormatHint)>) -> Vec<ConfigPath> {
+    path_lists
+        .into_iter()
+        .flat_map(|(paths, format)| {
+            paths
+                .iter()
+                .cloned()
+                .map(move |path| ConfigPath::File(path, format))
+        })
+        .collect()
+}
+
+/// Combine the paths and directories given on the command line into a list of
+/// config paths, each carrying the format hint of the flag it came from.
+pub fn paths_with_formats(
+    paths: &[PathBuf],
+    paths_toml: &[PathBuf],
+    paths_json: &[PathBuf],
+    paths_yaml: &[PathBuf],
+    config_dirs: &[PathBuf],
+) -> Vec<ConfigPath> {
+    merge_path_lists(vec![
+        (paths, None),
+        (paths_toml, Some(Format::TOML)),
+        (paths_json, Some(Format::JSON)),
+        (paths_yaml, Some(Format::YAML)),
+    ])
+    .into_iter()
+    .chain(config_dirs.iter().cloned().map(ConfigPath::Dir))
+    .collect()
+}
 
 /// Expand a list of paths (potentially containing glob patterns) into real
 /// config paths, replacing it with the default paths when empty.
-pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
+pub fn process_paths(config_paths: &[ConfigPath]) -> Option<Vec<ConfigPath>> {
     let starting_paths = if !config_paths.is_empty() {
         config_paths
     } else {
@@ -25,7 +86,8 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
 
     let mut paths = Vec::new();
 
-    for config_pattern in starting_paths {
+    for config_path in starting_paths {
+        let config_pattern = config_path.as_path();
         let matches: Vec<PathBuf> = match glob(config_pattern.to_str().expect("No ability to glob"))
         {
             Ok(glob_paths) => glob_paths.filter_map(Result::ok).collect(),
@@ -40,8 +102,17 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
             std::process::exit(exitcode::CONFIG);
         }
 
-        for path in matches {
-            paths.push(path);
+        match config_path {
+            ConfigPath::File(_, format) => {
+                for path in matches {
+                    paths.push(ConfigPath::File(path, *format));
+                }
+            }
+            ConfigPath::Dir(_) => {
+                for path in matches {
+                    paths.push(ConfigPath::Dir(path));
+                }
+            }
         }
     }
 
@@ -54,43 +125,152 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
     Some(paths)
 }
 
-pub fn load_from_paths(config_paths: &[PathBuf]) -> Result<Config, Vec<String>> {
+pub fn load_from_paths(config_paths: &[ConfigPath]) -> Result<Config, Vec<String>> {
     load_builder_from_paths(config_paths).and_then(|builder| builder.build())
 }
 
 pub(super) fn load_builder_from_paths(
-    config_paths: &[PathBuf],
+    config_paths: &[ConfigPath],
 ) -> Result<ConfigBuilder, Vec<String>> {
     let mut inputs = Vec::new();
+    let mut dirs = Vec::new();
     let mut errors = Vec::new();
 
-    for path in config_paths {
+    for config_path in config_paths {
+        match config_path {
+            ConfigPath::File(path, format) => {
+                if let Some(file) = open_config(path) {
+                    inputs.push((file, format.or_else(|| Format::from_path(path).ok())));
+                } else {
+                    errors.push(format!("Config file not found in path: {:?}.", path));
+                }
+            }
+            ConfigPath::Dir(path) => dirs.push(path),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut builder = load_from_inputs(inputs)?;
+    for dir in dirs {
+        if let Err(errs) = load_from_dir(dir).and_then(|n| builder.append(n)) {
+            errors.extend(errs);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(builder)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Load a config directory. Files at the top level are merged as regular config
+/// files, and every file in a component subdirectory becomes a single
+/// component named after the file.
+fn load_from_dir(dir: &Path) -> Result<ConfigBuilder, Vec<String>> {
+    let mut errors = Vec::new();
+
+    let mut inputs = Vec::new();
+    for (path, format) in config_files_in_dir(dir)? {
         if let Some(file) = open_config(&path) {
-            inputs.push(file);
+            inputs.push((file, Some(format)));
         } else {
             errors.push(format!("Config file not found in path: {:?}.", path));
-        };
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut builder = load_from_inputs(inputs)?;
+    for &section in COMPONENT_DIRS {
+        let subdir = dir.join(section);
+        if !subdir.is_dir() {
+            continue;
+        }
+
+        for (path, format) in config_files_in_dir(&subdir)? {
+            if let Err(errs) =
+                load_component(&path, format, section).and_then(|n| builder.append(n))
+            {
+                errors.extend(errs.iter().map(|e| format!("{:?}: {}", path, e)));
+            }
+        }
     }
 
     if errors.is_empty() {
-        load_from_inputs(inputs)
+        Ok(builder)
     } else {
         Err(errors)
     }
 }
 
-pub fn load_from_str(input: &str) -> Result<Config, Vec<String>> {
-    load_from_inputs(std::iter::once(input.as_bytes())).and_then(|builder| builder.build())
+/// List the files directly inside the directory whose format can be inferred
+/// from their extension, in a stable order. Anything else is ignored.
+fn config_files_in_dir(dir: &Path) -> Result<Vec<(PathBuf, Format)>, Vec<String>> {
+    let entries = fs::read_dir(dir)
+        .map_err(|error| vec![format!("Could not read config dir {:?}: {}.", dir, error)])?;
+
+    let mut files = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| match Format::from_path(&path) {
+            Ok(format) => Some((path, format)),
+            Err(_) => {
+                debug!(message = "Ignoring file with unknown config format.", ?path);
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+/// Load a file holding a single component into the given config section,
+/// using the file name as the component name.
+fn load_component(
+    path: &Path,
+    format: Format,
+    section: &str,
+) -> Result<ConfigBuilder, Vec<String>> {
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| vec!["Could not infer component name from file name.".to_owned()])?;
+
+    let file = open_config(path).ok_or_else(|| vec!["Could not open file.".to_owned()])?;
+    let mut component: Value = deserialize(file, Some(format))?;
+
+    let config = if section == "tests" {
+        if let Value::Object(ref mut test) = component {
+            test.entry("name").or_insert_with(|| name.into());
+        }
+        json!({ section: [component] })
+    } else {
+        json!({ section: { name: component } })
+    };
+
+    serde_json::from_value(config).map_err(|e| vec![e.to_string()])
+}
+
+pub fn load_from_str(input: &str, format: FormatHint) -> Result<Config, Vec<String>> {
+    load_from_inputs(std::iter::once((input.as_bytes(), format)))
+        .and_then(|builder| builder.build())
 }
 
 fn load_from_inputs(
-    inputs: impl IntoIterator<Item = impl std::io::Read>,
+    inputs: impl IntoIterator<Item = (impl std::io::Read, FormatHint)>,
 ) -> Result<ConfigBuilder, Vec<String>> {
     let mut config = Config::builder();
     let mut errors = Vec::new();
 
-    for input in inputs {
-        if let Err(errs) = load(input).and_then(|n| config.append(n)) {
+    for (input, format) in inputs {
+        if let Err(errs) = load(input, format).and_then(|n| config.append(n)) {
             // TODO: add back paths
             errors.extend(errs.iter().map(|e| e.to_string()));
         }
@@ -118,7 +298,14 @@ fn open_config(path: &Path) -> Option<File> {
     }
 }
 
-fn load(mut input: impl std::io::Read) -> Result<ConfigBuilder, Vec<String>> {
+fn load(input: impl std::io::Read, format: FormatHint) -> Result<ConfigBuilder, Vec<String>> {
+    deserialize(input, format)
+}
+
+fn deserialize<T>(mut input: impl std::io::Read, format: FormatHint) -> Result<T, Vec<String>>
+where
+    T: serde::de::DeserializeOwned,
+{
     let mut source_string = String::new();
     input
         .read_to_string(&mut source_string)
@@ -132,5 +319,89 @@ fn load(mut input: impl std::io::Read) -> Result<ConfigBuilder, Vec<String>> {
     }
     let with_vars = vars::interpolate(&source_string, &vars);
 
-    toml::from_str(&with_vars).map_err(|e| vec![e.to_string()])
+    format::deserialize(&with_vars, format)
+}
+
+#[cfg(all(
+    test,
+    feature = "sources-socket",
+    feature = "transforms-sampler",
+    feature = "sinks-socket"
+))]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn write(path: PathBuf, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn load_config_dir() {
+        let dir = temp_dir();
+        write(dir.join("vector.toml"), r#"data_dir = "/tmp""#);
+        write(
+            dir.join("sources/in.toml"),
+            r#"
+            type = "socket"
+            mode = "tcp"
+            address = "127.0.0.1:1235"
+            "#,
+        );
+        write(
+            dir.join("transforms/sampler.yaml"),
+            r#"
+            type: sampler
+            inputs: ["in"]
+            rate: 10
+            "#,
+        );
+        write(
+            dir.join("sinks/out.json"),
+            r#"{
+                "type": "socket",
+                "mode": "tcp",
+                "inputs": ["sampler"],
+                "encoding": "text",
+                "address": "127.0.0.1:9999"
+            }"#,
+        );
+        write(dir.join("sinks/README.md"), "Not a config file.");
+
+        let builder = load_builder_from_paths(&[ConfigPath::Dir(dir)]).unwrap();
+
+        assert_eq!(Some(PathBuf::from("/tmp")), builder.global.data_dir);
+        assert!(builder.sources.contains_key("in"));
+        assert_eq!(vec!["in"], builder.transforms["sampler"].inputs);
+        assert_eq!(vec!["sampler"], builder.sinks["out"].inputs);
+        assert_eq!(1, builder.sinks.len());
+    }
+
+    #[test]
+    fn load_config_dir_duplicate_names() {
+        let dir = temp_dir();
+        write(
+            dir.join("vector.toml"),
+            r#"
+            [sources.in]
+            type = "socket"
+            mode = "tcp"
+            address = "127.0.0.1:1235"
+            "#,
+        );
+        write(
+            dir.join("sources/in.toml"),
+            r#"
+            type = "socket"
+            mode = "tcp"
+            address = "127.0.0.1:1236"
+            "#,
+        );
+
+        let errors = load_builder_from_paths(&[ConfigPath::Dir(dir)]).unwrap_err();
+
+        assert_eq!(1, errors.len());
+        assert!(errors[0].contains("duplicate source name found: in"));
+    }
 }
@@ -15,6 +15,7 @@ mod builder;
 mod compiler;
 pub mod component;
 mod diff;
+pub mod format;
 mod loading;
 mod log_schema;
 mod unit_test;
@@ -24,7 +25,11 @@ pub mod watcher;
 
 pub use builder::ConfigBuilder;
 pub use diff::ConfigDiff;
-pub use loading::{load_from_paths, load_from_str, process_paths, CONFIG_PATHS};
+pub use format::{Format, FormatHint};
+pub use loading::{
+    load_from_paths, load_from_str, merge_path_lists, paths_with_formats, process_paths,
+    ConfigPath, CONFIG_PATHS,
+};
 pub use log_schema::{log_schema, LogSchema, LOG_SCHEMA};
 pub use unit_test::build_unit_tests_main as build_unit_tests;
 pub use validation::warnings;
@@ -242,20 +247,46 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync {
 
     /// Allows a transform configuration to expand itself into multiple "child"
     /// transformations to replace it. This allows a transform to act as a macro
-    /// for various patterns.
+    /// for various patterns. Children are named `<name>.<child>`, except for a child
+    /// with an empty name, which takes over the name of the transform.
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
         Ok(None)
     }
+
+    /// For a child of an expanded transform, the name of the sibling it takes its input
+    /// from, instead of the inputs of the transform it was expanded from.
+    fn sibling_input(&self) -> Option<&str> {
+        None
+    }
+
+    /// Checks the parts of the configuration that can be checked without building the
+    /// transform, so that errors in them are reported when the config is loaded.
+    fn check(&self) -> crate::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TransformContext {
+    pub(super) name: String,
     pub(super) resolver: Resolver,
 }
 
 impl TransformContext {
     pub fn new_test() -> Self {
-        Self { resolver: Resolver }
+        Self::new_test_named("test")
+    }
+
+    pub fn new_test_named(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            resolver: Resolver,
+        }
+    }
+
+    /// The name of the transform being built.
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     pub fn resolver(&self) -> Resolver {
@@ -346,7 +377,7 @@ fn healthcheck_default() -> bool {
     feature = "transforms-json_parser"
 ))]
 mod test {
-    use super::{builder::ConfigBuilder, load_from_str};
+    use super::{builder::ConfigBuilder, load_from_str, Format};
     use std::path::PathBuf;
 
     #[test]
@@ -362,6 +393,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::TOML),
         )
         .unwrap();
 
@@ -384,6 +416,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::TOML),
         )
         .unwrap();
 
@@ -416,6 +449,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::TOML),
         )
         .unwrap();
 
@@ -437,6 +471,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::TOML),
         )
         .unwrap();
 
@@ -488,6 +523,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::TOML),
         )
         .unwrap();
 
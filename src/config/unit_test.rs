@@ -1,5 +1,5 @@
 use super::{
-    Config, ConfigBuilder, TestCondition, TestDefinition, TestInput, TestInputValue,
+    Config, ConfigBuilder, ConfigPath, TestCondition, TestDefinition, TestInput, TestInputValue,
     TransformContext,
 };
 use crate::{
@@ -8,9 +8,9 @@ use crate::{
     transforms::Transform,
 };
 use indexmap::IndexMap;
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-pub fn build_unit_tests_main(path: PathBuf) -> Result<Vec<UnitTest>, Vec<String>> {
+pub fn build_unit_tests_main(path: ConfigPath) -> Result<Vec<UnitTest>, Vec<String>> {
     let config = super::loading::load_builder_from_paths(&[path])?;
 
     // Ignore failures on calls other than the first
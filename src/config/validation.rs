@@ -79,6 +79,26 @@ pub fn warnings(config: &Config) -> Option<Vec<String>> {
     }
 }
 
+pub fn check_transforms(config: &Config) -> Result<(), Vec<String>> {
+    let errors = config
+        .transforms
+        .iter()
+        .filter_map(|(name, transform)| {
+            transform
+                .inner
+                .check()
+                .err()
+                .map(|error| format!("Transform \"{}\": {}", name, error))
+        })
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 pub fn typecheck(config: &Config) -> Result<(), Vec<String>> {
     Graph::from(config).typecheck()
 }
@@ -0,0 +1,47 @@
+use super::InternalEvent;
+use metrics::{counter, gauge};
+use std::time::Duration;
+
+/// Periodic report on how full the buffer of a sink is. Emitted from within the sink's
+/// span, so it's labelled with the sink the buffer belongs to.
+#[derive(Debug)]
+pub struct BufferUsageReported {
+    pub events: usize,
+    pub byte_size: Option<usize>,
+    pub oldest_event_age: Option<Duration>,
+    pub dropped: usize,
+}
+
+impl InternalEvent for BufferUsageReported {
+    fn emit_metrics(&self) {
+        gauge!("buffer_events", self.events as f64);
+        if let Some(byte_size) = self.byte_size {
+            gauge!("buffer_byte_size", byte_size as f64);
+        }
+        gauge!(
+            "buffer_oldest_event_age_seconds",
+            self.oldest_event_age
+                .map(|age| age.as_secs_f64())
+                .unwrap_or(0.0)
+        );
+        if self.dropped > 0 {
+            counter!("buffer_discarded_events_total", self.dropped as u64);
+        }
+    }
+}
+
+/// An event read back from the disk buffer was corrupted, so it's skipped.
+#[derive(Debug)]
+pub struct BufferEventCorrupted {
+    pub reason: String,
+}
+
+impl InternalEvent for BufferEventCorrupted {
+    fn emit_logs(&self) {
+        error!(message = "Skipping corrupted event in disk buffer.", %self.reason, rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("buffer_corrupted_events_total", 1);
+    }
+}
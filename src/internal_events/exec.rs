@@ -0,0 +1,104 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct ExecEventReceived<'a> {
+    pub command: &'a str,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ExecEventReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", command = %self.command);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "events_processed", 1,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+        counter!(
+            "bytes_processed", self.byte_size as u64,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecFailedToSpawn<'a> {
+    pub command: &'a str,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ExecFailedToSpawn<'_> {
+    fn emit_logs(&self) {
+        error!(message = "Unable to run command.", command = %self.command, error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "processes_failed_to_spawn", 1,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecReadFailed<'a> {
+    pub command: &'a str,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ExecReadFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to read command output.",
+            command = %self.command,
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "exec_reads_failed", 1,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecCommandExited<'a> {
+    pub command: &'a str,
+    pub exit_status: Option<i32>,
+    pub elapsed: Duration,
+}
+
+impl InternalEvent for ExecCommandExited<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Command exited.",
+            command = %self.command,
+            exit_status = ?self.exit_status,
+            elapsed_millis = %self.elapsed.as_millis(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "processes_exited", 1,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+        histogram!(
+            "process_duration_nanoseconds", self.elapsed,
+            "component_kind" => "source",
+            "component_type" => "exec",
+        );
+    }
+}
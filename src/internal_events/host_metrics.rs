@@ -0,0 +1,47 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::io;
+
+#[derive(Debug)]
+pub struct HostMetricsEventReceived {
+    pub count: usize,
+}
+
+impl InternalEvent for HostMetricsEventReceived {
+    fn emit_logs(&self) {
+        debug!(message = "Scraped host metrics.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "events_processed", self.count as u64,
+            "component_kind" => "source",
+            "component_type" => "host_metrics",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HostMetricsCollectError {
+    pub collector: &'static str,
+    pub error: io::Error,
+}
+
+impl InternalEvent for HostMetricsCollectError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to collect host metrics.",
+            collector = %self.collector,
+            error = %self.error,
+            rate_limit_secs = 60,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("collect_errors", 1,
+            "component_kind" => "source",
+            "component_type" => "host_metrics",
+            "collector" => self.collector,
+        );
+    }
+}
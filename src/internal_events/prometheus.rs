@@ -1,8 +1,9 @@
 use super::InternalEvent;
-use crate::sources::prometheus::parser::ParserError;
+use crate::sources::prometheus::{discovery::FileSdError, parser::ParserError};
 use metrics::{counter, histogram};
 use std::borrow::Cow;
-use std::time::Instant;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct PrometheusEventReceived {
@@ -114,3 +115,46 @@ impl InternalEvent for PrometheusHttpError {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct PrometheusScrapeTimeout {
+    pub timeout: Duration,
+    pub url: String,
+}
+
+impl InternalEvent for PrometheusScrapeTimeout {
+    fn emit_logs(&self) {
+        error!(message = "Scrape timed out.", url = %self.url, timeout = ?self.timeout);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("scrape_timeouts", 1,
+            "component_kind" => "source",
+            "component_type" => "prometheus",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PrometheusFileSdError<'a> {
+    pub path: &'a Path,
+    pub error: FileSdError,
+}
+
+impl<'a> InternalEvent for PrometheusFileSdError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to read targets from file.",
+            path = ?self.path,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("file_sd_errors", 1,
+            "component_kind" => "source",
+            "component_type" => "prometheus",
+        );
+    }
+}
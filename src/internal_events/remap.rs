@@ -13,17 +13,34 @@ impl InternalEvent for RemapEventProcessed {
     }
 }
 
+#[derive(Debug)]
+pub struct RemapEventDropped;
+
+impl InternalEvent for RemapEventDropped {
+    fn emit_metrics(&self) {
+        counter!("events_discarded", 1,
+            "component_kind" => "transform",
+            "component_type" => "remap",
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct RemapFailedMapping {
     /// If set to true, the remap transform has dropped the event after a failed
     /// mapping. This internal event will reflect that in its messaging.
     pub event_dropped: bool,
+    /// If set to true, the event was sent to the errors output of the transform
+    /// instead.
+    pub event_rerouted: bool,
     pub error: String,
 }
 
 impl InternalEvent for RemapFailedMapping {
     fn emit_logs(&self) {
-        let message = if self.event_dropped {
+        let message = if self.event_rerouted {
+            "Mapping failed with event; rerouting event to errors output."
+        } else if self.event_dropped {
             "Mapping failed with event; discarding event."
         } else {
             "Mapping failed with event."
@@ -0,0 +1,24 @@
+use super::InternalEvent;
+use metrics::counter;
+
+/// An event entered a transform or a sink. Emitted from within the component's
+/// span, so it's labelled with the component it was counted for.
+#[derive(Debug)]
+pub struct EventIn;
+
+impl InternalEvent for EventIn {
+    fn emit_metrics(&self) {
+        counter!("events_in_total", 1);
+    }
+}
+
+/// An event left a source or a transform. Emitted from within the component's
+/// span, so it's labelled with the component it was counted for.
+#[derive(Debug)]
+pub struct EventOut;
+
+impl InternalEvent for EventOut {
+    fn emit_metrics(&self) {
+        counter!("events_out_total", 1);
+    }
+}
@@ -45,6 +45,7 @@ pub mod mapping;
 pub mod metrics;
 pub(crate) mod pipeline;
 pub mod region;
+pub mod remap;
 pub mod serde;
 pub mod shutdown;
 pub mod signal;
@@ -54,6 +55,8 @@ pub mod stream;
 pub mod template;
 pub mod test_util;
 pub mod tls;
+#[cfg(feature = "api")]
+pub mod top;
 pub mod topology;
 pub mod trace;
 pub mod transforms;
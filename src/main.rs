@@ -13,7 +13,7 @@ use futures::{
 use std::cmp::max;
 use tokio::{runtime, select};
 #[cfg(feature = "api")]
-use vector::{api, internal_events::ApiStarted};
+use vector::{api, internal_events::ApiStarted, top};
 use vector::{
     config::{self, ConfigDiff},
     generate, heartbeat,
@@ -21,7 +21,7 @@ use vector::{
         VectorConfigLoadFailed, VectorQuit, VectorRecoveryFailed, VectorReloadFailed,
         VectorReloaded, VectorStarted, VectorStopped,
     },
-    list, metrics,
+    list, metrics, remap,
     signal::{self, SignalTo},
     topology, trace, unit_test, validate,
 };
@@ -94,16 +94,24 @@ fn main() {
                 SubCommand::List(l) => list::cmd(&l),
                 SubCommand::Test(t) => unit_test::cmd(&t),
                 SubCommand::Generate(g) => generate::cmd(&g),
+                SubCommand::Remap(r) => remap::cmd(&r),
+                #[cfg(feature = "api")]
+                SubCommand::Top(t) => top::cmd(&t).await,
             })
         };
 
-        let config_paths = config::process_paths(&opts.config_paths).unwrap_or_else(|| {
-            std::process::exit(exitcode::CONFIG);
-        });
+        let config_paths = config::process_paths(&opts.config_paths_with_formats())
+            .unwrap_or_else(|| {
+                std::process::exit(exitcode::CONFIG);
+            });
 
         if opts.watch_config {
             // Start listening for config changes immediately.
-            config::watcher::spawn_thread(&config_paths, None).unwrap_or_else(|error| {
+            let watched_paths = config_paths
+                .iter()
+                .map(|config_path| config_path.as_path().clone())
+                .collect::<Vec<_>>();
+            config::watcher::spawn_thread(&watched_paths, None).unwrap_or_else(|error| {
                 error!(message = "Unable to start config watcher.", %error);
                 std::process::exit(exitcode::CONFIG);
             });
@@ -129,25 +137,31 @@ fn main() {
             std::process::exit(exitcode::CONFIG);
         });
 
+        #[cfg(feature = "api")]
+        let api_options = config.api;
+
+        let result =
+            topology::start_validated(config, diff, pieces, opts.require_healthy).await;
+        let (mut topology, graceful_crash) = result.unwrap_or_else(|| {
+            std::process::exit(exitcode::CONFIG);
+        });
+
+        #[cfg(feature = "api")]
+        let control = topology.control();
+
         #[cfg(feature = "api")]
         // assigned to prevent the API terminating when falling out of scope
-        let _api = if config.api.enabled {
+        let _api = if api_options.enabled {
             emit!(ApiStarted{
-                addr: config.api.bind.unwrap(),
-                playground: config.api.playground
+                addr: api_options.bind.unwrap(),
+                playground: api_options.playground
             });
 
-            Some(api::Server::start(config.api))
+            Some(api::Server::start(api_options, control.clone()))
         } else {
             None
         };
 
-        let result =
-            topology::start_validated(config, diff, pieces, opts.require_healthy).await;
-        let (mut topology, graceful_crash) = result.unwrap_or_else(|| {
-            std::process::exit(exitcode::CONFIG);
-        });
-
         emit!(VectorStarted);
         tokio::spawn(heartbeat::heartbeat());
 
@@ -160,26 +174,43 @@ fn main() {
             select! {
                 Some(signal) = signals.next() => {
                     if signal == SignalTo::Reload {
+                        #[cfg(feature = "api")]
+                        let requested = control.reload_started();
+
                         // Reload config
                         let new_config = config::load_from_paths(&config_paths).map_err(handle_config_errors).ok();
 
-                        if let Some(new_config) = new_config {
+                        let reloaded = if let Some(new_config) = new_config {
                             match topology
                                 .reload_config_and_respawn(new_config, opts.require_healthy)
                                 .await
                             {
-                                Ok(true) =>  emit!(VectorReloaded { config_paths: &config_paths }),
-                                Ok(false) => emit!(VectorReloadFailed),
-                                // Trigger graceful shutdown for what remains of the topology
+                                Ok(true) => {
+                                    emit!(VectorReloaded { config_paths: &config_paths });
+                                    Ok(true)
+                                }
+                                Ok(false) => {
+                                    emit!(VectorReloadFailed);
+                                    Ok(false)
+                                }
                                 Err(()) => {
                                     emit!(VectorReloadFailed);
                                     emit!(VectorRecoveryFailed);
-                                    break SignalTo::Shutdown;
+                                    Err(())
                                 }
                             }
-                            sources_finished = topology.sources_finished().compat();
                         } else {
                             emit!(VectorConfigLoadFailed);
+                            Ok(false)
+                        };
+
+                        #[cfg(feature = "api")]
+                        control.report_reload(requested, reloaded == Ok(true));
+
+                        match reloaded {
+                            Ok(_) => sources_finished = topology.sources_finished().compat(),
+                            // Trigger graceful shutdown for what remains of the topology
+                            Err(()) => break SignalTo::Shutdown,
                         }
                     } else {
                         break signal;
@@ -1,5 +1,5 @@
-use crate::event::{Event, Value};
-use std::collections::BTreeMap;
+use crate::event::{Event, LogEvent, Metric, Value};
+use std::{collections::BTreeMap, convert::TryInto};
 
 pub mod parser;
 pub mod query;
@@ -9,7 +9,43 @@ use string_cache::DefaultAtom as Atom;
 pub type Result<T> = std::result::Result<T, String>;
 
 pub(self) trait Function: Send + core::fmt::Debug {
-    fn apply(&self, target: &mut Event) -> Result<()>;
+    fn apply(&self, target: &mut Scope) -> Result<()>;
+}
+
+/// The event being mapped, along with the variables assigned so far. Variables can be
+/// queried just like event fields, but never end up in the event.
+pub(self) struct Scope<'a> {
+    event: &'a mut Event,
+    variables: BTreeMap<String, Value>,
+    // Set once the event is dropped, after which no further statements are applied
+    dropped: bool,
+}
+
+impl<'a> Scope<'a> {
+    fn new(event: &'a mut Event) -> Self {
+        Self {
+            event,
+            variables: BTreeMap::new(),
+            dropped: false,
+        }
+    }
+}
+
+/// What should become of an event once the mapping has been applied to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Keep,
+    Drop,
+}
+
+impl query::Context for Scope<'_> {
+    fn event(&self) -> &Event {
+        self.event
+    }
+
+    fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -27,9 +63,31 @@ impl Assignment {
 }
 
 impl Function for Assignment {
-    fn apply(&self, target: &mut Event) -> Result<()> {
-        let v = self.function.execute(&target)?;
-        target.as_mut_log().insert(&self.path, v);
+    fn apply(&self, target: &mut Scope) -> Result<()> {
+        let v = self.function.execute(target)?;
+        target.event.as_mut_log().insert(&self.path, v);
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(self) struct VariableAssignment {
+    name: String,
+    function: Box<dyn query::Function>,
+}
+
+impl VariableAssignment {
+    pub(self) fn new(name: String, function: Box<dyn query::Function>) -> Self {
+        Self { name, function }
+    }
+}
+
+impl Function for VariableAssignment {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
+        let v = self.function.execute(target)?;
+        target.variables.insert(self.name.clone(), v);
         Ok(())
     }
 }
@@ -51,9 +109,9 @@ impl Deletion {
 }
 
 impl Function for Deletion {
-    fn apply(&self, target: &mut Event) -> Result<()> {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
         for path in &self.paths {
-            target.as_mut_log().remove(&path);
+            target.event.as_mut_log().remove(&path);
         }
         Ok(())
     }
@@ -73,8 +131,8 @@ impl OnlyFields {
 }
 
 impl Function for OnlyFields {
-    fn apply(&self, target: &mut Event) -> Result<()> {
-        let target_log = target.as_mut_log();
+    fn apply(&self, target: &mut Scope) -> Result<()> {
+        let target_log = target.event.as_mut_log();
 
         let keys: Vec<String> = target_log
             .keys()
@@ -118,7 +176,7 @@ impl IfStatement {
 }
 
 impl Function for IfStatement {
-    fn apply(&self, target: &mut Event) -> Result<()> {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
         match self.query.execute(target)? {
             Value::Boolean(true) => self.true_statement.apply(target),
             Value::Boolean(false) => self.false_statement.apply(target),
@@ -133,7 +191,44 @@ impl Function for IfStatement {
 pub(self) struct Noop {}
 
 impl Function for Noop {
-    fn apply(&self, _: &mut Event) -> Result<()> {
+    fn apply(&self, _: &mut Scope) -> Result<()> {
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(self) struct Block {
+    statements: Vec<Box<dyn Function>>,
+}
+
+impl Block {
+    pub(self) fn new(statements: Vec<Box<dyn Function>>) -> Self {
+        Self { statements }
+    }
+}
+
+impl Function for Block {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
+        for statement in &self.statements {
+            statement.apply(target)?;
+            if target.dropped {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(self) struct DropEvent {}
+
+impl Function for DropEvent {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
+        target.dropped = true;
         Ok(())
     }
 }
@@ -150,18 +245,129 @@ impl Mapping {
         Mapping { assignments }
     }
 
-    pub fn execute(&self, event: &mut Event) -> Result<()> {
+    /// Applies the mapping to a log or metric event. Metrics are mapped through a log
+    /// event holding their serialized fields, e.g. `.name`, `.tags.host` or
+    /// `.counter.value`, and are left untouched should the mapping fail.
+    pub fn execute(&self, event: &mut Event) -> Result<Outcome> {
+        self.execute_with_variables(event, &mut BTreeMap::new())
+    }
+
+    /// Applies the mapping starting from the given variables rather than none, which
+    /// are left holding the variables assigned by the mapping. This allows variables
+    /// to carry over from one mapping to the next, e.g. between statements entered
+    /// interactively.
+    pub fn execute_with_variables(
+        &self,
+        event: &mut Event,
+        variables: &mut BTreeMap<String, Value>,
+    ) -> Result<Outcome> {
+        match event {
+            Event::Log(_) => self.execute_log(event, variables),
+            Event::Metric(metric) => {
+                let mut log = metric_to_log(metric)?;
+                let outcome = self.execute_log(&mut log, variables)?;
+                if outcome == Outcome::Keep {
+                    *metric = log_to_metric(log)?;
+                }
+                Ok(outcome)
+            }
+        }
+    }
+
+    fn execute_log(
+        &self,
+        event: &mut Event,
+        variables: &mut BTreeMap<String, Value>,
+    ) -> Result<Outcome> {
+        let mut scope = Scope::new(event);
+        scope.variables = std::mem::take(variables);
+        let result = self.apply_assignments(&mut scope);
+        *variables = scope.variables;
+        result
+    }
+
+    fn apply_assignments(&self, scope: &mut Scope) -> Result<Outcome> {
         for (i, assignment) in self.assignments.iter().enumerate() {
-            if let Err(err) = assignment.apply(event) {
+            if let Err(err) = assignment.apply(scope) {
                 return Err(format!("failed to apply mapping {}: {}", i, err));
             }
+            if scope.dropped {
+                return Ok(Outcome::Drop);
+            }
         }
-        Ok(())
+        Ok(Outcome::Keep)
     }
 }
 
 //------------------------------------------------------------------------------
 
+/// A query checking whether events match, e.g. `.status >= 500 || !.success`.
+#[derive(Debug)]
+pub struct Predicate {
+    query: Box<dyn query::Function>,
+}
+
+impl Predicate {
+    pub(self) fn new(query: Box<dyn query::Function>) -> Self {
+        Self { query }
+    }
+
+    /// Metrics are checked against their serialized fields, just like they are mapped.
+    pub fn check(&self, event: &Event) -> Result<bool> {
+        let value = match event {
+            Event::Log(_) => self.query.execute(event)?,
+            Event::Metric(metric) => self.query.execute(&metric_to_log(metric)?)?,
+        };
+
+        match value {
+            Value::Boolean(matched) => Ok(matched),
+            _ => Err("query returned non-boolean value".to_string()),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+fn metric_to_log(metric: &Metric) -> Result<Event> {
+    let fields = match serde_json::to_value(metric) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        Ok(_) => unreachable!("metrics serialize to maps"),
+        Err(err) => return Err(format!("unable to map metric: {}", err)),
+    };
+
+    let mut log = LogEvent::default();
+    for (key, value) in fields {
+        if !value.is_null() {
+            log.insert_flat(key, Value::from(value));
+        }
+    }
+    if let Some(timestamp) = metric.timestamp {
+        log.insert_flat("timestamp", timestamp);
+    }
+    Ok(log.into())
+}
+
+fn log_to_metric(event: Event) -> Result<Metric> {
+    let mut log = event.into_log();
+
+    // Tags can only hold strings, so spare mappings from having to convert them.
+    if let Some(Value::Map(tags)) = log.get_mut(&Atom::from("tags")) {
+        for value in tags.values_mut() {
+            if !matches!(value, Value::Bytes(_)) {
+                *value = Value::from(value.to_string_lossy());
+            }
+        }
+    }
+
+    let fields: serde_json::Value = log
+        .try_into()
+        .map_err(|err| format!("mapping produced an invalid metric: {}", err))?;
+    serde_json::from_value(fields)
+        .map_err(|err| format!("mapping produced an invalid metric: {}", err))
+}
+
+//------------------------------------------------------------------------------
+
 /// Merges two BTreeMaps of `Value`s.
 /// The second map is merged into the first one.
 ///
@@ -216,7 +422,7 @@ impl MergeFn {
 }
 
 impl Function for MergeFn {
-    fn apply(&self, target: &mut Event) -> Result<()> {
+    fn apply(&self, target: &mut Scope) -> Result<()> {
         let from_value = self.from.execute(target)?;
         let deep = match &self.deep {
             None => false,
@@ -226,10 +432,14 @@ impl Function for MergeFn {
             },
         };
 
-        let to_value = target.as_mut_log().get_mut(&self.to_path).ok_or(format!(
-            "parameter {} passed to merge is not found",
-            self.to_path
-        ))?;
+        let to_value = target
+            .event
+            .as_mut_log()
+            .get_mut(&self.to_path)
+            .ok_or(format!(
+                "parameter {} passed to merge is not found",
+                self.to_path
+            ))?;
 
         match (to_value, from_value) {
             (Value::Map(ref mut map1), Value::Map(ref map2)) => {
@@ -248,7 +458,7 @@ impl Function for MergeFn {
 mod tests {
     use super::query::{
         arithmetic::Arithmetic, arithmetic::Operator as ArithmeticOperator,
-        path::Path as QueryPath, Literal,
+        path::Path as QueryPath, Literal, Variable,
     };
     use super::*;
     use crate::event::{Event, Value};
@@ -272,7 +482,7 @@ mod tests {
                     "foo".to_string(),
                     Box::new(Literal::from(Value::from("bar"))),
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -292,7 +502,7 @@ mod tests {
                     "foo bar\\.baz.buz".to_string(),
                     Box::new(Literal::from(Value::from("quack"))),
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -307,7 +517,7 @@ mod tests {
                     event
                 },
                 Mapping::new(vec![Box::new(Deletion::new(vec!["foo".to_string()]))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -329,7 +539,7 @@ mod tests {
                     )),
                     Box::new(Deletion::new(vec!["bar".to_string()])),
                 ]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -357,7 +567,7 @@ mod tests {
                     )),
                     Box::new(Deletion::new(vec!["bar".to_string()])),
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -383,7 +593,7 @@ mod tests {
                     )),
                     Box::new(Deletion::new(vec!["bar".to_string()])),
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -451,7 +661,83 @@ mod tests {
                     "doesnt_exist.anyway".to_string(),
                     "nested".to_string(),
                 ]))]),
-                Ok(()),
+                Ok(Outcome::Keep),
+            ),
+            (
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().insert("bar", Value::from("baz"));
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().insert("foo", Value::from("baz"));
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                Mapping::new(vec![
+                    Box::new(VariableAssignment::new(
+                        "tmp".to_string(),
+                        Box::new(QueryPath::from("bar")),
+                    )),
+                    Box::new(Block::new(vec![
+                        Box::new(Deletion::new(vec!["bar".to_string()])),
+                        Box::new(Assignment::new(
+                            "foo".to_string(),
+                            Box::new(Variable::new("tmp".to_string())),
+                        )),
+                    ])),
+                ]),
+                Ok(Outcome::Keep),
+            ),
+            (
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Variable::new("tmp".to_string())),
+                ))]),
+                Err("failed to apply mapping 0: variable $tmp not assigned".to_string()),
+            ),
+            (
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                {
+                    let mut event = Event::from("foo body");
+                    event.as_mut_log().insert("foo", Value::from("bar"));
+                    event.as_mut_log().remove(&Atom::from("timestamp"));
+                    event
+                },
+                Mapping::new(vec![
+                    Box::new(Block::new(vec![
+                        Box::new(Assignment::new(
+                            "foo".to_string(),
+                            Box::new(Literal::from(Value::from("bar"))),
+                        )),
+                        Box::new(DropEvent {}),
+                        Box::new(Assignment::new(
+                            "bar".to_string(),
+                            Box::new(Literal::from(Value::from("baz"))),
+                        )),
+                    ])),
+                    Box::new(Assignment::new(
+                        "baz".to_string(),
+                        Box::new(Literal::from(Value::from("buz"))),
+                    )),
+                ]),
+                Ok(Outcome::Drop),
             ),
         ];
 
@@ -523,7 +809,7 @@ mod tests {
                     Box::new(QueryPath::from(vec![vec!["bar"]])),
                     None,
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -580,7 +866,7 @@ mod tests {
                     Box::new(QueryPath::from(vec![vec!["parent2"]])),
                     None,
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
             (
                 {
@@ -638,7 +924,7 @@ mod tests {
                     Box::new(QueryPath::from(vec![vec!["parent2"]])),
                     Some(Box::new(Literal::from(Value::Boolean(true)))),
                 ))]),
-                Ok(()),
+                Ok(Outcome::Keep),
             ),
         ];
 
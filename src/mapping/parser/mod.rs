@@ -1,25 +1,28 @@
 extern crate pest;
 
 use crate::{
-    event::Value,
+    event::{Event, Value},
     mapping::{
         query::{
             self,
             arithmetic::Arithmetic,
             arithmetic::Operator,
-            function::{Argument, ArgumentList, FunctionSignature, NotFn},
+            function::{Argument, ArgumentList, FunctionSignature, NotFn, Parameter, Resolver},
+            index::Index,
             path::Path as QueryPath,
-            Literal,
+            Array, Literal, Object, Variable,
         },
-        Assignment, Deletion, Function, IfStatement, Mapping, MergeFn, Noop, OnlyFields, Result,
+        Assignment, Block, Deletion, DropEvent, Function, IfStatement, Mapping, MergeFn, Noop,
+        OnlyFields, Predicate, Result, VariableAssignment,
     },
 };
 use pest::{
     error::ErrorVariant,
     iterators::{Pair, Pairs},
-    Parser,
+    Parser, Span,
 };
-use std::str::FromStr;
+use regex::{Regex, RegexBuilder};
+use std::{iter::Peekable, str::FromStr};
 
 // If this macro triggers, it means the parser syntax file (grammar.pest) was
 // updated in unexpected, and unsupported ways.
@@ -89,30 +92,44 @@ fn path_segments_from_pair(pair: Pair<Rule>) -> Result<Vec<Vec<String>>> {
     Ok(segments)
 }
 
-fn query_arithmetic_product_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
-    let pair = pairs.next().ok_or(TOKEN_ERR)?;
-    let mut left = query_from_pair(pair)?;
-    let mut op = Operator::Multiply;
+fn query_arithmetic_product_from_pairs(pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
+    let mut pairs = pairs.peekable();
+    let mut left = indexed_query_from_pairs(&mut pairs)?;
 
-    for pair in pairs {
-        match pair.as_rule() {
-            Rule::arithmetic_operator_product => {
-                op = match pair.as_str() {
-                    "*" => Operator::Multiply,
-                    "/" => Operator::Divide,
-                    "%" => Operator::Modulo,
-                    s => return Err(format!("operator not recognized: {}", s)),
-                };
-            }
-            _ => {
-                left = Box::new(Arithmetic::new(left, query_from_pair(pair)?, op.clone()));
-            }
-        }
+    while let Some(pair) = pairs.next() {
+        let op = match pair.as_str() {
+            "*" => Operator::Multiply,
+            "/" => Operator::Divide,
+            "%" => Operator::Modulo,
+            s => return Err(format!("operator not recognized: {}", s)),
+        };
+
+        left = Box::new(Arithmetic::new(
+            left,
+            indexed_query_from_pairs(&mut pairs)?,
+            op,
+        ));
     }
 
     Ok(left)
 }
 
+/// Takes a query along with any indexes following it.
+fn indexed_query_from_pairs(pairs: &mut Peekable<Pairs<Rule>>) -> Result<Box<dyn query::Function>> {
+    let mut query = query_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+
+    // Indexes are the only queries following another without an operator in
+    // between.
+    while pairs.peek().map_or(false, |pair| {
+        pair.as_rule() == Rule::query_arithmetic_boolean
+    }) {
+        let index = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+        query = Box::new(Index::new(query, index));
+    }
+
+    Ok(query)
+}
+
 fn query_arithmetic_sum_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
     let inner_pairs = pairs.next().ok_or(TOKEN_ERR)?.into_inner();
     let mut left = query_arithmetic_product_from_pairs(inner_pairs)?;
@@ -202,17 +219,47 @@ fn query_arithmetic_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Functio
     query_arithmetic_boolean_from_pairs(pair.into_inner())
 }
 
-fn query_function_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
-    let name = pairs.next().ok_or(TOKEN_ERR)?.as_span().as_str();
-    let signature = FunctionSignature::from_str(name)?;
-    let arguments = function_arguments_from_pairs(pairs, &signature)?;
+fn query_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
+    let span = pair.as_span();
+    let mut pairs = pair.into_inner();
+    let name = pairs.next().ok_or(TOKEN_ERR)?;
+    let signature =
+        FunctionSignature::from_str(name.as_str()).map_err(|err| error_at(name.as_span(), err))?;
+
+    let mut constants = Vec::new();
+    let arguments = function_arguments_from_pairs(pairs, &signature, &span, &mut constants)?;
+    let function = signature
+        .into_boxed_function(arguments)
+        .map_err(|err| error_at(span, err))?;
+
+    // Arguments known before any event is mapped are checked once the function has
+    // taken its arguments, which reports those given the wrong kind of literal first.
+    for (span, value, parameter) in constants {
+        if !(parameter.accepts)(&value) {
+            return Err(error_at(
+                span,
+                format!(
+                    "invalid argument type '{}' for parameter '{}' of function '{}'",
+                    value.kind(),
+                    parameter.keyword,
+                    signature.as_str()
+                ),
+            ));
+        }
+    }
 
-    signature.into_boxed_function(arguments)
+    Ok(function)
 }
 
-fn function_arguments_from_pairs(
-    mut pairs: Pairs<Rule>,
+/// A constant argument, along with where it was given and the parameter it was given
+/// for.
+type Constant<'i> = (Span<'i>, Value, Parameter);
+
+fn function_arguments_from_pairs<'i>(
+    mut pairs: Pairs<'i, Rule>,
     signature: &FunctionSignature,
+    span: &Span,
+    constants: &mut Vec<Constant<'i>>,
 ) -> Result<ArgumentList> {
     let mut arguments = ArgumentList::new();
 
@@ -230,9 +277,11 @@ fn function_arguments_from_pairs(
             .map(|pair| match pair.as_rule() {
                 Rule::positional_item => {
                     index += 1;
-                    positional_item_from_pair(pair, &mut arguments, index - 1, signature)
+                    positional_item_from_pair(pair, &mut arguments, index - 1, signature, constants)
+                }
+                Rule::keyword_item => {
+                    keyword_item_from_pair(pair, &mut arguments, signature, constants)
                 }
-                Rule::keyword_item => keyword_item_from_pair(pair, &mut arguments, signature),
                 _ => unexpected_parser_sytax!(pair),
             })
             .collect::<Result<()>>()?;
@@ -240,11 +289,14 @@ fn function_arguments_from_pairs(
 
     // check invalid arity
     if arguments.len() > signature.parameters().len() {
-        return Err(format!(
-            "invalid number of function arguments (got {}, expected {}) for function '{}'",
-            arguments.len(),
-            signature.parameters().len(),
-            signature.as_str(),
+        return Err(error_at(
+            span.clone(),
+            format!(
+                "invalid number of function arguments (got {}, expected {}) for function '{}'",
+                arguments.len(),
+                signature.parameters().len(),
+                signature.as_str(),
+            ),
         ));
     }
 
@@ -255,24 +307,13 @@ fn function_arguments_from_pairs(
         .filter(|p| p.required)
         .filter(|p| !arguments.keywords().contains(&p.keyword))
         .map(|p| {
-            Err(format!(
-                "required argument '{}' missing for function '{}'",
-                p.keyword,
-                signature.as_str()
-            ))
-        })
-        .collect::<Result<_>>()?;
-
-    // check unknown argument keywords
-    arguments
-        .keywords()
-        .iter()
-        .filter(|k| !signature.parameters().iter().any(|p| &p.keyword == *k))
-        .map(|k| {
-            Err(format!(
-                "unknown argument keyword '{}' for function '{}'",
-                k,
-                signature.as_str()
+            Err(error_at(
+                span.clone(),
+                format!(
+                    "required argument '{}' missing for function '{}'",
+                    p.keyword,
+                    signature.as_str()
+                ),
             ))
         })
         .collect::<Result<_>>()?;
@@ -280,55 +321,141 @@ fn function_arguments_from_pairs(
     Ok(arguments)
 }
 
-fn positional_item_from_pair(
-    pair: Pair<Rule>,
+fn positional_item_from_pair<'i>(
+    pair: Pair<'i, Rule>,
     list: &mut ArgumentList,
     index: usize,
     signature: &FunctionSignature,
+    constants: &mut Vec<Constant<'i>>,
 ) -> Result<()> {
-    let resolver = query_arithmetic_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?;
-
-    let parameter = signature.parameters().get(index).cloned().ok_or(format!(
-        "unknown positional argument '{}' for function: '{}'",
-        index,
-        signature.as_str()
-    ))?;
+    let span = pair.as_span();
+    let parameter = signature.parameters().get(index).cloned().ok_or_else(|| {
+        error_at(
+            span.clone(),
+            format!(
+                "unknown positional argument '{}' for function '{}'",
+                index,
+                signature.as_str()
+            ),
+        )
+    })?;
 
     let keyword = parameter.keyword.to_owned();
-    let argument = Argument::new(resolver, parameter);
+    if list.keywords().contains(&parameter.keyword) {
+        return Err(duplicate_argument(span, &keyword, signature));
+    }
+
+    let argument = argument_from_pair(
+        pair.into_inner().next().ok_or(TOKEN_ERR)?,
+        parameter,
+        constants,
+    )?;
 
     list.push(argument, Some(keyword));
 
     Ok(())
 }
 
-fn keyword_item_from_pair(
-    pair: Pair<Rule>,
+fn keyword_item_from_pair<'i>(
+    pair: Pair<'i, Rule>,
     list: &mut ArgumentList,
     signature: &FunctionSignature,
+    constants: &mut Vec<Constant<'i>>,
 ) -> Result<()> {
     let mut pairs = pair.into_inner();
-    let keyword = pairs.next().ok_or(TOKEN_ERR)?.as_span().as_str();
-    let resolver = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    let keyword = pairs.next().ok_or(TOKEN_ERR)?;
 
     let parameter = signature
         .parameters()
         .iter()
-        .find(|p| p.keyword == keyword)
-        .ok_or(format!(
-            "unknown argument keyword '{}' for function '{}'",
-            keyword,
-            signature.as_str()
-        ))?
+        .find(|p| p.keyword == keyword.as_str())
+        .ok_or_else(|| {
+            error_at(
+                keyword.as_span(),
+                format!(
+                    "unknown argument keyword '{}' for function '{}'",
+                    keyword.as_str(),
+                    signature.as_str()
+                ),
+            )
+        })?
         .clone();
+    if list.keywords().contains(&parameter.keyword) {
+        return Err(duplicate_argument(
+            keyword.as_span(),
+            keyword.as_str(),
+            signature,
+        ));
+    }
 
-    let argument = Argument::new(resolver, parameter);
+    let argument = argument_from_pair(pairs.next().ok_or(TOKEN_ERR)?, parameter, constants)?;
 
-    list.push(argument, Some(keyword.to_owned()));
+    list.push(argument, Some(keyword.as_str().to_owned()));
 
     Ok(())
 }
 
+fn duplicate_argument(span: Span, keyword: &str, signature: &FunctionSignature) -> String {
+    error_at(
+        span,
+        format!(
+            "argument '{}' given more than once for function '{}'",
+            keyword,
+            signature.as_str()
+        ),
+    )
+}
+
+/// Builds the argument given for a parameter. Arguments which don't depend on the
+/// event, e.g. literals, are evaluated right away so that their type can be checked
+/// before any event is mapped.
+fn argument_from_pair<'i>(
+    pair: Pair<'i, Rule>,
+    parameter: Parameter,
+    constants: &mut Vec<Constant<'i>>,
+) -> Result<Argument> {
+    let span = pair.as_span();
+    let constant = pair.clone().into_inner().flatten().all(|pair| {
+        !matches!(
+            pair.as_rule(),
+            Rule::dot_path | Rule::variable | Rule::query_function
+        )
+    });
+
+    let resolver = resolver_from_pair(pair)?;
+    if let (true, Resolver::Query(query)) = (constant, &resolver) {
+        let value = query
+            .execute(&Event::new_empty_log())
+            .map_err(|err| error_at(span.clone(), err))?;
+        constants.push((span, value, parameter.clone()));
+    }
+
+    Ok(Argument::new(resolver, parameter))
+}
+
+fn resolver_from_pair(pair: Pair<Rule>) -> Result<Resolver> {
+    match pair.as_rule() {
+        Rule::regex => Ok(Resolver::Regex(regex_from_pair(pair)?)),
+        _ => Ok(Resolver::Query(query_arithmetic_from_pair(pair)?)),
+    }
+}
+
+fn regex_from_pair(pair: Pair<Rule>) -> Result<Regex> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    // Slashes are the only characters escaped for the sake of the literal
+    // itself, any other escape sequence belongs to the regex.
+    let pattern = inner.next().ok_or(TOKEN_ERR)?.as_str().replace("\\/", "/");
+    let flags = inner.next().ok_or(TOKEN_ERR)?.as_str();
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .ignore_whitespace(flags.contains('x'))
+        .build()
+        .map_err(|err| error_at(span, format!("invalid regex /{}/: {}", pattern, err)))
+}
+
 fn inner_quoted_string_escaped_from_pair(pair: Pair<Rule>) -> Result<String> {
     // This is only executed once per string at parse time, and so I'm not
     // losing sleep over the reallocation. However, if we want to mutate the
@@ -365,9 +492,19 @@ fn inner_quoted_string_escaped_from_pair(pair: Pair<Rule>) -> Result<String> {
 fn query_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
     Ok(match pair.as_rule() {
         Rule::not_operator => {
-            let inner_query = query_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?;
+            let inner_query = indexed_query_from_pairs(&mut pair.into_inner().peekable())?;
             Box::new(NotFn::new(inner_query))
         }
+        Rule::array => Box::new(Array::new(
+            pair.into_inner()
+                .map(query_arithmetic_from_pair)
+                .collect::<Result<_>>()?,
+        )),
+        Rule::object => Box::new(Object::new(
+            pair.into_inner()
+                .map(object_field_from_pair)
+                .collect::<Result<_>>()?,
+        )),
         Rule::string => Box::new(Literal::from(Value::from(
             inner_quoted_string_escaped_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?,
         ))),
@@ -383,23 +520,58 @@ fn query_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
             Box::new(Literal::from(Value::from(v)))
         }
         Rule::dot_path => Box::new(QueryPath::from(path_segments_from_pair(pair)?)),
+        Rule::variable => Box::new(Variable::new(variable_name_from_pair(pair))),
         Rule::group => query_arithmetic_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?,
-        Rule::query_function => query_function_from_pairs(pair.into_inner())?,
+        Rule::query_function => query_function_from_pair(pair)?,
         _ => unexpected_parser_sytax!(pair),
     })
 }
 
+fn object_field_from_pair(pair: Pair<Rule>) -> Result<(String, Box<dyn query::Function>)> {
+    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let key = inner_quoted_string_escaped_from_pair(first.into_inner().next().ok_or(TOKEN_ERR)?)?;
+    let value = query_arithmetic_from_pair(other.next().ok_or(TOKEN_ERR)?)?;
+
+    Ok((key, value))
+}
+
+fn variable_name_from_pair(pair: Pair<Rule>) -> String {
+    pair.as_str().trim_start_matches('$').to_owned()
+}
+
 fn if_statement_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn Function>> {
-    let query = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    let mut branches = Vec::new();
+    let mut otherwise: Box<dyn Function> = Box::new(Noop {});
 
-    let first = statement_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    while let Some(pair) = pairs.next() {
+        match pair.as_rule() {
+            // Only the trailing `else` block isn't preceded by a query.
+            Rule::block => otherwise = block_from_pair(pair)?,
+            _ => {
+                let query = query_arithmetic_from_pair(pair)?;
+                let block = block_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+                branches.push((query, block));
+            }
+        }
+    }
 
-    let second = match pairs.next() {
-        Some(pair) => statement_from_pair(pair)?,
-        None => Box::new(Noop {}),
-    };
+    // `else if` chains are nested if statements in the `else` branch of the
+    // preceding one.
+    Ok(branches
+        .into_iter()
+        .rev()
+        .fold(otherwise, |otherwise, (query, block)| {
+            Box::new(IfStatement::new(query, block, otherwise))
+        }))
+}
 
-    Ok(Box::new(IfStatement::new(query, first, second)))
+fn block_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let statements = pair
+        .into_inner()
+        .map(statement_from_pair)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Box::new(Block::new(statements)))
 }
 
 fn merge_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
@@ -419,6 +591,7 @@ fn function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
         Rule::deletion => Ok(Box::new(Deletion::new(paths_from_pair(pair)?))),
         Rule::only_fields => Ok(Box::new(OnlyFields::new(paths_from_pair(pair)?))),
         Rule::merge => merge_function_from_pair(pair),
+        Rule::drop => Ok(Box::new(DropEvent {})),
         _ => unexpected_parser_sytax!(pair),
     }
 }
@@ -437,6 +610,12 @@ fn statement_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
             let query = query_arithmetic_from_pair(inner_rules.next().ok_or(TOKEN_ERR)?)?;
             Ok(Box::new(Assignment::new(path, query)))
         }
+        Rule::variable_assignment => {
+            let mut inner_rules = pair.into_inner();
+            let name = variable_name_from_pair(inner_rules.next().ok_or(TOKEN_ERR)?);
+            let query = query_arithmetic_from_pair(inner_rules.next().ok_or(TOKEN_ERR)?)?;
+            Ok(Box::new(VariableAssignment::new(name, query)))
+        }
         Rule::function => function_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?),
         Rule::if_statement => if_statement_from_pairs(pair.into_inner()),
         _ => unexpected_parser_sytax!(pair),
@@ -455,7 +634,7 @@ fn mapping_from_pairs(pairs: Pairs<Rule>) -> Result<Mapping> {
     for pair in pairs {
         match pair.as_rule() {
             // Rules expected at the root of a mapping statement.
-            Rule::assignment | Rule::function | Rule::if_statement => {
+            Rule::assignment | Rule::variable_assignment | Rule::function | Rule::if_statement => {
                 assignments.push(statement_from_pair(pair)?);
             }
             Rule::EOI => (),
@@ -465,63 +644,84 @@ fn mapping_from_pairs(pairs: Pairs<Rule>) -> Result<Mapping> {
     Ok(Mapping::new(assignments))
 }
 
+/// Formats an error found in a mapping which is syntactically valid just like syntax
+/// errors are, pointing at the part of the mapping at fault.
+fn error_at(span: Span, message: impl Into<String>) -> String {
+    let err = pest::error::Error::<Rule>::new_from_span(
+        ErrorVariant::CustomError {
+            message: message.into(),
+        },
+        span,
+    );
+    format!("mapping parse error\n{}", err)
+}
+
 pub fn parse(input: &str) -> Result<Mapping> {
-    match MappingParser::parse(Rule::mapping, input) {
-        Ok(a) => mapping_from_pairs(a),
-        // We need to do a bit of manual pruning of the error here as any
-        // non-silent rule will be included in the list of candidates for a
-        // parse error. Since we have several different sets of arithmetic
-        // operator rules we first remove all but one type and then we rename it
-        // to a more general 'operator' rule.
-        Err(mut err) => {
-            if let ErrorVariant::ParsingError {
-                ref mut positives,
-                negatives: _,
-            } = err.variant
-            {
-                let mut i = 0;
-                while i != positives.len() {
-                    match positives[i] {
-                        Rule::arithmetic_operator_boolean
-                        | Rule::arithmetic_operator_compare
-                        | Rule::arithmetic_operator_sum => {
-                            positives.remove(i);
-                        }
-                        _ => {
-                            i += 1;
-                        }
-                    };
+    MappingParser::parse(Rule::mapping, input)
+        .map_err(syntax_error)
+        .and_then(mapping_from_pairs)
+}
+
+/// Parses a single query, which events are then checked against.
+pub fn parse_predicate(input: &str) -> Result<Predicate> {
+    let mut pairs = MappingParser::parse(Rule::predicate, input).map_err(syntax_error)?;
+    let query = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    Ok(Predicate::new(query))
+}
+
+fn syntax_error(mut err: pest::error::Error<Rule>) -> String {
+    // We need to do a bit of manual pruning of the error here as any
+    // non-silent rule will be included in the list of candidates for a
+    // parse error. Since we have several different sets of arithmetic
+    // operator rules we first remove all but one type and then we rename it
+    // to a more general 'operator' rule.
+    if let ErrorVariant::ParsingError {
+        ref mut positives,
+        negatives: _,
+    } = err.variant
+    {
+        let mut i = 0;
+        while i != positives.len() {
+            match positives[i] {
+                Rule::arithmetic_operator_boolean
+                | Rule::arithmetic_operator_compare
+                | Rule::arithmetic_operator_sum => {
+                    positives.remove(i);
                 }
-            }
-            err = err.renamed_rules(|rule| match *rule {
-                Rule::arithmetic_operator_product => "operator".to_owned(),
-                _ => format!("{:?}", rule),
-            });
-            Err(format!("mapping parse error\n{}", err))
+                _ => {
+                    i += 1;
+                }
+            };
         }
     }
+    err = err.renamed_rules(|rule| match *rule {
+        Rule::arithmetic_operator_product => "operator".to_owned(),
+        _ => format!("{:?}", rule),
+    });
+    format!("mapping parse error\n{}", err)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::mapping::query::function::{
-        ContainsFn, DowncaseFn, FormatTimestampFn, Md5Fn, NowFn, ParseJsonFn, ParseTimestampFn,
-        Sha1Fn, SliceFn, StripWhitespaceFn, ToBooleanFn, ToFloatFn, ToIntegerFn, ToStringFn,
-        ToTimestampFn, TokenizeFn, TruncateFn, UpcaseFn, UuidV4Fn,
+        ContainsFn, DowncaseFn, FormatTimestampFn, LengthFn, MatchFn, Md5Fn, NowFn, ParseJsonFn,
+        ParseRegexFn, ParseTimestampFn, PushFn, ReplaceFn, Sha1Fn, SliceFn, SplitFn,
+        StripWhitespaceFn, ToBooleanFn, ToFloatFn, ToIntegerFn, ToStringFn, ToTimestampFn,
+        TokenizeFn, TruncateFn, UpcaseFn, UuidV4Fn,
     };
 
     #[test]
     fn check_parser_errors() {
         let cases = vec![
-            (".foo = {\"bar\"}", vec![" 1:8\n", "= expected query"]),
+            (".foo = {\"bar\"}", vec![" 1:9\n", "= expected object_field"]),
             (
                 ". = \"bar\"",
                 vec![" 1:2\n", "= expected path_segment or quoted_path_segment"],
             ),
             (
                 ".foo = !",
-                vec![" 1:9\n", "= expected dot_path, ident, group, boolean, null, string, integer, float, or not_operator"],
+                vec![" 1:9\n", "= expected variable, dot_path, ident, group, array, object, boolean, null, string, integer, float, or not_operator"],
             ),
             (
                 ".foo = to_string",
@@ -531,7 +731,7 @@ mod tests {
                 "foo = \"bar\"",
                 vec![
                     " 1:1\n",
-                    "= expected if_statement, target_path, or function",
+                    "= expected if_statement, target_path, variable, or function",
                 ],
             ),
             (
@@ -545,24 +745,29 @@ mod tests {
             ),
             (
                 "if .foo > 0 { .foo = \"bar\" } else",
-                vec![" 1:30\n", "= expected EOI"],
+                vec![" 1:34\n", "= expected block"],
             ),
             (
                 r#"if .foo { }"#,
                 vec![
                     " 1:11\n",
-                    "= expected if_statement, target_path, or function",
+                    "= expected if_statement, target_path, variable, or function",
                 ],
             ),
             (
+                // The block is taken for an object.
                 r#"if { del(.foo) } else { del(.bar) }"#,
-                vec![" 1:4\n", "= expected query"],
+                vec![" 1:6\n", "= expected string"],
             ),
             (
                 r#"if .foo > .bar { del(.foo) } else { .bar = .baz"#,
                 // This message isn't great, ideally I'd like "expected closing bracket"
                 vec![" 1:48\n", "= expected path_index or operator"],
             ),
+            (
+                "$ = .foo",
+                vec![" 1:1\n", "= expected if_statement, target_path, variable, or function"],
+            ),
             (
                 r#"only_fields(.foo,)"#,
                 vec![" 1:18\n", "= expected target_path"],
@@ -579,6 +784,68 @@ mod tests {
                 ".foo = to_string(\"bar\",)",
                 vec![" 1:24\n", "= expected argument"],
             ),
+            (
+                r#".foo = match(.foo, "bar")"#,
+                vec!["argument 'pattern' must be a regex literal"],
+            ),
+            (
+                r#".foo = match(.foo, /(/)"#,
+                vec![" 1:20\n", "invalid regex /(/"],
+            ),
+            (
+                ".foo = upcsae(.foo)",
+                vec![" 1:8\n", "= unknown function 'upcsae'"],
+            ),
+            (
+                ".foo = upcase(.foo, .bar)",
+                vec![
+                    " 1:21\n",
+                    "= unknown positional argument '1' for function 'upcase'",
+                ],
+            ),
+            (
+                ".foo = slice(.foo, end = 2)",
+                vec![" 1:8\n", "= required argument 'start' missing for function 'slice'"],
+            ),
+            (
+                ".foo = truncate(.foo, limit = 5, elipsis = true)",
+                vec![
+                    " 1:34\n",
+                    "= unknown argument keyword 'elipsis' for function 'truncate'",
+                ],
+            ),
+            (
+                ".foo = truncate(.foo, 5, limit = 3)",
+                vec![
+                    " 1:26\n",
+                    "= argument 'limit' given more than once for function 'truncate'",
+                ],
+            ),
+            (
+                ".foo = truncate(limit = 3, .foo, 5)",
+                vec![
+                    " 1:34\n",
+                    "= argument 'limit' given more than once for function 'truncate'",
+                ],
+            ),
+            (
+                r#".foo = format_timestamp("500", "%s")"#,
+                vec![
+                    " 1:25\n",
+                    "= invalid argument type 'string' for parameter 'value' of function 'format_timestamp'",
+                ],
+            ),
+            (
+                "if .foo {\n  .bar = slice(.bar, \"1\")\n}",
+                vec![
+                    " 2:22\n",
+                    "= invalid argument type 'string' for parameter 'start' of function 'slice'",
+                ],
+            ),
+            (
+                ".foo = to_int(.foo, 1 + \"a\")",
+                vec![" 1:21\n", "unable to add right-hand field type"],
+            ),
             (
                 // Due to the explicit list of allowed escape chars our grammar
                 // doesn't actually recognize this as a string literal.
@@ -885,11 +1152,13 @@ mod tests {
                         Box::new(Literal::from(Value::from(5))),
                         Operator::Equal,
                     )),
-                    Box::new(Assignment::new(
+                    Box::new(Block::new(vec![Box::new(Assignment::new(
                         "foo".to_string(),
                         Box::new(QueryPath::from("bar")),
-                    )),
-                    Box::new(Deletion::new(vec!["buz".to_string()])),
+                    ))])),
+                    Box::new(Block::new(vec![Box::new(Deletion::new(vec![
+                        "buz".to_string()
+                    ]))])),
                 ))]),
             ),
             (
@@ -900,13 +1169,88 @@ mod tests {
                         Box::new(QueryPath::from("buz")),
                         Operator::Greater,
                     )),
-                    Box::new(Assignment::new(
+                    Box::new(Block::new(vec![Box::new(Assignment::new(
                         "thing".to_string(),
                         Box::new(QueryPath::from("foo")),
-                    )),
+                    ))])),
                     Box::new(Noop {}),
                 ))]),
             ),
+            (
+                r#"if .foo == 5 {
+                    .foo = .bar
+                    del(.bar)
+                  } else if .foo == 6 {
+                    del(.foo)
+                  } else {
+                    del(.buz)
+                  }"#,
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("foo")),
+                        Box::new(Literal::from(Value::from(5))),
+                        Operator::Equal,
+                    )),
+                    Box::new(Block::new(vec![
+                        Box::new(Assignment::new(
+                            "foo".to_string(),
+                            Box::new(QueryPath::from("bar")),
+                        )),
+                        Box::new(Deletion::new(vec!["bar".to_string()])),
+                    ])),
+                    Box::new(IfStatement::new(
+                        Box::new(Arithmetic::new(
+                            Box::new(QueryPath::from("foo")),
+                            Box::new(Literal::from(Value::from(6))),
+                            Operator::Equal,
+                        )),
+                        Box::new(Block::new(vec![Box::new(Deletion::new(vec![
+                            "foo".to_string()
+                        ]))])),
+                        Box::new(Block::new(vec![Box::new(Deletion::new(vec![
+                            "buz".to_string()
+                        ]))])),
+                    )),
+                ))]),
+            ),
+            (
+                "if .foo { del(.foo) } else if .bar { del(.bar) }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(QueryPath::from("foo")),
+                    Box::new(Block::new(vec![Box::new(Deletion::new(vec![
+                        "foo".to_string()
+                    ]))])),
+                    Box::new(IfStatement::new(
+                        Box::new(QueryPath::from("bar")),
+                        Box::new(Block::new(vec![Box::new(Deletion::new(vec![
+                            "bar".to_string()
+                        ]))])),
+                        Box::new(Noop {}),
+                    )),
+                ))]),
+            ),
+            // variables
+            (
+                "$tmp = .foo + 1\n.bar = $tmp * $tmp",
+                Mapping::new(vec![
+                    Box::new(VariableAssignment::new(
+                        "tmp".to_string(),
+                        Box::new(Arithmetic::new(
+                            Box::new(QueryPath::from("foo")),
+                            Box::new(Literal::from(Value::from(1))),
+                            Operator::Add,
+                        )),
+                    )),
+                    Box::new(Assignment::new(
+                        "bar".to_string(),
+                        Box::new(Arithmetic::new(
+                            Box::new(Variable::new("tmp".to_string())),
+                            Box::new(Variable::new("tmp".to_string())),
+                            Operator::Multiply,
+                        )),
+                    )),
+                ]),
+            ),
             // function: only_fields
             (
                 "only_fields(.foo)",
@@ -919,6 +1263,19 @@ mod tests {
                     "baz".to_string(),
                 ]))]),
             ),
+            // function: drop
+            (
+                "if .foo == \"bar\" { drop() }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("foo")),
+                        Box::new(Literal::from(Value::from("bar"))),
+                        Operator::Equal,
+                    )),
+                    Box::new(Block::new(vec![Box::new(DropEvent {})])),
+                    Box::new(Noop {}),
+                ))]),
+            ),
             // function: to_string
             (
                 ".foo = to_string(.foo, \"bar\")",
@@ -1146,11 +1503,11 @@ mod tests {
                 ))]),
             ),
             (
-                r#".foo = format_timestamp("500", "%s")"#,
+                r#".foo = format_timestamp(.foo, "%s")"#,
                 Mapping::new(vec![Box::new(Assignment::new(
                     "foo".to_string(),
                     Box::new(FormatTimestampFn::new(
-                        Box::new(Literal::from(Value::from("500"))),
+                        Box::new(QueryPath::from("foo")),
                         "%s",
                     )),
                 ))]),
@@ -1180,6 +1537,132 @@ mod tests {
                     Box::new(TokenizeFn::new(Box::new(QueryPath::from("foo")))),
                 ))]),
             ),
+            // arrays, objects and indexes
+            (
+                r#".foo = [1, .bar, "baz",]"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Array::new(vec![
+                        Box::new(Literal::from(Value::from(1))),
+                        Box::new(QueryPath::from("bar")),
+                        Box::new(Literal::from(Value::from("baz"))),
+                    ])),
+                ))]),
+            ),
+            (
+                ".foo = []",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Array::new(vec![])),
+                ))]),
+            ),
+            (
+                r#".foo = {
+                    "bar": 1,
+                    "baz": [.qux]
+                }"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Object::new(vec![
+                        ("bar".to_string(), Box::new(Literal::from(Value::from(1)))),
+                        (
+                            "baz".to_string(),
+                            Box::new(Array::new(vec![Box::new(QueryPath::from("qux"))])),
+                        ),
+                    ])),
+                ))]),
+            ),
+            (
+                ".foo = .bar[0] + .bar[-1]",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("bar[0]")),
+                        Box::new(Index::new(
+                            Box::new(QueryPath::from("bar")),
+                            Box::new(Literal::from(Value::from(-1))),
+                        )),
+                        Operator::Add,
+                    )),
+                ))]),
+            ),
+            (
+                r#".foo = $bar[$i + 1]["baz"] * 2"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Arithmetic::new(
+                        Box::new(Index::new(
+                            Box::new(Index::new(
+                                Box::new(Variable::new("bar".to_string())),
+                                Box::new(Arithmetic::new(
+                                    Box::new(Variable::new("i".to_string())),
+                                    Box::new(Literal::from(Value::from(1))),
+                                    Operator::Add,
+                                )),
+                            )),
+                            Box::new(Literal::from(Value::from("baz"))),
+                        )),
+                        Box::new(Literal::from(Value::from(2))),
+                        Operator::Multiply,
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = !.bar[-1]",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(NotFn::new(Box::new(Index::new(
+                        Box::new(QueryPath::from("bar")),
+                        Box::new(Literal::from(Value::from(-1))),
+                    )))),
+                ))]),
+            ),
+            (
+                ".foo = length(push(.bar, 1))",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(LengthFn::new(Box::new(PushFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Value::from(1),
+                    )))),
+                ))]),
+            ),
+            (
+                ".foo = match(.foo, /^bar/i)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(MatchFn::new(Box::new(QueryPath::from("foo")), "^bar")),
+                ))]),
+            ),
+            (
+                r#".foo = replace(.foo, pattern = /\s+/, with = " ", count = 2)"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ReplaceFn::new(
+                        Box::new(QueryPath::from("foo")),
+                        r"\s+",
+                        " ",
+                        Some(2),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = split(.foo, /,/)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(SplitFn::new(Box::new(QueryPath::from("foo")), ",", None)),
+                ))]),
+            ),
+            (
+                r#".foo = parse_regex(.foo, /^(?P<a>\w+)\/(?P<b>\w+)$/)"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ParseRegexFn::new(
+                        Box::new(QueryPath::from("foo")),
+                        r"^(?P<a>\w+)/(?P<b>\w+)$",
+                    )),
+                ))]),
+            ),
         ];
 
         for (mapping, exp) in cases {
@@ -1,8 +1,5 @@
-use super::Function;
-use crate::{
-    event::{Event, Value},
-    mapping::Result,
-};
+use super::{Context, Function};
+use crate::{event::Value, mapping::Result};
 use bytes::BytesMut;
 
 #[derive(Debug, Clone)]
@@ -71,7 +68,7 @@ fn compare_number_types(
 }
 
 impl Function for Arithmetic {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let left = self.left.execute(ctx);
         let right = self.right.execute(ctx);
 
@@ -218,7 +215,10 @@ impl Function for Arithmetic {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mapping::query::{path::Path, Literal};
+    use crate::{
+        event::Event,
+        mapping::query::{path::Path, Literal},
+    };
 
     #[test]
     fn check_compare_query() {
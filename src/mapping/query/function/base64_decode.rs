@@ -0,0 +1,105 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Base64DecodeFn {
+    query: Box<dyn Function>,
+    charset: Option<Box<dyn Function>>,
+}
+
+impl Base64DecodeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, charset: Option<&str>) -> Self {
+        let charset = charset.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self { query, charset }
+    }
+}
+
+impl Function for Base64DecodeFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+        let charset = optional!(ctx, self.charset, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned());
+
+        // Both padded and unpadded values are decoded.
+        let config = match charset.as_deref().unwrap_or("standard") {
+            "standard" => base64::STANDARD,
+            "url_safe" => base64::URL_SAFE,
+            _ => return Err("'charset' must be one of standard, url_safe".to_owned()),
+        };
+
+        base64::decode_config(&bytes, config)
+            .map(Value::from)
+            .map_err(|err| format!("unable to decode base64: {}", err))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "charset",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for Base64DecodeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let charset = arguments.optional("charset");
+
+        Ok(Self { query, charset })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_decode() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from("some?data?>")),
+                Base64DecodeFn::new(
+                    Box::new(Literal::from(Value::from("c29tZT9kYXRhPz4="))),
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("some?data?>")),
+                Base64DecodeFn::new(
+                    Box::new(Literal::from(Value::from("c29tZT9kYXRhPz4"))),
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("some?data?>")),
+                Base64DecodeFn::new(
+                    Box::new(Literal::from(Value::from("c29tZT9kYXRhPz4"))),
+                    Some("url_safe"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to decode base64: Invalid byte 33, offset 3.".to_owned()),
+                Base64DecodeFn::new(Box::new(Literal::from(Value::from("foo!"))), None),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
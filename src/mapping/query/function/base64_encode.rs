@@ -0,0 +1,132 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Base64EncodeFn {
+    query: Box<dyn Function>,
+    padding: Option<Box<dyn Function>>,
+    charset: Option<Box<dyn Function>>,
+}
+
+impl Base64EncodeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        query: Box<dyn Function>,
+        padding: Option<bool>,
+        charset: Option<&str>,
+    ) -> Self {
+        let padding = padding.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+        let charset = charset.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self {
+            query,
+            padding,
+            charset,
+        }
+    }
+}
+
+impl Function for Base64EncodeFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+        let padding = optional!(ctx, self.padding, Value::Boolean(v) => v).unwrap_or(true);
+        let charset = optional!(ctx, self.charset, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned());
+
+        let config = match (charset.as_deref().unwrap_or("standard"), padding) {
+            ("standard", true) => base64::STANDARD,
+            ("standard", false) => base64::STANDARD_NO_PAD,
+            ("url_safe", true) => base64::URL_SAFE,
+            ("url_safe", false) => base64::URL_SAFE_NO_PAD,
+            _ => return Err("'charset' must be one of standard, url_safe".to_owned()),
+        };
+
+        Ok(Value::from(base64::encode_config(&bytes, config)))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "padding",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "charset",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for Base64EncodeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let padding = arguments.optional("padding");
+        let charset = arguments.optional("charset");
+
+        Ok(Self {
+            query,
+            padding,
+            charset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_encode() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from("c29tZT9kYXRh")),
+                Base64EncodeFn::new(
+                    Box::new(Literal::from(Value::from("some?data"))),
+                    None,
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("c29tZT9kYXRhPz4=")),
+                Base64EncodeFn::new(
+                    Box::new(Literal::from(Value::from("some?data?>"))),
+                    None,
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("c29tZT9kYXRhPz4")),
+                Base64EncodeFn::new(
+                    Box::new(Literal::from(Value::from("some?data?>"))),
+                    Some(false),
+                    Some("url_safe"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'charset' must be one of standard, url_safe".to_owned()),
+                Base64EncodeFn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    None,
+                    Some("hex"),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
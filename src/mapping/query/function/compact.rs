@@ -0,0 +1,112 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct CompactFn {
+    query: Box<dyn Function>,
+}
+
+impl CompactFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for CompactFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        required! {
+            ctx, self.query,
+            v @ Value::Array(_) => Ok(compact(v)),
+            v @ Value::Map(_) => Ok(compact(v)),
+        }
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for CompactFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+/// Removes nulls, along with empty strings, arrays and maps, from nested arrays
+/// and maps.
+fn compact(value: Value) -> Value {
+    match value {
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(compact)
+                .filter(|value| !is_empty(value))
+                .collect(),
+        ),
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(key, value)| (key, compact(value)))
+                .filter(|(_, value)| !is_empty(value))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bytes(v) => v.is_empty(),
+        Value::Array(v) => v.is_empty(),
+        Value::Map(v) => v.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn compact() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(json!([1, "foo", [2]]))),
+                CompactFn::new(Box::new(Literal::from(Value::from(json!([
+                    1,
+                    null,
+                    "",
+                    "foo",
+                    [],
+                    [2, null],
+                    {},
+                    { "bar": null }
+                ]))))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(json!({"foo": 1, "baz": {"qux": false}}))),
+                CompactFn::new(Box::new(Literal::from(Value::from(json!({
+                    "foo": 1,
+                    "bar": null,
+                    "baz": {"qux": false, "quux": ""},
+                    "corge": [],
+                }))))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -26,7 +26,7 @@ impl ContainsFn {
 }
 
 impl Function for ContainsFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let substring = {
             let bytes = required!(ctx, self.substring, Value::Bytes(v) => v);
             String::from_utf8_lossy(&bytes).into_owned()
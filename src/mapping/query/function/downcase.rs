@@ -13,7 +13,7 @@ impl DowncaseFn {
 }
 
 impl Function for DowncaseFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx)? {
             Value::Bytes(bytes) => Ok(Value::Bytes(
                 String::from_utf8_lossy(&bytes).to_lowercase().into(),
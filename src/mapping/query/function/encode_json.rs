@@ -0,0 +1,81 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct EncodeJsonFn {
+    query: Box<dyn Function>,
+}
+
+impl EncodeJsonFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for EncodeJsonFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = self.query.execute(ctx)?;
+
+        serde_json::to_string(&value)
+            .map(Value::from)
+            .map_err(|err| format!("unable to encode json: {}", err))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |_| true,
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for EncodeJsonFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn encode_json() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(r#""foo""#)),
+                EncodeJsonFn::new(Box::new(Literal::from(Value::from("foo")))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("[1,true,null]")),
+                EncodeJsonFn::new(Box::new(Literal::from(Value::from(vec![
+                    Value::from(1),
+                    Value::from(true),
+                    Value::Null,
+                ])))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo.bar", Value::from(1.5));
+                    event.as_mut_log().insert("foo.baz", Value::from("qux"));
+                    event
+                },
+                Ok(Value::from(r#"{"bar":1.5,"baz":"qux"}"#)),
+                EncodeJsonFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
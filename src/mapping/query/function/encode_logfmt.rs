@@ -0,0 +1,169 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct EncodeLogfmtFn {
+    query: Box<dyn Function>,
+    fields_ordering: Option<Box<dyn Function>>,
+}
+
+impl EncodeLogfmtFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, fields_ordering: Vec<&str>) -> Self {
+        let fields_ordering = if fields_ordering.is_empty() {
+            None
+        } else {
+            let fields = fields_ordering
+                .into_iter()
+                .map(Value::from)
+                .collect::<Vec<_>>();
+            Some(Box::new(Literal::from(Value::from(fields))) as _)
+        };
+
+        Self {
+            query,
+            fields_ordering,
+        }
+    }
+}
+
+impl Function for EncodeLogfmtFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let map = required!(ctx, self.query, Value::Map(v) => v);
+
+        let mut fields = BTreeMap::new();
+        flatten("", map, &mut fields);
+
+        // Fields listed in `fields_ordering` come first, the rest follow in
+        // alphabetical order.
+        let ordering = optional!(ctx, self.fields_ordering, Value::Array(v) => v);
+        let mut pairs = Vec::with_capacity(fields.len());
+        for field in ordering.unwrap_or_default() {
+            let key = match field {
+                Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                v => return Err(format!(r#"unexpected field name type: "{}""#, v.kind())),
+            };
+            if let Some(value) = fields.remove(&key) {
+                pairs.push((key, value));
+            }
+        }
+        pairs.extend(fields);
+
+        let encoded = pairs
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, encode_value(&value)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Ok(Value::from(encoded))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "fields_ordering",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for EncodeLogfmtFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let fields_ordering = arguments.optional("fields_ordering");
+
+        Ok(Self {
+            query,
+            fields_ordering,
+        })
+    }
+}
+
+/// Nested maps are flattened into dotted keys, so that `{"a": {"b": 1}}`
+/// becomes `a.b=1`.
+fn flatten(prefix: &str, map: BTreeMap<String, Value>, fields: &mut BTreeMap<String, Value>) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match value {
+            Value::Map(map) => flatten(&key, map, fields),
+            value => {
+                fields.insert(key, value);
+            }
+        }
+    }
+}
+
+fn encode_value(value: &Value) -> String {
+    let value = match value {
+        Value::Null => return String::new(),
+        value => value.to_string_lossy(),
+    };
+
+    if value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control())
+    {
+        format!("{:?}", value)
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn encode_logfmt() {
+        let event = {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo.lvl", Value::from("info"));
+            event
+                .as_mut_log()
+                .insert("foo.msg", Value::from("all good"));
+            event.as_mut_log().insert("foo.status", Value::from(200));
+            event.as_mut_log().insert("foo.req.path", Value::from("/"));
+            event.as_mut_log().insert("foo.empty", Value::from(""));
+            event.as_mut_log().insert("foo.user", Value::Null);
+            event
+        };
+
+        let cases = vec![
+            (
+                Ok(Value::from(
+                    r#"empty="" lvl=info msg="all good" req.path=/ status=200 user="#,
+                )),
+                EncodeLogfmtFn::new(Box::new(Path::from(vec![vec!["foo"]])), vec![]),
+            ),
+            (
+                Ok(Value::from(
+                    r#"lvl=info msg="all good" empty="" req.path=/ status=200 user="#,
+                )),
+                EncodeLogfmtFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    vec!["lvl", "msg", "missing"],
+                ),
+            ),
+        ];
+
+        for (exp, query) in cases {
+            assert_eq!(query.execute(&event), exp);
+        }
+    }
+}
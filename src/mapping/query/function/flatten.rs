@@ -0,0 +1,120 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct FlattenFn {
+    query: Box<dyn Function>,
+}
+
+impl FlattenFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for FlattenFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        required! {
+            ctx, self.query,
+            Value::Array(v) => {
+                let mut flattened = Vec::new();
+                flatten_array(v, &mut flattened);
+                Ok(Value::Array(flattened))
+            },
+            Value::Map(v) => {
+                let mut flattened = BTreeMap::new();
+                flatten_map(None, v, &mut flattened);
+                Ok(Value::Map(flattened))
+            },
+        }
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for FlattenFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+/// Moves the items of nested arrays into a single one.
+fn flatten_array(array: Vec<Value>, flattened: &mut Vec<Value>) {
+    for value in array {
+        match value {
+            Value::Array(array) => flatten_array(array, flattened),
+            value => flattened.push(value),
+        }
+    }
+}
+
+/// Moves the fields of nested maps into a single one, joining their keys with
+/// dots.
+fn flatten_map(
+    prefix: Option<&str>,
+    map: BTreeMap<String, Value>,
+    flattened: &mut BTreeMap<String, Value>,
+) {
+    for (key, value) in map {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key,
+        };
+
+        match value {
+            Value::Map(map) => flatten_map(Some(&key), map, flattened),
+            value => {
+                flattened.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flatten() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(json!([1, 2, 3, 4, {"foo": [5]}]))),
+                FlattenFn::new(Box::new(Literal::from(Value::from(
+                    json!([1, [2, [3]], [], [4, {"foo": [5]}]]),
+                )))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(json!({
+                    "foo": 1,
+                    "bar.baz": [2],
+                    "bar.qux.quux": 3,
+                }))),
+                FlattenFn::new(Box::new(Literal::from(Value::from(json!({
+                    "foo": 1,
+                    "bar": {
+                        "baz": [2],
+                        "qux": {"quux": 3},
+                    },
+                }))))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -18,7 +18,7 @@ impl FormatTimestampFn {
 }
 
 impl Function for FormatTimestampFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let format = match self.format.execute(ctx)? {
             Value::Bytes(b) => String::from_utf8_lossy(&b).into_owned(),
             v => unexpected_type!(v),
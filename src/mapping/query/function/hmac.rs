@@ -0,0 +1,153 @@
+use super::prelude::*;
+
+const ALGORITHMS: &[&str] = &["SHA1", "SHA-224", "SHA-256", "SHA-384", "SHA-512"];
+
+macro_rules! hex_hmac {
+    ($digest:ty, $key:expr, $value:expr) => {{
+        use hmac::{Hmac, Mac, NewMac};
+
+        let mut mac = Hmac::<$digest>::new_varkey($key).expect("HMAC accepts keys of any size");
+        mac.update($value);
+        hex::encode(mac.finalize().into_bytes())
+    }};
+}
+
+#[derive(Debug)]
+pub(in crate::mapping) struct HmacFn {
+    query: Box<dyn Function>,
+    key: Box<dyn Function>,
+    algorithm: Option<Box<dyn Function>>,
+}
+
+impl HmacFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        query: Box<dyn Function>,
+        key: &str,
+        algorithm: Option<&str>,
+    ) -> Self {
+        let key = Box::new(Literal::from(Value::from(key)));
+        let algorithm = algorithm.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self {
+            query,
+            key,
+            algorithm,
+        }
+    }
+}
+
+impl Function for HmacFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+        let key = required!(ctx, self.key, Value::Bytes(v) => v);
+
+        if key.is_empty() {
+            return Err("'key' must not be empty".to_owned());
+        }
+
+        let algorithm = optional!(ctx, self.algorithm, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| "SHA-256".to_owned());
+
+        let hash = match algorithm.as_str() {
+            "SHA1" => hex_hmac!(sha1::Sha1, &key, &bytes),
+            "SHA-224" => hex_hmac!(sha2::Sha224, &key, &bytes),
+            "SHA-256" => hex_hmac!(sha2::Sha256, &key, &bytes),
+            "SHA-384" => hex_hmac!(sha2::Sha384, &key, &bytes),
+            "SHA-512" => hex_hmac!(sha2::Sha512, &key, &bytes),
+            _ => {
+                return Err(format!(
+                    "'algorithm' must be one of {}",
+                    ALGORITHMS.join(", ")
+                ))
+            }
+        };
+
+        Ok(Value::Bytes(hash.into()))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for HmacFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let key = arguments.required("key")?;
+        let algorithm = arguments.optional("algorithm");
+
+        Ok(Self {
+            query,
+            key,
+            algorithm,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "147933218aaabc0b8b10a2b3a5c34684c8d94341bcf10a4736dc7270f7741851",
+                )),
+                HmacFn::new(Box::new(Literal::from(Value::from("foo"))), "bar", None),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("85d155c55ed286a300bd1cf124de08d87e914f3a")),
+                HmacFn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    "bar",
+                    Some("SHA1"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err(
+                    "'algorithm' must be one of SHA1, SHA-224, SHA-256, SHA-384, SHA-512"
+                        .to_owned(),
+                ),
+                HmacFn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    "bar",
+                    Some("MD5"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'key' must not be empty".to_owned()),
+                HmacFn::new(Box::new(Literal::from(Value::from("foo"))), "", None),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::prelude::*;
+use cidr_utils::cidr::IpCidr;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct IpCidrContainsFn {
+    cidr: Box<dyn Function>,
+    query: Box<dyn Function>,
+}
+
+impl IpCidrContainsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(cidr: Box<dyn Function>, query: Box<dyn Function>) -> Self {
+        Self { cidr, query }
+    }
+}
+
+impl Function for IpCidrContainsFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let ip_addr = IpAddr::from_str(&value)
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let cidrs = required!(
+            ctx, self.cidr,
+            Value::Bytes(v) => vec![Value::Bytes(v)],
+            Value::Array(v) => v,
+        );
+
+        for cidr in cidrs {
+            let cidr = match cidr {
+                Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                v => return Err(format!(r#"unexpected CIDR type: "{}""#, v.kind())),
+            };
+            let cidr =
+                IpCidr::from_str(&cidr).map_err(|err| format!("unable to parse CIDR: {}", err))?;
+
+            if cidr.contains(ip_addr) {
+                return Ok(Value::from(true));
+            }
+        }
+
+        Ok(Value::from(false))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "cidr",
+                accepts: |v| matches!(v, Value::Bytes(_) | Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for IpCidrContainsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let cidr = arguments.required("cidr")?;
+        let query = arguments.required("value")?;
+
+        Ok(Self { cidr, query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_cidr_contains() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(true)),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from(Value::from("192.168.0.0/16"))),
+                    Box::new(Literal::from(Value::from("192.168.10.32"))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(false)),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from(Value::from("192.168.0.0/16"))),
+                    Box::new(Literal::from(Value::from("10.0.0.1"))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(true)),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from(Value::from(vec![
+                        Value::from("10.0.0.0/8"),
+                        Value::from("2001:4f8:3:ba::/64"),
+                    ]))),
+                    Box::new(Literal::from(Value::from(
+                        "2001:4f8:3:ba:2e0:81ff:fe22:d1f1",
+                    ))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to parse IP address: invalid IP address syntax".to_owned()),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from(Value::from("192.168.0.0/16"))),
+                    Box::new(Literal::from(Value::from("foo"))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
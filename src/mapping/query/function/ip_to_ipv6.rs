@@ -0,0 +1,81 @@
+use super::prelude::*;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct IpToIpv6Fn {
+    query: Box<dyn Function>,
+}
+
+impl IpToIpv6Fn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for IpToIpv6Fn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let ipv6 = match IpAddr::from_str(&value)
+            .map_err(|err| format!("unable to parse IP address: {}", err))?
+        {
+            IpAddr::V4(addr) => addr.to_ipv6_mapped(),
+            IpAddr::V6(addr) => addr,
+        };
+
+        Ok(Value::from(ipv6.to_string()))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for IpToIpv6Fn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_to_ipv6() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from("::ffff:192.168.0.1")),
+                IpToIpv6Fn::new(Box::new(Literal::from(Value::from("192.168.0.1")))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("2001:db8::1")),
+                IpToIpv6Fn::new(Box::new(Literal::from(Value::from("2001:db8::1")))),
+            ),
+            (
+                Event::from(""),
+                Err("unable to parse IP address: invalid IP address syntax".to_owned()),
+                IpToIpv6Fn::new(Box::new(Literal::from(Value::from("foo")))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
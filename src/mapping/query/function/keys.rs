@@ -0,0 +1,67 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct KeysFn {
+    query: Box<dyn Function>,
+}
+
+impl KeysFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for KeysFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let map = required!(ctx, self.query, Value::Map(v) => v);
+
+        Ok(map.into_iter().map(|(key, _)| Value::from(key)).collect())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for KeysFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keys() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(json!(["bar", "foo"]))),
+                KeysFn::new(Box::new(Literal::from(Value::from(
+                    json!({"foo": 1, "bar": {"baz": 2}}),
+                )))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(json!([]))),
+                KeysFn::new(Box::new(Literal::from(Value::from(json!({}))))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct LengthFn {
+    query: Box<dyn Function>,
+}
+
+impl LengthFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for LengthFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let len = required! {
+            ctx, self.query,
+            Value::Array(v) => v.len(),
+            Value::Map(v) => v.len(),
+            Value::Bytes(v) => v.len(),
+        };
+
+        Ok(Value::from(len as i64))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_) | Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for LengthFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn length() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(3)),
+                LengthFn::new(Box::new(Literal::from(Value::from(json!([1, 2, 3]))))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(2)),
+                LengthFn::new(Box::new(Literal::from(Value::from(
+                    json!({"foo": 1, "bar": 2}),
+                )))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(3)),
+                LengthFn::new(Box::new(Literal::from(Value::from("foo")))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
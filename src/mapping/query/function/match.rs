@@ -0,0 +1,92 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct MatchFn {
+    query: Box<dyn Function>,
+    pattern: Regex,
+}
+
+impl MatchFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, pattern: &str) -> Self {
+        let pattern = Regex::new(pattern).unwrap();
+
+        Self { query, pattern }
+    }
+}
+
+impl Function for MatchFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        Ok(Value::from(self.pattern.is_match(&value)))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| false,
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for MatchFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let pattern = arguments.required_regex("pattern")?;
+
+        Ok(Self { query, pattern })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn is_match() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Err("path .foo not found in event".to_string()),
+                MatchFn::new(Box::new(Path::from(vec![vec!["foo"]])), "^bar"),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("bar baz"));
+                    event
+                },
+                Ok(Value::from(true)),
+                MatchFn::new(Box::new(Path::from(vec![vec!["foo"]])), "^bar"),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("baz bar"));
+                    event
+                },
+                Ok(Value::from(false)),
+                MatchFn::new(Box::new(Path::from(vec![vec!["foo"]])), "^bar"),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
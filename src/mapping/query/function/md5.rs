@@ -13,7 +13,7 @@ impl Md5Fn {
 }
 
 impl Function for Md5Fn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         use md5::{Digest, Md5};
 
         match self.query.execute(ctx)? {
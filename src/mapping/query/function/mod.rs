@@ -3,9 +3,9 @@
 mod not;
 pub(in crate::mapping) use not::NotFn;
 
-use super::Function;
-use crate::Event;
+use super::{Context, Function};
 use crate::{event::Value, mapping::Result};
+use regex::Regex;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -13,12 +13,15 @@ use std::str::FromStr;
 /// Commonly used types when building new functions.
 mod prelude {
     pub(super) use super::{is_scalar_value, ArgumentList, Parameter};
-    pub(super) use crate::event::{Event, Value};
-    pub(super) use crate::mapping::query::Function;
+    #[cfg(test)]
+    pub(super) use crate::event::Event;
+    pub(super) use crate::event::Value;
     #[cfg(test)]
     pub(super) use crate::mapping::query::Literal;
+    pub(super) use crate::mapping::query::{Context, Function};
     pub(super) use crate::mapping::Result;
     pub(super) use crate::types::Conversion;
+    pub(super) use regex::Regex;
     pub(super) use std::convert::TryFrom;
 }
 
@@ -68,7 +71,7 @@ macro_rules! build_signatures {
 
             fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
                 let func = match s {
-                    $(stringify!($name) => Self::$func,)*
+                    $(s if s == Self::$func.as_str() => Self::$func,)*
                     _ => return Err(format!("unknown function '{}'", s)),
                 };
 
@@ -78,8 +81,9 @@ macro_rules! build_signatures {
 
         impl FunctionSignature {
             pub fn as_str(&self) -> &str {
+                // Functions named after keywords are raw identifiers, e.g. `r#match`.
                 match self {
-                    $(Self::$func => stringify!($name),)*
+                    $(Self::$func => stringify!($name).trim_start_matches("r#"),)*
                 }
             }
 
@@ -120,6 +124,32 @@ build_signatures! {
     contains => ContainsFn,
     slice => SliceFn,
     tokenize => TokenizeFn,
+    r#match => MatchFn,
+    replace => ReplaceFn,
+    split => SplitFn,
+    parse_regex => ParseRegexFn,
+    length => LengthFn,
+    push => PushFn,
+    flatten => FlattenFn,
+    keys => KeysFn,
+    values => ValuesFn,
+    compact => CompactFn,
+    parse_syslog => ParseSyslogFn,
+    parse_key_value => ParseKeyValueFn,
+    parse_url => ParseUrlFn,
+    parse_aws_alb_log => ParseAwsAlbLogFn,
+    ip_cidr_contains => IpCidrContainsFn,
+    ip_to_ipv6 => IpToIpv6Fn,
+    parse_user_agent => ParseUserAgentFn,
+    sha2 => Sha2Fn,
+    sha3 => Sha3Fn,
+    hmac => HmacFn,
+    base64_encode => Base64EncodeFn,
+    base64_decode => Base64DecodeFn,
+    encode_json => EncodeJsonFn,
+    encode_logfmt => EncodeLogfmtFn,
+    redact => RedactFn,
+    pseudonymize => PseudonymizeFn,
 }
 
 /// A parameter definition accepted by a function.
@@ -133,6 +163,8 @@ pub(in crate::mapping) struct Parameter {
 
     /// The parser calls this method to determine if a given argument value is
     /// accepted by the parameter.
+    ///
+    /// Parameters taking a regex literal accept no values.
     pub accepts: fn(&Value) -> bool,
 
     /// Whether or not this is a required parameter.
@@ -176,14 +208,32 @@ impl ArgumentList {
     }
 
     pub fn optional(&mut self, keyword: &str) -> Option<Box<dyn Function>> {
-        self.take(keyword)
+        self.take(keyword).map(|v| Box::new(v) as _)
     }
 
     pub fn required(&mut self, keyword: &str) -> Result<Box<dyn Function>> {
-        self.take(keyword)
+        self.optional(keyword)
+            .ok_or(format!("unknown keyword: {}", keyword))
+    }
+
+    /// Take an argument that has to be given as a regex literal, so that it is
+    /// only compiled once when the mapping is parsed.
+    pub fn required_regex(&mut self, keyword: &str) -> Result<Regex> {
+        self.optional_regex(keyword)?
             .ok_or(format!("unknown keyword: {}", keyword))
     }
 
+    pub fn optional_regex(&mut self, keyword: &str) -> Result<Option<Regex>> {
+        match self.take(keyword).map(|v| v.resolver) {
+            Some(Resolver::Regex(regex)) => Ok(Some(regex)),
+            Some(Resolver::Query(_)) => Err(format!(
+                "argument '{}' must be a regex literal, e.g. /foo/",
+                keyword
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub fn keywords(&self) -> Vec<&str> {
         self.keywords.keys().map(String::as_str).collect()
     }
@@ -192,17 +242,31 @@ impl ArgumentList {
         self.arguments.len()
     }
 
-    fn take(&mut self, keyword: &str) -> Option<Box<dyn Function>> {
+    fn take(&mut self, keyword: &str) -> Option<Argument> {
         self.arguments
             .iter()
             .position(|a| a.parameter.keyword == keyword)
             .map(|i| self.arguments.remove(i))
-            .map(|v| Box::new(v) as _)
+    }
+}
+
+/// How the value of an argument is resolved.
+pub(in crate::mapping) enum Resolver {
+    Query(Box<dyn Function>),
+    Regex(Regex),
+}
+
+impl std::fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resolver::Query(query) => query.fmt(f),
+            Resolver::Regex(regex) => regex.fmt(f),
+        }
     }
 }
 
 pub(in crate::mapping) struct Argument {
-    resolver: Box<dyn Function>,
+    resolver: Resolver,
     parameter: Parameter,
 }
 
@@ -214,7 +278,7 @@ impl std::fmt::Debug for Argument {
 }
 
 impl Argument {
-    pub fn new(resolver: Box<dyn Function>, parameter: Parameter) -> Self {
+    pub fn new(resolver: Resolver, parameter: Parameter) -> Self {
         Self {
             resolver,
             parameter,
@@ -223,8 +287,16 @@ impl Argument {
 }
 
 impl Function for Argument {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
-        let value = self.resolver.execute(ctx)?;
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = match &self.resolver {
+            Resolver::Query(query) => query.execute(ctx)?,
+            Resolver::Regex(_) => {
+                return Err(format!(
+                    "parameter '{}' doesn't accept a regex",
+                    self.parameter.keyword
+                ))
+            }
+        };
 
         // Ask the parameter if it accepts the given value.
         if !(self.parameter.accepts)(&value) {
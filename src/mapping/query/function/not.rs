@@ -12,7 +12,7 @@ impl NotFn {
 }
 
 impl Function for NotFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         self.query.execute(ctx).and_then(|v| match v {
             Value::Boolean(b) => Ok(Value::Boolean(!b)),
             _ => Err(format!("unable to perform NOT on {:?} value", v)),
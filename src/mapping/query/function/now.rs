@@ -12,7 +12,7 @@ impl NowFn {
 }
 
 impl Function for NowFn {
-    fn execute(&self, _: &Event) -> Result<Value> {
+    fn execute(&self, _: &dyn Context) -> Result<Value> {
         Ok(Value::Timestamp(Utc::now()))
     }
 }
@@ -0,0 +1,193 @@
+use super::prelude::*;
+use crate::transforms::util::tokenize;
+use std::collections::BTreeMap;
+
+/// The fields of an access log entry, in order. Entries written before some fields
+/// were introduced end early.
+const FIELDS: &[(&str, Conversion)] = &[
+    ("type", Conversion::Bytes),
+    ("timestamp", Conversion::Timestamp),
+    ("elb", Conversion::Bytes),
+    ("client_host", Conversion::Bytes),
+    ("target_host", Conversion::Bytes),
+    ("request_processing_time", Conversion::Float),
+    ("target_processing_time", Conversion::Float),
+    ("response_processing_time", Conversion::Float),
+    ("elb_status_code", Conversion::Integer),
+    ("target_status_code", Conversion::Integer),
+    ("received_bytes", Conversion::Integer),
+    ("sent_bytes", Conversion::Integer),
+    ("request", Conversion::Bytes),
+    ("user_agent", Conversion::Bytes),
+    ("ssl_cipher", Conversion::Bytes),
+    ("ssl_protocol", Conversion::Bytes),
+    ("target_group_arn", Conversion::Bytes),
+    ("trace_id", Conversion::Bytes),
+    ("domain_name", Conversion::Bytes),
+    ("chosen_cert_arn", Conversion::Bytes),
+    ("matched_rule_priority", Conversion::Integer),
+    ("request_creation_time", Conversion::Timestamp),
+    ("actions_executed", Conversion::Bytes),
+    ("redirect_url", Conversion::Bytes),
+    ("error_reason", Conversion::Bytes),
+    ("target_port_list", Conversion::Bytes),
+    ("target_status_code_list", Conversion::Bytes),
+    ("classification", Conversion::Bytes),
+    ("classification_reason", Conversion::Bytes),
+];
+
+/// Entries always have at least the fields up to and including the request.
+const REQUIRED_FIELDS: usize = 13;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseAwsAlbLogFn {
+    query: Box<dyn Function>,
+}
+
+impl ParseAwsAlbLogFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for ParseAwsAlbLogFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let tokens = tokenize::parse(&value);
+        if tokens.len() < REQUIRED_FIELDS {
+            return Err(format!(
+                "unable to parse AWS ALB log: expected at least {} fields, got {}",
+                REQUIRED_FIELDS,
+                tokens.len()
+            ));
+        }
+
+        let mut fields = BTreeMap::new();
+        for (token, (name, conversion)) in tokens.into_iter().zip(FIELDS) {
+            let value = match token {
+                "" | "-" => Value::Null,
+                _ => conversion
+                    .convert(Value::from(token.to_owned()))
+                    .map_err(|err| {
+                        format!("unable to parse AWS ALB log field {}: {}", name, err)
+                    })?,
+            };
+
+            if *name == "request" {
+                // The request line is split up, as in `GET http://example.com/ HTTP/1.1`.
+                let parts = match value {
+                    Value::Null => vec![],
+                    _ => token.splitn(3, ' ').collect::<Vec<_>>(),
+                };
+                for (i, field) in ["request_method", "request_url", "request_protocol"]
+                    .iter()
+                    .enumerate()
+                {
+                    fields.insert(
+                        (*field).to_owned(),
+                        parts
+                            .get(i)
+                            .map(|part| Value::from((*part).to_owned()))
+                            .unwrap_or(Value::Null),
+                    );
+                }
+            } else {
+                fields.insert((*name).to_owned(), value);
+            }
+        }
+
+        Ok(Value::Map(fields))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseAwsAlbLogFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn parse_aws_alb_log() {
+        let log = r#"http 2018-07-02T22:23:00.186641Z app/my-loadbalancer/50dc6c495c0c9188 192.168.131.39:2817 10.0.0.1:80 0.000 0.001 0.000 200 200 34 366 "GET http://www.example.com:80/ HTTP/1.1" "curl/7.46.0" - - arn:aws:elasticloadbalancing:us-east-2:123456789012:targetgroup/my-targets/73e2d6bc24d8a067 "Root=1-58337262-36d228ad5d99923122bbe354" "-" "-" 0 2018-07-02T22:22:48.364000Z "forward" "-" "-" "10.0.0.1:80" "200" "-" "-""#;
+
+        let exp: BTreeMap<String, Value> = vec![
+            ("type", Value::from("http")),
+            (
+                "timestamp",
+                Value::from(Utc.ymd(2018, 7, 2).and_hms_micro(22, 23, 0, 186_641)),
+            ),
+            ("elb", Value::from("app/my-loadbalancer/50dc6c495c0c9188")),
+            ("client_host", Value::from("192.168.131.39:2817")),
+            ("target_host", Value::from("10.0.0.1:80")),
+            ("request_processing_time", Value::from(0.0)),
+            ("target_processing_time", Value::from(0.001)),
+            ("response_processing_time", Value::from(0.0)),
+            ("elb_status_code", Value::from(200)),
+            ("target_status_code", Value::from(200)),
+            ("received_bytes", Value::from(34)),
+            ("sent_bytes", Value::from(366)),
+            ("request_method", Value::from("GET")),
+            ("request_url", Value::from("http://www.example.com:80/")),
+            ("request_protocol", Value::from("HTTP/1.1")),
+            ("user_agent", Value::from("curl/7.46.0")),
+            ("ssl_cipher", Value::Null),
+            ("ssl_protocol", Value::Null),
+            (
+                "target_group_arn",
+                Value::from("arn:aws:elasticloadbalancing:us-east-2:123456789012:targetgroup/my-targets/73e2d6bc24d8a067"),
+            ),
+            ("trace_id", Value::from("Root=1-58337262-36d228ad5d99923122bbe354")),
+            ("domain_name", Value::Null),
+            ("chosen_cert_arn", Value::Null),
+            ("matched_rule_priority", Value::from(0)),
+            (
+                "request_creation_time",
+                Value::from(Utc.ymd(2018, 7, 2).and_hms_micro(22, 22, 48, 364_000)),
+            ),
+            ("actions_executed", Value::from("forward")),
+            ("redirect_url", Value::Null),
+            ("error_reason", Value::Null),
+            ("target_port_list", Value::from("10.0.0.1:80")),
+            ("target_status_code_list", Value::from("200")),
+            ("classification", Value::Null),
+            ("classification_reason", Value::Null),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+
+        let query = ParseAwsAlbLogFn::new(Box::new(Literal::from(Value::from(log))));
+        assert_eq!(query.execute(&Event::from("")), Ok(Value::Map(exp)));
+    }
+
+    #[test]
+    fn parse_aws_alb_log_too_short() {
+        let query = ParseAwsAlbLogFn::new(Box::new(Literal::from(Value::from("http -"))));
+        assert_eq!(
+            query.execute(&Event::from("")),
+            Err("unable to parse AWS ALB log: expected at least 13 fields, got 2".to_owned())
+        );
+    }
+}
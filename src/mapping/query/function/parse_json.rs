@@ -13,7 +13,7 @@ impl ParseJsonFn {
 }
 
 impl Function for ParseJsonFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx)? {
             Value::Bytes(b) => serde_json::from_slice(&b)
                 .map(|v: serde_json::Value| v.into())
@@ -0,0 +1,87 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseKeyValueFn {
+    query: Box<dyn Function>,
+}
+
+impl ParseKeyValueFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for ParseKeyValueFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        // Keys without a value are left out, just like the logfmt_parser transform
+        // does.
+        let fields = logfmt::parse(&value)
+            .into_iter()
+            .filter_map(|logfmt::Pair { key, val }| val.map(|val| (key, Value::from(val))))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Value::Map(fields))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseKeyValueFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_value() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::Map(
+                    vec![
+                        ("at".to_owned(), Value::from("info")),
+                        ("method".to_owned(), Value::from("GET")),
+                        ("path".to_owned(), Value::from("/")),
+                        ("status".to_owned(), Value::from("200")),
+                        ("desc".to_owned(), Value::from("all good")),
+                    ]
+                    .into_iter()
+                    .collect(),
+                )),
+                ParseKeyValueFn::new(Box::new(Literal::from(Value::from(
+                    r#"at=info method=GET path=/ status=200 desc="all good" flag"#,
+                )))),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Map(BTreeMap::new())),
+                ParseKeyValueFn::new(Box::new(Literal::from(Value::from("")))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
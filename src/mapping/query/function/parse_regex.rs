@@ -0,0 +1,119 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseRegexFn {
+    query: Box<dyn Function>,
+    pattern: Regex,
+}
+
+impl ParseRegexFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, pattern: &str) -> Self {
+        let pattern = Regex::new(pattern).unwrap();
+
+        Self { query, pattern }
+    }
+}
+
+impl Function for ParseRegexFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let captures = self
+            .pattern
+            .captures(&value)
+            .ok_or("could not find any pattern matches")?;
+
+        // Groups which didn't take part in the match are null.
+        Ok(Value::Map(
+            self.pattern
+                .capture_names()
+                .flatten()
+                .map(|name| {
+                    let value = captures
+                        .name(name)
+                        .map(|capture| Value::from(capture.as_str()))
+                        .unwrap_or(Value::Null);
+                    (name.to_owned(), value)
+                })
+                .collect::<BTreeMap<_, _>>(),
+        ))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| false,
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseRegexFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let pattern = arguments.required_regex("pattern")?;
+
+        Ok(Self { query, pattern })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_regex() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(json!({
+                    "method": "GET",
+                    "path": "/index.html",
+                    "status": "200",
+                }))),
+                ParseRegexFn::new(
+                    Box::new(Literal::from(Value::from("GET /index.html 200"))),
+                    r"^(?P<method>\w+) (?P<path>\S+) (?P<status>\d+)$",
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(json!({
+                    "method": "GET",
+                    "query": null,
+                }))),
+                ParseRegexFn::new(
+                    Box::new(Literal::from(Value::from("GET /index.html"))),
+                    r"^(?P<method>\w+) /\S+?(\?(?P<query>\S+))?$",
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("could not find any pattern matches".to_string()),
+                ParseRegexFn::new(
+                    Box::new(Literal::from(Value::from("nope"))),
+                    r"^(?P<number>\d+)$",
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::prelude::*;
+use crate::{
+    event::Event,
+    sources::util::syslog::{insert_fields_from_syslog, resolve_year},
+};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseSyslogFn {
+    query: Box<dyn Function>,
+}
+
+impl ParseSyslogFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for ParseSyslogFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        // Messages are parsed just like the syslog source does, so that both end up
+        // with the same fields.
+        let parsed = syslog_loose::parse_message_with_year(value.trim(), resolve_year);
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", parsed.msg.to_owned());
+        if let Some(timestamp) = parsed.timestamp {
+            event
+                .as_mut_log()
+                .insert("timestamp", DateTime::<Utc>::from(timestamp));
+        }
+        insert_fields_from_syslog(&mut event, parsed);
+
+        let fields: BTreeMap<String, Value> = event.into_log().into();
+        Ok(Value::Map(fields))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseSyslogFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parse_syslog() {
+        let message = r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - [meta sequenceId="1" language="EN"] i am foobar"#;
+
+        let mut exp = BTreeMap::new();
+        exp.insert("message".to_owned(), Value::from("i am foobar"));
+        exp.insert(
+            "timestamp".to_owned(),
+            Value::from(Utc.ymd(2019, 2, 13).and_hms(19, 48, 34)),
+        );
+        exp.insert("hostname".to_owned(), Value::from("74794bfb6795"));
+        exp.insert("severity".to_owned(), Value::from("notice"));
+        exp.insert("facility".to_owned(), Value::from("user"));
+        exp.insert("version".to_owned(), Value::from(1));
+        exp.insert("appname".to_owned(), Value::from("root"));
+        exp.insert("procid".to_owned(), Value::from(8449));
+        exp.insert("meta".to_owned(), {
+            let mut meta = BTreeMap::new();
+            meta.insert("sequenceId".to_owned(), Value::from("1"));
+            meta.insert("language".to_owned(), Value::from("EN"));
+            Value::Map(meta)
+        });
+
+        let query = ParseSyslogFn::new(Box::new(Literal::from(Value::from(message))));
+        assert_eq!(query.execute(&Event::from("")), Ok(Value::Map(exp)));
+    }
+}
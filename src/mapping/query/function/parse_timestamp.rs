@@ -26,7 +26,7 @@ impl ParseTimestampFn {
 }
 
 impl Function for ParseTimestampFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let format = match self.format.execute(ctx)? {
             Value::Bytes(b) => format!("timestamp|{}", String::from_utf8_lossy(&b)),
             v => unexpected_type!(v),
@@ -0,0 +1,101 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+use woothee::parser::Parser;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseUserAgentFn {
+    query: Box<dyn Function>,
+}
+
+impl ParseUserAgentFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for ParseUserAgentFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let parsed = Parser::new()
+            .parse(&value)
+            .ok_or_else(|| "unable to parse user agent".to_owned())?;
+
+        let mut fields = BTreeMap::new();
+        fields.insert("name".to_owned(), Value::from(parsed.name.to_string()));
+        fields.insert(
+            "category".to_owned(),
+            Value::from(parsed.category.to_string()),
+        );
+        fields.insert("os".to_owned(), Value::from(parsed.os.to_string()));
+        fields.insert(
+            "os_version".to_owned(),
+            Value::from(parsed.os_version.to_string()),
+        );
+        fields.insert(
+            "browser_type".to_owned(),
+            Value::from(parsed.browser_type.to_string()),
+        );
+        fields.insert(
+            "version".to_owned(),
+            Value::from(parsed.version.to_string()),
+        );
+        fields.insert("vendor".to_owned(), Value::from(parsed.vendor.to_string()));
+
+        Ok(Value::Map(fields))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseUserAgentFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_user_agent() {
+        let cases = vec![(
+            Event::from(""),
+            Ok(Value::Map(
+                vec![
+                    ("name".to_owned(), Value::from("Firefox")),
+                    ("category".to_owned(), Value::from("pc")),
+                    ("os".to_owned(), Value::from("Linux")),
+                    ("os_version".to_owned(), Value::from("UNKNOWN")),
+                    ("browser_type".to_owned(), Value::from("browser")),
+                    ("version".to_owned(), Value::from("79.0")),
+                    ("vendor".to_owned(), Value::from("Mozilla")),
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            ParseUserAgentFn::new(Box::new(Literal::from(Value::from(
+                "Mozilla/5.0 (X11; Linux x86_64; rv:79.0) Gecko/20100101 Firefox/79.0",
+            )))),
+        )];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use super::prelude::*;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+
+/// Replaces a value with a keyed HMAC-SHA256 hash of it.
+///
+/// The same value and key always give the same pseudonym, so that events can
+/// still be correlated, while the original value can't be recovered without
+/// the key.
+#[derive(Debug)]
+pub(in crate::mapping) struct PseudonymizeFn {
+    query: Box<dyn Function>,
+    key: Box<dyn Function>,
+}
+
+impl PseudonymizeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, key: &str) -> Self {
+        let key = Box::new(Literal::from(Value::from(key)));
+
+        Self { query, key }
+    }
+}
+
+impl Function for PseudonymizeFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = self.query.execute(ctx)?;
+        let key = required!(ctx, self.key, Value::Bytes(v) => v);
+
+        if key.is_empty() {
+            return Err("'key' must not be empty".to_owned());
+        }
+
+        let mut mac = Hmac::<Sha256>::new_varkey(&key).expect("HMAC accepts keys of any size");
+        mac.update(&value.as_bytes());
+
+        Ok(Value::from(hex::encode(mac.finalize().into_bytes())))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: is_scalar_value,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for PseudonymizeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let key = arguments.required("key")?;
+
+        Ok(Self { query, key })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pseudonymize() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "3212eddab48c148e4a7d0489cca5956d880a4093c1047ed78f0b82384cf96e52",
+                )),
+                PseudonymizeFn::new(
+                    Box::new(Literal::from(Value::from("jane.doe@example.com"))),
+                    "secret",
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "d759a47143515e55cafbf10d15399bf1d602e0bbed0295197f587d6273324640",
+                )),
+                PseudonymizeFn::new(
+                    Box::new(Literal::from(Value::from("jane.doe@example.com"))),
+                    "other",
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "93c121e7aa437a1e01e3c512c6f0ce3c821a839025dca4408f85616de4aaee70",
+                )),
+                PseudonymizeFn::new(Box::new(Literal::from(Value::from(42))), "secret"),
+            ),
+            (
+                Event::from(""),
+                Err("'key' must not be empty".to_owned()),
+                PseudonymizeFn::new(Box::new(Literal::from(Value::from("foo"))), ""),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
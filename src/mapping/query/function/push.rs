@@ -0,0 +1,83 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct PushFn {
+    query: Box<dyn Function>,
+    item: Box<dyn Function>,
+}
+
+impl PushFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, item: Value) -> Self {
+        let item = Box::new(Literal::from(item));
+
+        Self { query, item }
+    }
+}
+
+impl Function for PushFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let mut array = required!(ctx, self.query, Value::Array(v) => v);
+        array.push(self.item.execute(ctx)?);
+
+        Ok(Value::Array(array))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "item",
+                accepts: |_| true,
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for PushFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let item = arguments.required("item")?;
+
+        Ok(Self { query, item })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn push() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(json!([1, 2, 3]))),
+                PushFn::new(
+                    Box::new(Literal::from(Value::from(json!([1, 2])))),
+                    Value::from(3),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(json!([[1]]))),
+                PushFn::new(
+                    Box::new(Literal::from(Value::from(json!([])))),
+                    Value::from(json!([1])),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
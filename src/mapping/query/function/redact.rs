@@ -0,0 +1,276 @@
+use super::prelude::*;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // Runs of digit groups, within which card numbers are then looked for.
+    static ref DIGIT_GROUPS: Regex = Regex::new(r"\b\d+(?:[ -]\d+)*\b").unwrap();
+    static ref DIGITS: Regex = Regex::new(r"\d+").unwrap();
+    static ref EMAIL: Regex =
+        Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").unwrap();
+    static ref US_SOCIAL_SECURITY_NUMBER: Regex = Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap();
+}
+
+const FILTERS: &[&str] = &["credit_card", "email", "us_social_security_number"];
+
+const DEFAULT_REDACTOR: &str = "[REDACTED]";
+
+#[derive(Debug)]
+pub(in crate::mapping) struct RedactFn {
+    query: Box<dyn Function>,
+    filters: Option<Box<dyn Function>>,
+    pattern: Option<Regex>,
+    with: Option<Box<dyn Function>>,
+}
+
+impl RedactFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        query: Box<dyn Function>,
+        filters: Vec<&str>,
+        pattern: Option<&str>,
+        with: Option<&str>,
+    ) -> Self {
+        let filters = if filters.is_empty() {
+            None
+        } else {
+            let filters = filters.into_iter().map(Value::from).collect::<Vec<_>>();
+            Some(Box::new(Literal::from(Value::from(filters))) as _)
+        };
+        let pattern = pattern.map(|p| Regex::new(p).unwrap());
+        let with = with.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self {
+            query,
+            filters,
+            pattern,
+            with,
+        }
+    }
+}
+
+impl Function for RedactFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let mut value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let with = optional!(ctx, self.with, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| DEFAULT_REDACTOR.to_owned());
+
+        // Without any filters or a custom pattern, all built-in filters apply.
+        let filters = match optional!(ctx, self.filters, Value::Array(v) => v) {
+            Some(filters) => filters
+                .into_iter()
+                .map(|filter| match filter {
+                    Value::Bytes(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+                    v => Err(format!(r#"unexpected filter type: "{}""#, v.kind())),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None if self.pattern.is_some() => vec![],
+            None => FILTERS.iter().map(|&filter| filter.to_owned()).collect(),
+        };
+
+        for filter in filters {
+            value = match filter.as_str() {
+                "credit_card" => DIGIT_GROUPS
+                    .replace_all(&value, |caps: &regex::Captures| {
+                        redact_card_numbers(&caps[0], &with)
+                    })
+                    .into_owned(),
+                "email" => EMAIL.replace_all(&value, with.as_str()).into_owned(),
+                "us_social_security_number" => US_SOCIAL_SECURITY_NUMBER
+                    .replace_all(&value, with.as_str())
+                    .into_owned(),
+                _ => {
+                    return Err(format!(
+                        "'filters' must only contain {}",
+                        FILTERS.join(", ")
+                    ))
+                }
+            };
+        }
+
+        if let Some(pattern) = &self.pattern {
+            value = pattern.replace_all(&value, with.as_str()).into_owned();
+        }
+
+        Ok(Value::from(value))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "filters",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| false,
+                required: false,
+            },
+            Parameter {
+                keyword: "with",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for RedactFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let filters = arguments.optional("filters");
+        let pattern = arguments.optional_regex("pattern")?;
+        let with = arguments.optional("with");
+
+        Ok(Self {
+            query,
+            filters,
+            pattern,
+            with,
+        })
+    }
+}
+
+/// Redacts the card numbers found in a run of digit groups, e.g. `4111 1111 1111
+/// 1111 2`. Card numbers are made of whole groups holding 13 to 19 digits in total
+/// which pass the Luhn check, so that a number next to a card number doesn't stop
+/// it from being redacted, while other long numbers are left as they are. The
+/// longest card number starting at a group wins.
+fn redact_card_numbers(run: &str, with: &str) -> String {
+    let groups = DIGITS.find_iter(run).collect::<Vec<_>>();
+
+    let mut redacted = String::with_capacity(run.len());
+    let mut copied = 0;
+    let mut first = 0;
+    while first < groups.len() {
+        let mut digits = 0;
+        let mut card = None;
+        for (last, group) in groups.iter().enumerate().skip(first) {
+            digits += group.end() - group.start();
+            if digits > 19 {
+                break;
+            }
+            if digits >= 13 && luhn_valid(&run[groups[first].start()..group.end()]) {
+                card = Some(last);
+            }
+        }
+
+        match card {
+            Some(last) => {
+                redacted.push_str(&run[copied..groups[first].start()]);
+                redacted.push_str(with);
+                copied = groups[last].end();
+                first = last + 1;
+            }
+            None => first += 1,
+        }
+    }
+    redacted.push_str(&run[copied..]);
+
+    redacted
+}
+
+/// Checks the digits in `number` against the Luhn checksum used by payment
+/// card numbers.
+fn luhn_valid(number: &str) -> bool {
+    let sum: u32 = number
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match i % 2 {
+            0 => digit,
+            _ if digit * 2 > 9 => digit * 2 - 9,
+            _ => digit * 2,
+        })
+        .sum();
+
+    sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "card [REDACTED], order 1234567890123, mail [REDACTED], ssn [REDACTED]",
+                )),
+                RedactFn::new(
+                    Box::new(Literal::from(Value::from(
+                        "card 4111 1111 1111 1111, order 1234567890123, mail jane.doe@example.com, ssn 078-05-1120",
+                    ))),
+                    vec![],
+                    None,
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("mail ***, ssn 078-05-1120")),
+                RedactFn::new(
+                    Box::new(Literal::from(Value::from(
+                        "mail jane.doe@example.com, ssn 078-05-1120",
+                    ))),
+                    vec!["email"],
+                    None,
+                    Some("***"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "cards [REDACTED] 2, 7 [REDACTED] and [REDACTED], ids 4111 1111 1111 1112",
+                )),
+                RedactFn::new(
+                    Box::new(Literal::from(Value::from(
+                        "cards 4111 1111 1111 1111 2, 7 5500-0000-0000-0004 and 4111111111111111, ids 4111 1111 1111 1112",
+                    ))),
+                    vec!["credit_card"],
+                    None,
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("token=[REDACTED] user=jane")),
+                RedactFn::new(
+                    Box::new(Literal::from(Value::from("token=s3cr3t user=jane"))),
+                    vec![],
+                    Some(r"s3cr3t"),
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'filters' must only contain credit_card, email, us_social_security_number".to_owned()),
+                RedactFn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    vec!["phone_number"],
+                    None,
+                    None,
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ReplaceFn {
+    query: Box<dyn Function>,
+    pattern: Regex,
+    with: Box<dyn Function>,
+    count: Option<Box<dyn Function>>,
+}
+
+impl ReplaceFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        query: Box<dyn Function>,
+        pattern: &str,
+        with: &str,
+        count: Option<i64>,
+    ) -> Self {
+        let pattern = Regex::new(pattern).unwrap();
+        let with = Box::new(Literal::from(Value::from(with)));
+        let count = count.map(|i| Box::new(Literal::from(Value::from(i))) as _);
+
+        Self {
+            query,
+            pattern,
+            with,
+            count,
+        }
+    }
+}
+
+impl Function for ReplaceFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let with = {
+            let bytes = required!(ctx, self.with, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        // A limit of zero replaces every match.
+        let count = match optional!(ctx, self.count, Value::Integer(v) => v) {
+            Some(count) if count < 0 => return Err("'count' must not be negative".to_owned()),
+            Some(count) => count as usize,
+            None => 0,
+        };
+
+        Ok(Value::from(
+            self.pattern
+                .replacen(&value, count, with.as_str())
+                .into_owned(),
+        ))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| false,
+                required: true,
+            },
+            Parameter {
+                keyword: "with",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "count",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ReplaceFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let pattern = arguments.required_regex("pattern")?;
+        let with = arguments.required("with")?;
+        let count = arguments.optional("count");
+
+        Ok(Self {
+            query,
+            pattern,
+            with,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from("I like opples ond bononos")),
+                ReplaceFn::new(
+                    Box::new(Literal::from(Value::from("I like apples and bananas"))),
+                    "a",
+                    "o",
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("I like opples and bananas")),
+                ReplaceFn::new(
+                    Box::new(Literal::from(Value::from("I like apples and bananas"))),
+                    "a",
+                    "o",
+                    Some(1),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("bananas and apples")),
+                ReplaceFn::new(
+                    Box::new(Literal::from(Value::from("apples and bananas"))),
+                    r"(?P<first>\w+) and (?P<second>\w+)",
+                    "$second and $first",
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'count' must not be negative".to_string()),
+                ReplaceFn::new(
+                    Box::new(Literal::from(Value::from("apples"))),
+                    "a",
+                    "o",
+                    Some(-1),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
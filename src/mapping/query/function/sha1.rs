@@ -13,7 +13,7 @@ impl Sha1Fn {
 }
 
 impl Function for Sha1Fn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         use sha1::{Digest, Sha1};
 
         match self.query.execute(ctx)? {
@@ -0,0 +1,115 @@
+use super::prelude::*;
+
+const VARIANTS: &[&str] = &[
+    "SHA-224",
+    "SHA-256",
+    "SHA-384",
+    "SHA-512",
+    "SHA-512/224",
+    "SHA-512/256",
+];
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Sha2Fn {
+    query: Box<dyn Function>,
+    variant: Option<Box<dyn Function>>,
+}
+
+impl Sha2Fn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, variant: Option<&str>) -> Self {
+        let variant = variant.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self { query, variant }
+    }
+}
+
+impl Function for Sha2Fn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        use sha2::{Digest, Sha224, Sha256, Sha384, Sha512, Sha512Trunc224, Sha512Trunc256};
+
+        let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+
+        let variant = optional!(ctx, self.variant, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| "SHA-512/256".to_owned());
+
+        let hash = match variant.as_str() {
+            "SHA-224" => hex::encode(Sha224::digest(&bytes)),
+            "SHA-256" => hex::encode(Sha256::digest(&bytes)),
+            "SHA-384" => hex::encode(Sha384::digest(&bytes)),
+            "SHA-512" => hex::encode(Sha512::digest(&bytes)),
+            "SHA-512/224" => hex::encode(Sha512Trunc224::digest(&bytes)),
+            "SHA-512/256" => hex::encode(Sha512Trunc256::digest(&bytes)),
+            _ => return Err(format!("'variant' must be one of {}", VARIANTS.join(", "))),
+        };
+
+        Ok(Value::Bytes(hash.into()))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for Sha2Fn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let variant = arguments.optional("variant");
+
+        Ok(Self { query, variant })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha2() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "d58042e6aa5a335e03ad576c6a9e43b41591bfd2077f72dec9df7930e492055d",
+                )),
+                Sha2Fn::new(Box::new(Literal::from(Value::from("foo"))), None),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae",
+                )),
+                Sha2Fn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    Some("SHA-256"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'variant' must be one of SHA-224, SHA-256, SHA-384, SHA-512, SHA-512/224, SHA-512/256".to_owned()),
+                Sha2Fn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    Some("SHA-1"),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
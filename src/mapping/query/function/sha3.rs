@@ -0,0 +1,106 @@
+use super::prelude::*;
+
+const VARIANTS: &[&str] = &["SHA3-224", "SHA3-256", "SHA3-384", "SHA3-512"];
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Sha3Fn {
+    query: Box<dyn Function>,
+    variant: Option<Box<dyn Function>>,
+}
+
+impl Sha3Fn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, variant: Option<&str>) -> Self {
+        let variant = variant.map(|v| Box::new(Literal::from(Value::from(v))) as _);
+
+        Self { query, variant }
+    }
+}
+
+impl Function for Sha3Fn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        use sha3::{Digest, Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+        let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+
+        let variant = optional!(ctx, self.variant, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| "SHA3-512".to_owned());
+
+        let hash = match variant.as_str() {
+            "SHA3-224" => hex::encode(Sha3_224::digest(&bytes)),
+            "SHA3-256" => hex::encode(Sha3_256::digest(&bytes)),
+            "SHA3-384" => hex::encode(Sha3_384::digest(&bytes)),
+            "SHA3-512" => hex::encode(Sha3_512::digest(&bytes)),
+            _ => return Err(format!("'variant' must be one of {}", VARIANTS.join(", "))),
+        };
+
+        Ok(Value::Bytes(hash.into()))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for Sha3Fn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let variant = arguments.optional("variant");
+
+        Ok(Self { query, variant })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha3() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "4bca2b137edc580fe50a88983ef860ebaca36c857b1f492839d6d7392452a63c82cbebc68e3b70a2a1480b4bb5d437a7cba6ecf9d89f9ff3ccd14cd6146ea7e7",
+                )),
+                Sha3Fn::new(Box::new(Literal::from(Value::from("foo"))), None),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(
+                    "76d3bc41c9f588f7fcd0d5bf4718f8f84b1c41b20882703100b9eb9413807c01",
+                )),
+                Sha3Fn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    Some("SHA3-256"),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("'variant' must be one of SHA3-224, SHA3-256, SHA3-384, SHA3-512".to_owned()),
+                Sha3Fn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    Some("SHA-256"),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
@@ -22,7 +22,7 @@ impl SliceFn {
 }
 
 impl Function for SliceFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let range = |len: i64| {
             let start = match required!(ctx, self.start, Value::Integer(v) => v) {
                 start if start < 0 => start + len,
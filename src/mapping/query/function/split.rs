@@ -0,0 +1,133 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct SplitFn {
+    query: Box<dyn Function>,
+    pattern: Regex,
+    limit: Option<Box<dyn Function>>,
+}
+
+impl SplitFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        query: Box<dyn Function>,
+        pattern: &str,
+        limit: Option<i64>,
+    ) -> Self {
+        let pattern = Regex::new(pattern).unwrap();
+        let limit = limit.map(|i| Box::new(Literal::from(Value::from(i))) as _);
+
+        Self {
+            query,
+            pattern,
+            limit,
+        }
+    }
+}
+
+impl Function for SplitFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let value = {
+            let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        let parts: Vec<Value> = match optional!(ctx, self.limit, Value::Integer(v) => v) {
+            Some(limit) if limit < 1 => return Err("'limit' must be greater than zero".to_owned()),
+            Some(limit) => self
+                .pattern
+                .splitn(&value, limit as usize)
+                .map(Value::from)
+                .collect(),
+            None => self.pattern.split(&value).map(Value::from).collect(),
+        };
+
+        Ok(Value::from(parts))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| false,
+                required: true,
+            },
+            Parameter {
+                keyword: "limit",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for SplitFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+        let pattern = arguments.required_regex("pattern")?;
+        let limit = arguments.optional("limit");
+
+        Ok(Self {
+            query,
+            pattern,
+            limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::from(vec![
+                    Value::from("foo"),
+                    Value::from("bar"),
+                    Value::from("baz"),
+                ])),
+                SplitFn::new(
+                    Box::new(Literal::from(Value::from("foo, bar,baz"))),
+                    r",\s*",
+                    None,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(vec![
+                    Value::from("foo"),
+                    Value::from("bar,baz"),
+                ])),
+                SplitFn::new(
+                    Box::new(Literal::from(Value::from("foo,bar,baz"))),
+                    ",",
+                    Some(2),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from(vec![Value::from("foo")])),
+                SplitFn::new(Box::new(Literal::from(Value::from("foo"))), ",", None),
+            ),
+            (
+                Event::from(""),
+                Err("'limit' must be greater than zero".to_string()),
+                SplitFn::new(Box::new(Literal::from(Value::from("foo"))), ",", Some(0)),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
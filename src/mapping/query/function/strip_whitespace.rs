@@ -13,7 +13,7 @@ impl StripWhitespaceFn {
 }
 
 impl Function for StripWhitespaceFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx)? {
             Value::Bytes(b) => std::str::from_utf8(&b)
                 .map(|s| Value::Bytes(b.slice_ref(s.trim().as_bytes())))
@@ -15,7 +15,7 @@ impl ToBooleanFn {
 }
 
 impl Function for ToBooleanFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx) {
             Ok(v) => match v {
                 Value::Boolean(_) => Ok(v),
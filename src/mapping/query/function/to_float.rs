@@ -15,7 +15,7 @@ impl ToFloatFn {
 }
 
 impl Function for ToFloatFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx) {
             Ok(v) => match v {
                 Value::Float(_) => Ok(v),
@@ -15,7 +15,7 @@ impl ToIntegerFn {
 }
 
 impl Function for ToIntegerFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx) {
             Ok(v) => match v {
                 Value::Integer(_) => Ok(v),
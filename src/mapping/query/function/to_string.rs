@@ -15,7 +15,7 @@ impl ToStringFn {
 }
 
 impl Function for ToStringFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx) {
             Ok(v) => Ok(match v {
                 Value::Bytes(_) => v,
@@ -16,7 +16,7 @@ impl ToTimestampFn {
 }
 
 impl Function for ToTimestampFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         self.query
             .execute(ctx)
             .and_then(to_timestamp)
@@ -14,7 +14,7 @@ impl TokenizeFn {
 }
 
 impl Function for TokenizeFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let value = {
             let bytes = required!(ctx, self.query, Value::Bytes(v) => v);
             String::from_utf8_lossy(&bytes).into_owned()
@@ -25,7 +25,7 @@ impl TruncateFn {
 }
 
 impl Function for TruncateFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         let value = self.query.execute(ctx)?;
         if let Value::Bytes(bytes) = value {
             let limit = match self.limit.execute(ctx)? {
@@ -13,7 +13,7 @@ impl UpcaseFn {
 }
 
 impl Function for UpcaseFn {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         match self.query.execute(ctx)? {
             Value::Bytes(bytes) => Ok(Value::Bytes(
                 String::from_utf8_lossy(&bytes).to_uppercase().into(),
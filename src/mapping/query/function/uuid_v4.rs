@@ -12,7 +12,7 @@ impl UuidV4Fn {
 }
 
 impl Function for UuidV4Fn {
-    fn execute(&self, _: &Event) -> Result<Value> {
+    fn execute(&self, _: &dyn Context) -> Result<Value> {
         let mut buf = [0; 36];
         let uuid = uuid::Uuid::new_v4().to_hyphenated().encode_lower(&mut buf);
 
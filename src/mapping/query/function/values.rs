@@ -0,0 +1,60 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ValuesFn {
+    query: Box<dyn Function>,
+}
+
+impl ValuesFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(query: Box<dyn Function>) -> Self {
+        Self { query }
+    }
+}
+
+impl Function for ValuesFn {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        let map = required!(ctx, self.query, Value::Map(v) => v);
+
+        Ok(map.into_iter().map(|(_, value)| value).collect())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ValuesFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let query = arguments.required("value")?;
+
+        Ok(Self { query })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn values() {
+        let cases = vec![(
+            Event::from(""),
+            Ok(Value::from(json!([{"baz": 2}, 1]))),
+            ValuesFn::new(Box::new(Literal::from(Value::from(
+                json!({"foo": 1, "bar": {"baz": 2}}),
+            )))),
+        )];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp);
+        }
+    }
+}
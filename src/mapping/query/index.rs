@@ -0,0 +1,111 @@
+use super::{Context, Function};
+use crate::{event::Value, mapping::Result};
+
+/// Indexes into the array or map another query resolves to. Negative indexes
+/// count back from the end of an array.
+#[derive(Debug)]
+pub(in crate::mapping) struct Index {
+    query: Box<dyn Function>,
+    index: Box<dyn Function>,
+}
+
+impl Index {
+    pub(in crate::mapping) fn new(query: Box<dyn Function>, index: Box<dyn Function>) -> Self {
+        Self { query, index }
+    }
+}
+
+impl Function for Index {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        match (self.query.execute(ctx)?, self.index.execute(ctx)?) {
+            (Value::Array(mut array), Value::Integer(index)) => {
+                let len = array.len() as i64;
+                let position = if index < 0 { index + len } else { index };
+
+                if position < 0 || position >= len {
+                    return Err(format!(
+                        "index {} out of bounds for array of length {}",
+                        index, len
+                    ));
+                }
+
+                Ok(array.swap_remove(position as usize))
+            }
+            (Value::Map(mut map), Value::Bytes(key)) => {
+                let key = String::from_utf8_lossy(&key);
+                map.remove(key.as_ref())
+                    .ok_or_else(|| format!("key \"{}\" not found in map", key))
+            }
+            (Value::Array(_), index) => Err(format!(
+                "arrays can only be indexed by integers, not {}",
+                index.kind()
+            )),
+            (Value::Map(_), index) => Err(format!(
+                "maps can only be indexed by strings, not {}",
+                index.kind()
+            )),
+            (value, _) => Err(format!("unable to index into {}", value.kind())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Event, mapping::query::Literal};
+    use serde_json::json;
+
+    #[test]
+    fn check_index_query() {
+        let cases = vec![
+            (
+                Value::from(json!([1, 2, 3])),
+                Value::from(0),
+                Ok(Value::from(1)),
+            ),
+            (
+                Value::from(json!([1, 2, 3])),
+                Value::from(-1),
+                Ok(Value::from(3)),
+            ),
+            (
+                Value::from(json!([1, 2, 3])),
+                Value::from(3),
+                Err("index 3 out of bounds for array of length 3".to_string()),
+            ),
+            (
+                Value::from(json!([1, 2, 3])),
+                Value::from(-4),
+                Err("index -4 out of bounds for array of length 3".to_string()),
+            ),
+            (
+                Value::from(json!([1, 2, 3])),
+                Value::from("foo"),
+                Err("arrays can only be indexed by integers, not string".to_string()),
+            ),
+            (
+                Value::from(json!({"foo": "bar"})),
+                Value::from("foo"),
+                Ok(Value::from("bar")),
+            ),
+            (
+                Value::from(json!({"foo": "bar"})),
+                Value::from("baz"),
+                Err("key \"baz\" not found in map".to_string()),
+            ),
+            (
+                Value::from("foo"),
+                Value::from(0),
+                Err("unable to index into string".to_string()),
+            ),
+        ];
+
+        for (value, index, exp) in cases {
+            let query = Index::new(
+                Box::new(Literal::from(value)),
+                Box::new(Literal::from(index)),
+            );
+            assert_eq!(query.execute(&Event::from("")), exp);
+        }
+    }
+}
@@ -2,14 +2,37 @@ use crate::{
     event::{Event, Value},
     mapping::Result,
 };
+use std::collections::BTreeMap;
 
 pub mod arithmetic;
 pub mod function;
+pub mod index;
 pub mod path;
 
+/// What queries are resolved against.
+pub(in crate::mapping) trait Context {
+    /// The event being mapped.
+    fn event(&self) -> &Event;
+
+    /// The value of a variable assigned earlier in the mapping, if any.
+    fn variable(&self, name: &str) -> Option<&Value>;
+}
+
+impl Context for Event {
+    fn event(&self) -> &Event {
+        self
+    }
+
+    fn variable(&self, _: &str) -> Option<&Value> {
+        None
+    }
+}
+
+//------------------------------------------------------------------------------
+
 pub(in crate::mapping) trait Function: Send + core::fmt::Debug {
     /// Run the function to produce a [`Value`].
-    fn execute(&self, context: &Event) -> Result<Value>;
+    fn execute(&self, context: &dyn Context) -> Result<Value>;
 
     /// Return the static set of parameters this function accepts.
     fn parameters() -> &'static [function::Parameter]
@@ -34,7 +57,74 @@ impl From<Value> for Literal {
 }
 
 impl Function for Literal {
-    fn execute(&self, _: &Event) -> Result<Value> {
+    fn execute(&self, _: &dyn Context) -> Result<Value> {
         Ok(self.value.clone())
     }
 }
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Variable {
+    name: String,
+}
+
+impl Variable {
+    pub(in crate::mapping) fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl Function for Variable {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        ctx.variable(&self.name)
+            .cloned()
+            .ok_or_else(|| format!("variable ${} not assigned", self.name))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Array {
+    values: Vec<Box<dyn Function>>,
+}
+
+impl Array {
+    pub(in crate::mapping) fn new(values: Vec<Box<dyn Function>>) -> Self {
+        Self { values }
+    }
+}
+
+impl Function for Array {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        self.values
+            .iter()
+            .map(|value| value.execute(ctx))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(in crate::mapping) struct Object {
+    fields: Vec<(String, Box<dyn Function>)>,
+}
+
+impl Object {
+    pub(in crate::mapping) fn new(fields: Vec<(String, Box<dyn Function>)>) -> Self {
+        Self { fields }
+    }
+}
+
+impl Function for Object {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
+        self.fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), value.execute(ctx)?)))
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(Value::Map)
+    }
+}
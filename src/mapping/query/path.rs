@@ -1,6 +1,6 @@
-use super::Function;
+use super::{Context, Function};
 use crate::{
-    event::{util::log::get_value, Event, PathIter, Value},
+    event::{util::log::get_value, PathIter, Value},
     mapping::Result,
 };
 use string_cache::DefaultAtom as Atom;
@@ -52,7 +52,7 @@ impl From<Vec<Vec<&str>>> for Path {
 }
 
 impl Function for Path {
-    fn execute(&self, ctx: &Event) -> Result<Value> {
+    fn execute(&self, ctx: &dyn Context) -> Result<Value> {
         // Event.as_log returns a LogEvent struct rather than a naked
         // IndexMap<_, Value>, which means specifically for the first item in
         // the path we need to manually call .get.
@@ -62,7 +62,7 @@ impl Function for Path {
         // the path walker.
         let mut value = self.path[0]
             .iter()
-            .find_map(|p| ctx.as_log().get(p))
+            .find_map(|p| ctx.event().as_log().get(p))
             .ok_or_else(|| format!("path .{} not found in event", self.path[0].first().unwrap()))?;
 
         // Walk remaining (if any) path segments. Our parse is already capable
@@ -103,6 +103,7 @@ impl Function for Path {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::Event;
     use serde_json::json;
 
     #[test]
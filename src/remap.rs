@@ -0,0 +1,319 @@
+use crate::{
+    event::{Event, LogEvent, Metric, Value},
+    mapping::{parser::parse as parse_mapping, Mapping, Outcome},
+};
+use std::{
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Mapping to run against the events.
+    #[structopt(short, long)]
+    mapping: Option<String>,
+
+    /// Read the mapping to run from a file.
+    #[structopt(short = "f", long, parse(from_os_str), conflicts_with = "mapping")]
+    mapping_file: Option<PathBuf>,
+
+    /// Read the events to map from a file rather than stdin, as one JSON object
+    /// per line. Objects encoding a metric, like `{"name": "requests", "kind":
+    /// "absolute", "counter": {"value": 1}}`, are mapped as metrics, any other as
+    /// logs.
+    ///
+    /// If no mapping is given, the events from this file (or a single empty event)
+    /// are mapped interactively instead: each statement entered is applied to them
+    /// and the resulting events are printed. End a line with `\` to continue a
+    /// statement on the next one.
+    #[structopt(short, long, parse(from_os_str))]
+    input: Option<PathBuf>,
+}
+
+/// Maps events one at a time in one-shot mode, failing should every event fail to
+/// be mapped.
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let mapping = match (&opts.mapping, &opts.mapping_file) {
+        (Some(mapping), _) => mapping.clone(),
+        (None, Some(path)) => match fs::read_to_string(path) {
+            Ok(mapping) => mapping,
+            Err(error) => {
+                eprintln!("Couldn't read mapping from {:?}: {}", path, error);
+                return exitcode::NOINPUT;
+            }
+        },
+        (None, None) => return repl(opts.input.as_deref()),
+    };
+
+    let mapping = match parse_mapping(&mapping) {
+        Ok(mapping) => mapping,
+        Err(error) => {
+            eprintln!("{}", error);
+            return exitcode::CONFIG;
+        }
+    };
+
+    let input: Box<dyn BufRead> = match &opts.input {
+        Some(path) => match fs::File::open(path) {
+            Ok(file) => Box::new(io::BufReader::new(file)),
+            Err(error) => {
+                eprintln!("Couldn't open {:?}: {}", path, error);
+                return exitcode::NOINPUT;
+            }
+        },
+        None => Box::new(io::BufReader::new(io::stdin())),
+    };
+
+    let mut events = 0;
+    let mut failed = 0;
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("Couldn't read events: {}", error);
+                return exitcode::IOERR;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        events += 1;
+
+        // Only mapped events go to stdout, so that they can be piped elsewhere.
+        let error = match parse_event(&line) {
+            Ok(mut event) => match mapping.execute(&mut event) {
+                Ok(Outcome::Keep) => {
+                    println!("{}", format_event(event));
+                    continue;
+                }
+                Ok(Outcome::Drop) => {
+                    eprintln!("dropped");
+                    continue;
+                }
+                Err(error) => error,
+            },
+            Err(error) => error,
+        };
+        eprintln!("error: {}", error);
+        failed += 1;
+    }
+
+    if events > 0 && failed == events {
+        exitcode::DATAERR
+    } else {
+        exitcode::OK
+    }
+}
+
+/// Applies every statement entered to the events, keeping those which have been
+/// mapped without errors. Variables assigned by a statement remain available to
+/// the following ones, separately for each event.
+fn repl(input: Option<&Path>) -> exitcode::ExitCode {
+    let events = match input {
+        Some(path) => match read_events(path) {
+            Ok(events) => events,
+            Err(error) => {
+                eprintln!("Couldn't read events from {:?}: {}", path, error);
+                return exitcode::NOINPUT;
+            }
+        },
+        None => vec![Event::from(LogEvent::default())],
+    };
+
+    for event in &events {
+        println!("{}", format_event(event.clone()));
+    }
+    let mut events = events
+        .into_iter()
+        .map(|event| (event, BTreeMap::new()))
+        .collect();
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        let mut statement = String::new();
+        print!("> ");
+        let _ = io::stdout().flush();
+
+        // Read lines until the statement is complete.
+        loop {
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => {
+                    eprintln!("Couldn't read statement: {}", error);
+                    return exitcode::IOERR;
+                }
+                None => return exitcode::OK,
+            };
+            if line.ends_with('\\') {
+                statement.push_str(&line[..line.len() - 1]);
+                statement.push('\n');
+                print!("| ");
+                let _ = io::stdout().flush();
+            } else {
+                statement.push_str(&line);
+                break;
+            }
+        }
+        if statement.trim().is_empty() {
+            continue;
+        }
+
+        match parse_mapping(&statement) {
+            Ok(mapping) => events = apply(&mapping, events),
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+type Variables = BTreeMap<String, Value>;
+
+/// Applies the mapping to each event along with its variables, both of which are
+/// left as they were should the mapping fail.
+fn apply(mapping: &Mapping, events: Vec<(Event, Variables)>) -> Vec<(Event, Variables)> {
+    events
+        .into_iter()
+        .filter_map(|(event, variables)| {
+            let mut mapped = event.clone();
+            let mut assigned = variables.clone();
+            match mapping.execute_with_variables(&mut mapped, &mut assigned) {
+                Ok(Outcome::Keep) => {
+                    println!("{}", format_event(mapped.clone()));
+                    Some((mapped, assigned))
+                }
+                Ok(Outcome::Drop) => {
+                    println!("dropped");
+                    None
+                }
+                Err(error) => {
+                    println!("error: {}", error);
+                    Some((event, variables))
+                }
+            }
+        })
+        .collect()
+}
+
+fn read_events(path: &Path) -> Result<Vec<Event>, String> {
+    fs::read_to_string(path)
+        .map_err(|error| error.to_string())?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_event)
+        .collect()
+}
+
+fn parse_event(line: &str) -> Result<Event, String> {
+    let json = serde_json::from_str::<serde_json::Value>(line)
+        .map_err(|error| format!("invalid event: {}", error))?;
+
+    match serde_json::from_value::<Metric>(json.clone()) {
+        Ok(metric) => Ok(Event::Metric(metric)),
+        Err(_) => LogEvent::try_from(json)
+            .map(Event::from)
+            .map_err(|error| format!("invalid event: {}", error)),
+    }
+}
+
+fn format_event(event: Event) -> String {
+    match event {
+        Event::Log(log) => TryInto::<serde_json::Value>::try_into(log)
+            .map(|json| json.to_string())
+            .unwrap_or_else(|error| format!("error: {}", error)),
+        Event::Metric(metric) => {
+            serde_json::to_string(&metric).unwrap_or_else(|error| format!("error: {}", error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_events() {
+        let mapping = parse_mapping(
+            r#".message = upcase(.message)
+if .drop == true { drop() }"#,
+        )
+        .unwrap();
+        let events = vec![
+            parse_event(r#"{"message": "foo", "drop": false}"#).unwrap(),
+            parse_event(r#"{"message": "bar", "drop": true}"#).unwrap(),
+            parse_event(r#"{"message": 1, "drop": false}"#).unwrap(),
+        ];
+
+        let events = apply(&mapping, with_variables(events));
+        assert_eq!(
+            events
+                .into_iter()
+                .map(|(event, _)| format_event(event))
+                .collect::<Vec<_>>(),
+            vec![
+                r#"{"drop":false,"message":"FOO"}"#,
+                r#"{"drop":false,"message":1}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_variables_between_statements() {
+        let events = with_variables(vec![
+            parse_event(r#"{"message": "foo"}"#).unwrap(),
+            parse_event(r#"{"message": "bar"}"#).unwrap(),
+        ]);
+
+        let events = apply(&parse_mapping("$upper = upcase(.message)").unwrap(), events);
+        // A failing statement leaves the variables assigned so far untouched.
+        let events = apply(&parse_mapping("$upper = upcase(.missing)").unwrap(), events);
+        let events = apply(&parse_mapping(".message = $upper").unwrap(), events);
+        assert_eq!(
+            events
+                .into_iter()
+                .map(|(event, _)| format_event(event))
+                .collect::<Vec<_>>(),
+            vec![r#"{"message":"FOO"}"#, r#"{"message":"BAR"}"#]
+        );
+    }
+
+    #[test]
+    fn maps_metrics() {
+        let mapping = parse_mapping(".tags.host = \"my-host\"").unwrap();
+        let events = with_variables(vec![
+            parse_event(r#"{"name": "requests", "kind": "absolute", "counter": {"value": 1.0}}"#)
+                .unwrap(),
+            parse_event(r#"{"name": "requests"}"#).unwrap(),
+        ]);
+
+        let events = apply(&mapping, events);
+        assert!(matches!(events[0].0, Event::Metric(_)));
+        assert_eq!(
+            events
+                .into_iter()
+                .map(|(event, _)| format_event(event))
+                .collect::<Vec<_>>(),
+            vec![
+                r#"{"name":"requests","timestamp":null,"tags":{"host":"my-host"},"kind":"absolute","counter":{"value":1.0}}"#,
+                r#"{"name":"requests","tags":{"host":"my-host"}}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_events() {
+        assert!(parse_event("[1, 2]").is_err());
+        assert!(parse_event("{").is_err());
+    }
+
+    fn with_variables(events: Vec<Event>) -> Vec<(Event, Variables)> {
+        events
+            .into_iter()
+            .map(|event| (event, BTreeMap::new()))
+            .collect()
+    }
+}
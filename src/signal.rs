@@ -1,4 +1,6 @@
 use futures::Stream;
+use lazy_static::lazy_static;
+use tokio::sync::broadcast;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum SignalTo {
@@ -10,6 +12,16 @@ pub enum SignalTo {
     Quit,
 }
 
+lazy_static! {
+    static ref RAISED: broadcast::Sender<SignalTo> = broadcast::channel(16).0;
+}
+
+/// Raises a signal from within the process, e.g. from the API. It's handled just like
+/// the matching signal from the OS. Returns whether anything is listening for signals.
+pub fn raise(signal: SignalTo) -> bool {
+    RAISED.send(signal).is_ok()
+}
+
 /// Signals from OS/user.
 #[cfg(unix)]
 pub fn signals() -> impl Stream<Item = SignalTo> {
@@ -19,6 +31,7 @@ pub fn signals() -> impl Stream<Item = SignalTo> {
     let mut sigterm = signal(SignalKind::terminate()).expect("Signal handlers should not panic.");
     let mut sigquit = signal(SignalKind::quit()).expect("Signal handlers should not panic.");
     let mut sighup = signal(SignalKind::hangup()).expect("Signal handlers should not panic.");
+    let mut raised = RAISED.subscribe();
 
     async_stream::stream! {
         loop {
@@ -27,6 +40,7 @@ pub fn signals() -> impl Stream<Item = SignalTo> {
                 _ = sigterm.recv() => SignalTo::Shutdown,
                 _ = sigquit.recv() => SignalTo::Quit,
                 _ = sighup.recv() => SignalTo::Reload,
+                Ok(signal) = raised.recv() => signal,
             };
             yield signal;
         }
@@ -38,9 +52,14 @@ pub fn signals() -> impl Stream<Item = SignalTo> {
 pub fn signals() -> impl Stream<Item = SignalTo> {
     use futures::future::FutureExt;
 
+    let mut raised = RAISED.subscribe();
+
     async_stream::stream! {
         loop {
-            let signal = tokio::signal::ctrl_c().map(|_| SignalTo::Shutdown).await;
+            let signal = tokio::select! {
+                signal = tokio::signal::ctrl_c().map(|_| SignalTo::Shutdown) => signal,
+                Ok(signal) = raised.recv() => signal,
+            };
             yield signal;
         }
     }
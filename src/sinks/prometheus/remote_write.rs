@@ -0,0 +1,623 @@
+//! Prometheus remote write sink
+//!
+//! This sink pushes metrics to any service implementing the receiving side of
+//! the Prometheus remote write protocol, such as Cortex, Thanos or
+//! VictoriaMetrics.
+//!
+//! https://prometheus.io/docs/prometheus/latest/storage/#remote-storage-integrations
+//!
+//! Prometheus expects the running totals of counters and histograms, so
+//! incremental metrics are added up into totals, which are kept until their
+//! series stop being updated for `flush_period_secs`.
+
+use super::{default_flush_period_secs, default_histogram_buckets};
+use crate::{
+    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    event::metric::{Metric, MetricValue},
+    sinks::{
+        util::{
+            encode_namespace,
+            http::{Auth, BatchedHttpSink, HttpClient, HttpSink},
+            BatchConfig, BatchSettings, EncodedLength, MetricEntry, TowerRequestConfig, UriSerde,
+            VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{TlsOptions, TlsSettings},
+    Event,
+};
+use chrono::Utc;
+use futures::{future, FutureExt};
+use futures01::Sink;
+use prometheus_parser::proto::{self, metric_metadata::MetricType};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteWriteConfig {
+    pub endpoint: UriSerde,
+    pub namespace: Option<String>,
+    #[serde(default = "default_histogram_buckets")]
+    pub buckets: Vec<f64>,
+    #[serde(default = "default_flush_period_secs")]
+    pub flush_period_secs: u64,
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOptions>,
+}
+
+inventory::submit! {
+    SinkDescription::new_without_default::<RemoteWriteConfig>("prometheus_remote_write")
+}
+
+#[typetag::serde(name = "prometheus_remote_write")]
+impl SinkConfig for RemoteWriteConfig {
+    fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch_settings = BatchSettings::default()
+            .events(1_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(cx.resolver(), tls)?;
+
+        let sink = BatchedHttpSink::new(
+            RemoteWriteSink {
+                config: self.clone(),
+                totals: Mutex::new(Totals::new()),
+            },
+            VecBuffer::new(batch_settings.size),
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(|e| error!("Fatal prometheus_remote_write sink error: {}", e));
+
+        // The remote write protocol has no endpoint to check the health of the
+        // receiving service with.
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((VectorSink::Futures01Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "prometheus_remote_write"
+    }
+}
+
+struct RemoteWriteSink {
+    config: RemoteWriteConfig,
+    totals: Mutex<Totals>,
+}
+
+/// The running totals of incremental metrics, along with when each of them was
+/// last updated.
+struct Totals {
+    metrics: HashMap<MetricEntry, (Metric, Instant)>,
+    last_flush: Instant,
+}
+
+impl Totals {
+    fn new() -> Self {
+        Self {
+            metrics: HashMap::new(),
+            last_flush: Instant::now(),
+        }
+    }
+}
+
+/// The time series a metric is written as, along with its metadata.
+#[derive(Clone, Debug)]
+struct RemoteWriteMetric {
+    series: Vec<proto::TimeSeries>,
+    metadata: proto::MetricMetadata,
+}
+
+impl EncodedLength for RemoteWriteMetric {
+    fn encoded_length(&self) -> usize {
+        self.series.iter().map(Message::encoded_len).sum::<usize>() + self.metadata.encoded_len()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for RemoteWriteSink {
+    type Input = RemoteWriteMetric;
+    type Output = Vec<RemoteWriteMetric>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let metric = match event.into_metric() {
+            metric if metric.value.is_distribution() => {
+                distribution_to_histogram(metric, &self.config.buckets)
+            }
+            metric => metric,
+        };
+        let metric = self.to_absolute(metric);
+
+        Some(encode_metric(self.config.namespace.as_deref(), &metric))
+    }
+
+    async fn build_request(&self, metrics: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        // Metrics of different types may share a name, so the metadata of each
+        // type is kept rather than the last one seen.
+        let mut timeseries = Vec::new();
+        let mut metadata = BTreeMap::new();
+        for metric in metrics {
+            timeseries.extend(metric.series);
+            let key = (
+                metric.metadata.metric_family_name.clone(),
+                metric.metadata.r#type,
+            );
+            metadata.insert(key, metric.metadata);
+        }
+        let request = proto::WriteRequest {
+            timeseries,
+            metadata: metadata.into_iter().map(|(_, metadata)| metadata).collect(),
+        };
+
+        let mut body = Vec::with_capacity(request.encoded_len());
+        request.encode(&mut body)?;
+        let body = snap::raw::Encoder::new().compress_vec(&body)?;
+
+        let mut builder = http::Request::post(http::Uri::from(self.config.endpoint.clone()))
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", "application/x-protobuf")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0");
+        if let Some(tenant_id) = &self.config.tenant_id {
+            builder = builder.header("X-Scope-OrgID", tenant_id);
+        }
+
+        let mut request = builder.body(body)?;
+        if let Some(auth) = &self.config.auth {
+            auth.apply(&mut request);
+        }
+
+        Ok(request)
+    }
+}
+
+impl RemoteWriteSink {
+    /// Adds an incremental metric to the running total of its series, and
+    /// returns that total. Sets and summaries have no meaningful total, and
+    /// are passed through as they are.
+    fn to_absolute(&self, metric: Metric) -> Metric {
+        match metric.value {
+            _ if metric.kind.is_absolute() => metric,
+            MetricValue::Set { .. } | MetricValue::AggregatedSummary { .. } => metric,
+            _ => {
+                let now = Instant::now();
+                let flush_period = Duration::from_secs(self.config.flush_period_secs);
+                let mut totals = self.totals.lock().unwrap();
+
+                // Totals of series which are no longer updated are dropped once
+                // every flush period, as they would otherwise be kept forever.
+                if now.duration_since(totals.last_flush) >= flush_period {
+                    totals
+                        .metrics
+                        .retain(|_, (_, updated)| now.duration_since(*updated) < flush_period);
+                    totals.last_flush = now;
+                }
+
+                let (total, updated) = totals
+                    .metrics
+                    .entry(MetricEntry(metric.clone()))
+                    .or_insert_with(|| {
+                        let mut total = metric.to_absolute();
+                        total.reset();
+                        (total, now)
+                    });
+                total.add(&metric);
+                total.timestamp = metric.timestamp;
+                *updated = now;
+                total.clone()
+            }
+        }
+    }
+}
+
+/// Sorts the values of a distribution into the buckets of a histogram, which
+/// like those of Prometheus count all values less than or equal to their bound.
+fn distribution_to_histogram(metric: Metric, buckets: &[f64]) -> Metric {
+    let Metric {
+        name,
+        timestamp,
+        tags,
+        kind,
+        value,
+    } = metric;
+
+    let value = match value {
+        MetricValue::Distribution {
+            values,
+            sample_rates,
+            ..
+        } => {
+            let mut counts = vec![0; buckets.len()];
+            let mut count = 0;
+            let mut sum = 0.0;
+            for (value, rate) in values.iter().zip(sample_rates.iter()) {
+                for (bucket, bucket_count) in buckets.iter().zip(counts.iter_mut()) {
+                    if value <= bucket {
+                        *bucket_count += rate;
+                    }
+                }
+                count += rate;
+                sum += value * f64::from(*rate);
+            }
+
+            MetricValue::AggregatedHistogram {
+                buckets: buckets.to_vec(),
+                counts,
+                count,
+                sum,
+            }
+        }
+        value => value,
+    };
+
+    Metric {
+        name,
+        timestamp,
+        tags,
+        kind,
+        value,
+    }
+}
+
+/// Writes a metric as Prometheus would expose it. Histograms and summaries
+/// become several series, suffixed with `_bucket`, `_sum` and `_count`.
+fn encode_metric(namespace: Option<&str>, metric: &Metric) -> RemoteWriteMetric {
+    let name = encode_namespace(namespace, '_', &metric.name);
+    let timestamp = metric.timestamp.unwrap_or_else(Utc::now).timestamp_millis();
+
+    let series = |suffix: &str, extra: Option<(&str, String)>, value: f64| {
+        let mut labels = metric.tags.clone().unwrap_or_default();
+        labels.insert("__name__".into(), format!("{}{}", name, suffix));
+        if let Some((label, label_value)) = extra {
+            labels.insert(label.into(), label_value);
+        }
+
+        // Labels are sorted by name, as required by the protocol.
+        proto::TimeSeries {
+            labels: labels
+                .into_iter()
+                .map(|(name, value)| proto::Label { name, value })
+                .collect(),
+            samples: vec![proto::Sample { value, timestamp }],
+        }
+    };
+
+    let (r#type, series) = match &metric.value {
+        MetricValue::Counter { value } => (MetricType::Counter, vec![series("", None, *value)]),
+        MetricValue::Gauge { value } => (MetricType::Gauge, vec![series("", None, *value)]),
+        MetricValue::Set { values } => (
+            MetricType::Gauge,
+            vec![series("", None, values.len() as f64)],
+        ),
+        MetricValue::Distribution { .. } => {
+            unreachable!("distributions are converted into histograms")
+        }
+        MetricValue::AggregatedHistogram {
+            buckets,
+            counts,
+            count,
+            sum,
+        } => {
+            let mut all = buckets
+                .iter()
+                .zip(counts.iter())
+                .map(|(bucket, bucket_count)| {
+                    series(
+                        "_bucket",
+                        Some(("le", bucket.to_string())),
+                        f64::from(*bucket_count),
+                    )
+                })
+                .collect::<Vec<_>>();
+            all.push(series(
+                "_bucket",
+                Some(("le", "+Inf".into())),
+                f64::from(*count),
+            ));
+            all.push(series("_sum", None, *sum));
+            all.push(series("_count", None, f64::from(*count)));
+            (MetricType::Histogram, all)
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            values,
+            count,
+            sum,
+        } => {
+            let mut all = quantiles
+                .iter()
+                .zip(values.iter())
+                .map(|(quantile, value)| {
+                    series("", Some(("quantile", quantile.to_string())), *value)
+                })
+                .collect::<Vec<_>>();
+            all.push(series("_sum", None, *sum));
+            all.push(series("_count", None, f64::from(*count)));
+            (MetricType::Summary, all)
+        }
+    };
+
+    RemoteWriteMetric {
+        series,
+        metadata: proto::MetricMetadata {
+            r#type: r#type as i32,
+            metric_family_name: name,
+            help: String::new(),
+            unit: String::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SinkContext,
+        event::metric::MetricKind,
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::next_addr,
+    };
+    use chrono::TimeZone;
+    use futures::{stream, StreamExt};
+    use pretty_assertions::assert_eq;
+
+    fn metric(kind: MetricKind, value: MetricValue) -> Metric {
+        Metric {
+            name: "requests".into(),
+            timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+            tags: Some(
+                vec![("code".to_owned(), "200".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value,
+        }
+    }
+
+    /// Flattens series into `(labels, value)` pairs, with labels written as
+    /// Prometheus would expose them.
+    fn samples(metric: &RemoteWriteMetric) -> Vec<(String, f64)> {
+        metric
+            .series
+            .iter()
+            .map(|series| {
+                let labels = series
+                    .labels
+                    .iter()
+                    .map(|label| format!("{}={:?}", label.name, label.value))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                assert_eq!(series.samples.len(), 1);
+                assert_eq!(series.samples[0].timestamp, 1_600_000_000_000);
+                (labels, series.samples[0].value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encodes_histograms() {
+        let metric = encode_metric(
+            Some("vector"),
+            &metric(
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vec![0.5, 1.0],
+                    counts: vec![2, 5],
+                    count: 6,
+                    sum: 4.5,
+                },
+            ),
+        );
+
+        assert_eq!(metric.metadata.r#type, MetricType::Histogram as i32);
+        assert_eq!(metric.metadata.metric_family_name, "vector_requests");
+        assert_eq!(
+            samples(&metric),
+            vec![
+                (
+                    r#"__name__="vector_requests_bucket",code="200",le="0.5""#.into(),
+                    2.0
+                ),
+                (
+                    r#"__name__="vector_requests_bucket",code="200",le="1""#.into(),
+                    5.0
+                ),
+                (
+                    r#"__name__="vector_requests_bucket",code="200",le="+Inf""#.into(),
+                    6.0
+                ),
+                (r#"__name__="vector_requests_sum",code="200""#.into(), 4.5),
+                (r#"__name__="vector_requests_count",code="200""#.into(), 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_summaries() {
+        let metric = encode_metric(
+            None,
+            &metric(
+                MetricKind::Absolute,
+                MetricValue::AggregatedSummary {
+                    quantiles: vec![0.5, 0.99],
+                    values: vec![1.5, 3.0],
+                    count: 6,
+                    sum: 12.0,
+                },
+            ),
+        );
+
+        assert_eq!(metric.metadata.r#type, MetricType::Summary as i32);
+        assert_eq!(
+            samples(&metric),
+            vec![
+                (
+                    r#"__name__="requests",code="200",quantile="0.5""#.into(),
+                    1.5
+                ),
+                (
+                    r#"__name__="requests",code="200",quantile="0.99""#.into(),
+                    3.0
+                ),
+                (r#"__name__="requests_sum",code="200""#.into(), 12.0),
+                (r#"__name__="requests_count",code="200""#.into(), 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn sums_incremental_metrics() {
+        let (config, _cx) = load_sink::<RemoteWriteConfig>(
+            r#"
+            endpoint = "http://localhost:9090/api/v1/write"
+            buckets = [1.0, 2.0]
+        "#,
+        )
+        .unwrap();
+        let sink = RemoteWriteSink {
+            config,
+            totals: Mutex::new(Totals::new()),
+        };
+
+        let counter = |value: f64| {
+            Event::Metric(metric(
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            ))
+        };
+        let distribution = |values: Vec<f64>| {
+            Event::Metric(metric(
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    sample_rates: vec![1; values.len()],
+                    values,
+                    statistic: crate::event::metric::StatisticKind::Histogram,
+                },
+            ))
+        };
+
+        let value = |event| samples(&sink.encode_event(event).unwrap());
+        assert_eq!(value(counter(1.0))[0].1, 1.0);
+        assert_eq!(value(counter(2.5))[0].1, 3.5);
+
+        let histogram = value(distribution(vec![0.5, 1.5]))
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        assert_eq!(histogram, vec![1.0, 2.0, 2.0, 2.0, 2.0]);
+        let histogram = value(distribution(vec![3.0]))
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        assert_eq!(histogram, vec![1.0, 2.0, 3.0, 5.0, 3.0]);
+    }
+
+    #[test]
+    fn expires_idle_totals() {
+        let (config, _cx) = load_sink::<RemoteWriteConfig>(
+            r#"
+            endpoint = "http://localhost:9090/api/v1/write"
+            flush_period_secs = 0
+        "#,
+        )
+        .unwrap();
+        let sink = RemoteWriteSink {
+            config,
+            totals: Mutex::new(Totals::new()),
+        };
+
+        let counter = |value: f64| {
+            Event::Metric(metric(
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            ))
+        };
+
+        // Without a flush period, totals are expired before every update.
+        let value = |event| samples(&sink.encode_event(event).unwrap())[0].1;
+        assert_eq!(value(counter(1.0)), 1.0);
+        assert_eq!(value(counter(2.5)), 2.5);
+        assert_eq!(sink.totals.lock().unwrap().metrics.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_to_endpoint() {
+        let addr = next_addr();
+        let config = format!(
+            r#"
+            endpoint = "http://{}/api/v1/write"
+            tenant_id = "tenant-1"
+            auth = { strategy = "bearer", token = "s3cr3t" }
+        "#,
+            addr
+        );
+        let config: RemoteWriteConfig = toml::from_str(&config).unwrap();
+
+        let (sink, _) = config.build(SinkContext::new_test()).unwrap();
+        let (rx, trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = vec![
+            Event::Metric(metric(
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 42.0 },
+            )),
+            Event::Metric(metric(
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 1.5 },
+            )),
+        ];
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let requests = rx.collect::<Vec<_>>().await;
+        assert_eq!(requests.len(), 1);
+        let (parts, body) = &requests[0];
+
+        assert_eq!(parts.method, http::Method::POST);
+        assert_eq!(parts.uri.path(), "/api/v1/write");
+        assert_eq!(parts.headers["Content-Encoding"], "snappy");
+        assert_eq!(parts.headers["X-Scope-OrgID"], "tenant-1");
+        assert_eq!(parts.headers["Authorization"], "Bearer s3cr3t");
+
+        let body = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+        let request = proto::WriteRequest::decode(body.as_slice()).unwrap();
+        let values = request
+            .timeseries
+            .iter()
+            .map(|series| series.samples[0].value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![42.0, 1.5]);
+        assert_eq!(
+            request
+                .metadata
+                .iter()
+                .map(|metadata| (metadata.metric_family_name.as_str(), metadata.r#type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("requests", MetricType::Counter as i32),
+                ("requests", MetricType::Gauge as i32),
+            ]
+        );
+    }
+}
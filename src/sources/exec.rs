@@ -0,0 +1,448 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::Event,
+    internal_events::{ExecCommandExited, ExecEventReceived, ExecFailedToSpawn, ExecReadFailed},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use codec::BytesDelimitedCodec;
+use futures::{
+    compat::{Future01CompatExt, Sink01CompatExt},
+    stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt,
+};
+use futures01::Sink as Sink01;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+use tokio::{
+    process::Command,
+    select,
+    time::{delay_for, delay_until, Instant as TokioInstant},
+};
+use tokio_retry::strategy::ExponentialBackoff;
+use tokio_util::codec::FramedRead;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Run the command every `exec_interval_secs`, waiting for it to exit.
+    Scheduled,
+    /// Run the command once, and again whenever it exits if `respawn_on_exit`
+    /// is set.
+    Streaming,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExecConfig {
+    pub command: Vec<String>,
+    pub mode: Mode,
+    #[serde(default = "default_exec_interval_secs")]
+    pub exec_interval_secs: u64,
+    #[serde(default = "default_respawn_on_exit")]
+    pub respawn_on_exit: bool,
+    pub working_directory: Option<PathBuf>,
+    #[serde(default = "default_include_stderr")]
+    pub include_stderr: bool,
+    #[serde(default)]
+    pub include_exit_event: bool,
+    #[serde(default = "default_max_line_bytes")]
+    pub max_line_bytes: usize,
+    pub host_key: Option<String>,
+}
+
+fn default_exec_interval_secs() -> u64 {
+    60
+}
+
+fn default_respawn_on_exit() -> bool {
+    true
+}
+
+fn default_include_stderr() -> bool {
+    true
+}
+
+fn default_max_line_bytes() -> usize {
+    bytesize::kib(100u64) as usize
+}
+
+const COMMAND_KEY: &str = "command";
+const PID_KEY: &str = "pid";
+const STREAM_KEY: &str = "stream";
+const EXIT_CODE_KEY: &str = "exit_code";
+
+inventory::submit! {
+    SourceDescription::new_without_default::<ExecConfig>("exec")
+}
+
+#[typetag::serde(name = "exec")]
+impl SourceConfig for ExecConfig {
+    fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        if self.command.is_empty() {
+            return Err("'command' must not be empty".into());
+        }
+        if self.mode == Mode::Scheduled && self.exec_interval_secs == 0 {
+            return Err("'exec_interval_secs' must be greater than zero".into());
+        }
+
+        let exec = Exec {
+            config: self.clone(),
+            host_key: self
+                .host_key
+                .clone()
+                .unwrap_or_else(|| log_schema().host_key().to_string()),
+            hostname: crate::get_hostname().ok(),
+        };
+
+        let out = out
+            .sink_map_err(|error| error!(message = "Error sending exec output.", ?error))
+            .sink_compat();
+
+        let fut = async move {
+            match exec.config.mode {
+                Mode::Scheduled => exec.run_scheduled(shutdown, out).await,
+                Mode::Streaming => exec.run_streaming(shutdown, out).await,
+            }
+        };
+
+        Ok(Box::new(fut.boxed().compat()))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "exec"
+    }
+}
+
+struct Exec {
+    config: ExecConfig,
+    host_key: String,
+    hostname: Option<String>,
+}
+
+impl Exec {
+    async fn run_scheduled<O>(&self, shutdown: ShutdownSignal, mut out: O) -> Result<(), ()>
+    where
+        O: Sink<Event, Error = ()> + Unpin,
+    {
+        let interval = Duration::from_secs(self.config.exec_interval_secs);
+
+        while !is_shutting_down(&shutdown) {
+            let started = Instant::now();
+            self.run_command(shutdown.clone(), &mut out).await?;
+
+            // Waiting from the start of the last run keeps a steady schedule,
+            // while never running the command twice at the same time.
+            select! {
+                _ = delay_until(TokioInstant::from_std(started + interval)) => {},
+                _ = shutdown.clone().compat() => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_streaming<O>(&self, shutdown: ShutdownSignal, mut out: O) -> Result<(), ()>
+    where
+        O: Sink<Event, Error = ()> + Unpin,
+    {
+        let mut backoff = fresh_backoff();
+
+        loop {
+            let started = Instant::now();
+            self.run_command(shutdown.clone(), &mut out).await?;
+
+            if !self.config.respawn_on_exit || is_shutting_down(&shutdown) {
+                break;
+            }
+
+            // Commands that ran for a while are restarted right away, while
+            // commands that keep failing are restarted less and less often.
+            let delay = if started.elapsed() > Duration::from_secs(60) {
+                backoff = fresh_backoff();
+                Duration::from_secs(0)
+            } else {
+                backoff.next().unwrap()
+            };
+
+            select! {
+                _ = delay_for(delay) => {},
+                _ = shutdown.clone().compat() => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the command once, sending each line it outputs as an event to
+    /// `out`, until it exits or shutdown begins. Once the command exits, an
+    /// event without output carrying its exit code follows if
+    /// `include_exit_event` is set.
+    ///
+    /// Returns the exit status, if the command could be run to completion.
+    async fn run_command<O>(
+        &self,
+        shutdown: ShutdownSignal,
+        out: &mut O,
+    ) -> Result<Option<ExitStatus>, ()>
+    where
+        O: Sink<Event, Error = ()> + Unpin,
+    {
+        let command = self.config.command.join(" ");
+
+        let mut builder = Command::new(&self.config.command[0]);
+        builder
+            .args(&self.config.command[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(if self.config.include_stderr {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .kill_on_drop(true);
+        if let Some(directory) = &self.config.working_directory {
+            builder.current_dir(directory);
+        }
+
+        let started = Instant::now();
+        let mut child = match builder.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                emit!(ExecFailedToSpawn {
+                    command: &command,
+                    error,
+                });
+                return Ok(None);
+            }
+        };
+        let pid = child.id();
+
+        let decoder =
+            || BytesDelimitedCodec::new_with_max_length(b'\n', self.config.max_line_bytes);
+        let stdout = child
+            .stdout
+            .take()
+            .map(|stdout| FramedRead::new(stdout, decoder()).map(|line| ("stdout", line)));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| FramedRead::new(stderr, decoder()).map(|line| ("stderr", line)));
+
+        let mut lines = stream::select(
+            stream::iter(stdout).flatten(),
+            stream::iter(stderr).flatten(),
+        )
+        .take_until(shutdown.clone().compat());
+
+        while let Some((stream, line)) = lines.next().await {
+            match line {
+                Ok(line) => {
+                    emit!(ExecEventReceived {
+                        command: &command,
+                        byte_size: line.len(),
+                    });
+                    let mut event = self.create_event(line, &command, pid);
+                    event.as_mut_log().insert(STREAM_KEY, stream);
+                    out.send(event).await?;
+                }
+                Err(error) => emit!(ExecReadFailed {
+                    command: &command,
+                    error,
+                }),
+            }
+        }
+
+        // Dropping the child kills it, should shutdown begin before it exits.
+        let status = select! {
+            status = &mut child => status.ok(),
+            _ = shutdown.compat() => None,
+        };
+
+        emit!(ExecCommandExited {
+            command: &command,
+            exit_status: status.and_then(|status| status.code()),
+            elapsed: started.elapsed(),
+        });
+
+        // Commands killed by a signal have no exit code to report.
+        let code = status.and_then(|status| status.code());
+        if let (Some(code), true) = (code, self.config.include_exit_event) {
+            let mut event = self.create_event(Bytes::new(), &command, pid);
+            event.as_mut_log().insert(EXIT_CODE_KEY, code as i64);
+            out.send(event).await?;
+        }
+
+        Ok(status)
+    }
+
+    fn create_event(&self, message: Bytes, command: &str, pid: u32) -> Event {
+        let mut event = Event::from(message);
+        let log = event.as_mut_log();
+
+        log.insert(log_schema().source_type_key(), Bytes::from("exec"));
+        log.insert(COMMAND_KEY, command.to_string());
+        log.insert(PID_KEY, pid as i64);
+        if let Some(hostname) = &self.hostname {
+            log.insert(self.host_key.as_str(), hostname.clone());
+        }
+
+        event
+    }
+}
+
+fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+fn is_shutting_down(shutdown: &ShutdownSignal) -> bool {
+    shutdown.clone().compat().now_or_never().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_n, collect_ready, trace_init};
+
+    fn config(mode: Mode, command: &str) -> ExecConfig {
+        ExecConfig {
+            command: vec!["sh".to_string(), "-c".to_string(), command.to_string()],
+            mode,
+            exec_interval_secs: default_exec_interval_secs(),
+            respawn_on_exit: false,
+            working_directory: None,
+            include_stderr: true,
+            include_exit_event: false,
+            max_line_bytes: default_max_line_bytes(),
+            host_key: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn exec_streaming() {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let config = config(Mode::Streaming, "echo hello; echo world");
+
+        config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .unwrap()
+            .compat()
+            .await
+            .unwrap();
+
+        let events = collect_ready(rx).await.unwrap();
+        assert_eq!(events.len(), 2);
+
+        let log = events[0].as_log();
+        assert_eq!(log[&log_schema().message_key()], "hello".into());
+        assert_eq!(log[log_schema().source_type_key()], "exec".into());
+        assert_eq!(
+            log[&COMMAND_KEY.into()],
+            "sh -c echo hello; echo world".into()
+        );
+        assert_eq!(log[&STREAM_KEY.into()], "stdout".into());
+        assert!(log.contains(&PID_KEY.into()));
+        assert!(!log.contains(&EXIT_CODE_KEY.into()));
+        assert_eq!(
+            events[1].as_log()[&log_schema().message_key()],
+            "world".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn exec_scheduled() {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let mut config = config(Mode::Scheduled, "echo out; echo err >&2; exit 3");
+        config.include_exit_event = true;
+
+        let source = config
+            .build("default", &GlobalOptions::default(), shutdown, tx)
+            .unwrap();
+        let source = tokio::spawn(source.compat());
+
+        let mut events = collect_n(rx, 3).await.unwrap();
+        drop(trigger);
+        source.await.unwrap().unwrap();
+
+        // The exit event comes last, while the output streams may interleave.
+        let exit = events.pop().unwrap();
+        assert_eq!(exit.as_log()[&log_schema().message_key()], "".into());
+        assert_eq!(exit.as_log()[&EXIT_CODE_KEY.into()], 3.into());
+        assert!(!exit.as_log().contains(&STREAM_KEY.into()));
+
+        events.sort_by_key(|event| event.as_log()[&STREAM_KEY.into()].to_string_lossy());
+        let err = events[0].as_log();
+        assert_eq!(err[&log_schema().message_key()], "err".into());
+        assert_eq!(err[&STREAM_KEY.into()], "stderr".into());
+        assert!(!err.contains(&EXIT_CODE_KEY.into()));
+        let out = events[1].as_log();
+        assert_eq!(out[&log_schema().message_key()], "out".into());
+        assert_eq!(out[&STREAM_KEY.into()], "stdout".into());
+        assert!(!out.contains(&EXIT_CODE_KEY.into()));
+    }
+
+    #[test]
+    fn exec_rejects_zero_interval() {
+        let (tx, _rx) = Pipeline::new_test();
+        let mut config = config(Mode::Scheduled, "echo hello");
+        config.exec_interval_secs = 0;
+
+        assert!(config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx
+            )
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn exec_command_not_found() {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let mut config = config(Mode::Streaming, "");
+        config.command = vec!["this-command-does-not-exist".to_string()];
+
+        config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .unwrap()
+            .compat()
+            .await
+            .unwrap();
+
+        assert!(collect_ready(rx).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,666 @@
+use crate::{
+    config::{DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    internal_events::{HostMetricsCollectError, HostMetricsEventReceived},
+    shutdown::ShutdownSignal,
+    Event, Pipeline,
+};
+use chrono::Utc;
+use futures::{
+    compat::{Future01CompatExt, Sink01CompatExt},
+    future::{FutureExt, TryFutureExt},
+    stream, SinkExt, StreamExt,
+};
+use futures01::Sink;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
+
+/// Block device statistics in `/sys` are always counted in 512 byte sectors,
+/// whatever the actual sector size of the device.
+const SECTOR_SIZE: f64 = 512.0;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Collector {
+    Cpu,
+    Disk,
+    Filesystem,
+    Load,
+    Memory,
+    Network,
+}
+
+impl Collector {
+    fn as_str(self) -> &'static str {
+        match self {
+            Collector::Cpu => "cpu",
+            Collector::Disk => "disk",
+            Collector::Filesystem => "filesystem",
+            Collector::Load => "load",
+            Collector::Memory => "memory",
+            Collector::Network => "network",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct HostMetricsConfig {
+    collectors: Vec<Collector>,
+    scrape_interval_secs: u64,
+    namespace: String,
+    procfs_root: PathBuf,
+    sysfs_root: PathBuf,
+}
+
+impl Default for HostMetricsConfig {
+    fn default() -> Self {
+        Self {
+            collectors: vec![
+                Collector::Cpu,
+                Collector::Disk,
+                Collector::Filesystem,
+                Collector::Load,
+                Collector::Memory,
+                Collector::Network,
+            ],
+            scrape_interval_secs: 15,
+            namespace: "host".to_string(),
+            procfs_root: PathBuf::from("/proc"),
+            sysfs_root: PathBuf::from("/sys"),
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<HostMetricsConfig>("host_metrics")
+}
+
+#[typetag::serde(name = "host_metrics")]
+impl SourceConfig for HostMetricsConfig {
+    fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        if self.scrape_interval_secs == 0 {
+            return Err("'scrape_interval_secs' must be greater than zero".into());
+        }
+
+        let ticks_per_second = nix::unistd::sysconf(nix::unistd::SysconfVar::CLK_TCK)?
+            .ok_or("unable to determine the number of clock ticks per second")?;
+
+        let host_metrics = HostMetrics {
+            config: self.clone(),
+            hostname: crate::get_hostname().ok(),
+            ticks_per_second: ticks_per_second as f64,
+        };
+
+        Ok(Box::new(host_metrics.run(out, shutdown).boxed().compat()))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "host_metrics"
+    }
+}
+
+struct HostMetrics {
+    config: HostMetricsConfig,
+    hostname: Option<String>,
+    ticks_per_second: f64,
+}
+
+impl HostMetrics {
+    async fn run(self, out: Pipeline, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let host_metrics = Arc::new(self);
+        let mut out = out
+            .sink_map_err(|error| error!(message = "Error sending host metrics.", ?error))
+            .sink_compat();
+
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            host_metrics.config.scrape_interval_secs,
+        ))
+        .take_until(shutdown.compat());
+
+        while interval.next().await.is_some() {
+            // Reading `/proc` and `/sys` and inspecting mounts can block, e.g. on a
+            // hung network filesystem, so it's kept off the runtime's workers.
+            let host_metrics = Arc::clone(&host_metrics);
+            let metrics = spawn_blocking(move || host_metrics.capture_metrics())
+                .await
+                .map_err(|error| error!(message = "Failed to collect host metrics.", %error))?;
+            emit!(HostMetricsEventReceived {
+                count: metrics.len()
+            });
+
+            out.send_all(&mut stream::iter(metrics).map(Event::Metric).map(Ok))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn capture_metrics(&self) -> Vec<Metric> {
+        let mut metrics = Vec::new();
+        for &collector in &self.config.collectors {
+            let result = match collector {
+                Collector::Cpu => self.cpu_metrics(),
+                Collector::Disk => self.disk_metrics(),
+                Collector::Filesystem => self.filesystem_metrics(),
+                Collector::Load => self.load_metrics(),
+                Collector::Memory => self.memory_metrics(),
+                Collector::Network => self.network_metrics(),
+            };
+
+            match result {
+                Ok(collected) => metrics.extend(collected),
+                Err(error) => emit!(HostMetricsCollectError {
+                    collector: collector.as_str(),
+                    error,
+                }),
+            }
+        }
+
+        // All metrics are namespaced, share the same timestamp and are tagged
+        // with the host they were collected on.
+        let timestamp = Utc::now();
+        for metric in &mut metrics {
+            if !self.config.namespace.is_empty() {
+                metric.name = format!("{}_{}", self.config.namespace, metric.name);
+            }
+            metric.timestamp = Some(timestamp);
+            if let Some(hostname) = &self.hostname {
+                metric
+                    .tags
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert("host".to_string(), hostname.clone());
+            }
+        }
+
+        metrics
+    }
+
+    fn cpu_metrics(&self) -> io::Result<Vec<Metric>> {
+        let stat = fs::read_to_string(self.config.procfs_root.join("stat"))?;
+        Ok(parse_cpu_stat(&stat, self.ticks_per_second))
+    }
+
+    fn disk_metrics(&self) -> io::Result<Vec<Metric>> {
+        // Only whole devices are listed in `/sys/block`, so that partitions
+        // aren't counted twice.
+        let mut metrics = Vec::new();
+        for entry in fs::read_dir(self.config.sysfs_root.join("block"))? {
+            let path = entry?.path();
+            let device = match path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            // Devices that can't be inspected, like ones that went away in the
+            // meantime, are skipped rather than failing the whole collector.
+            let stat = match fs::read_to_string(path.join("stat")) {
+                Ok(stat) => stat,
+                Err(_) => continue,
+            };
+            metrics.extend(parse_block_stat(&device, &stat));
+        }
+        Ok(metrics)
+    }
+
+    fn filesystem_metrics(&self) -> io::Result<Vec<Metric>> {
+        let mounts = fs::read_to_string(self.config.procfs_root.join("mounts"))?;
+
+        let mut metrics = Vec::new();
+        for mount in parse_mounts(&mounts) {
+            // Mounts that can't be inspected, like ones that went away in the
+            // meantime, are skipped rather than failing the whole collector.
+            let stat = match nix::sys::statvfs::statvfs(Path::new(&mount.mountpoint)) {
+                Ok(stat) => stat,
+                Err(_) => continue,
+            };
+
+            let fragment_size = stat.fragment_size() as f64;
+            let total = stat.blocks() as f64 * fragment_size;
+            let free = stat.blocks_free() as f64 * fragment_size;
+            let available = stat.blocks_available() as f64 * fragment_size;
+
+            let tags = tags(&[
+                ("device", mount.device.as_str()),
+                ("mountpoint", mount.mountpoint.as_str()),
+                ("filesystem", mount.filesystem.as_str()),
+            ]);
+            metrics.push(gauge("filesystem_total_bytes", total, tags.clone()));
+            metrics.push(gauge("filesystem_free_bytes", free, tags.clone()));
+            metrics.push(gauge("filesystem_available_bytes", available, tags.clone()));
+            metrics.push(gauge("filesystem_used_bytes", total - free, tags));
+        }
+        Ok(metrics)
+    }
+
+    fn load_metrics(&self) -> io::Result<Vec<Metric>> {
+        let loadavg = fs::read_to_string(self.config.procfs_root.join("loadavg"))?;
+        Ok(parse_loadavg(&loadavg))
+    }
+
+    fn memory_metrics(&self) -> io::Result<Vec<Metric>> {
+        let meminfo = fs::read_to_string(self.config.procfs_root.join("meminfo"))?;
+        Ok(parse_meminfo(&meminfo))
+    }
+
+    fn network_metrics(&self) -> io::Result<Vec<Metric>> {
+        let dev = fs::read_to_string(self.config.procfs_root.join("net/dev"))?;
+        Ok(parse_net_dev(&dev))
+    }
+}
+
+fn tags(tags: &[(&str, &str)]) -> Option<BTreeMap<String, String>> {
+    Some(
+        tags.iter()
+            .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+            .collect(),
+    )
+}
+
+fn counter(name: &str, value: f64, tags: Option<BTreeMap<String, String>>) -> Metric {
+    Metric {
+        name: name.to_string(),
+        timestamp: None,
+        tags,
+        kind: MetricKind::Absolute,
+        value: MetricValue::Counter { value },
+    }
+}
+
+fn gauge(name: &str, value: f64, tags: Option<BTreeMap<String, String>>) -> Metric {
+    Metric {
+        name: name.to_string(),
+        timestamp: None,
+        tags,
+        kind: MetricKind::Absolute,
+        value: MetricValue::Gauge { value },
+    }
+}
+
+/// Parses the per CPU lines of `/proc/stat`, which count the time spent in
+/// each mode in clock ticks.
+fn parse_cpu_stat(stat: &str, ticks_per_second: f64) -> Vec<Metric> {
+    const MODES: &[&str] = &[
+        "user", "nice", "system", "idle", "iowait", "irq", "softirq", "steal",
+    ];
+
+    let mut metrics = Vec::new();
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        let cpu = match fields.next() {
+            Some(name) if name.starts_with("cpu") && name.len() > 3 => &name[3..],
+            _ => continue,
+        };
+
+        for (mode, ticks) in MODES.iter().zip(fields) {
+            if let Ok(ticks) = ticks.parse::<f64>() {
+                metrics.push(counter(
+                    "cpu_seconds_total",
+                    ticks / ticks_per_second,
+                    tags(&[("cpu", cpu), ("mode", *mode)]),
+                ));
+            }
+        }
+    }
+    metrics
+}
+
+/// Parses a `/sys/block/<device>/stat` file.
+fn parse_block_stat(device: &str, stat: &str) -> Vec<Metric> {
+    let fields = stat
+        .split_whitespace()
+        .map(|field| field.parse::<f64>().ok())
+        .collect::<Vec<_>>();
+
+    let field = |index: usize| fields.get(index).cloned().flatten();
+    let tags = tags(&[("device", device)]);
+
+    let mut metrics = Vec::new();
+    let mut push = |name: &str, value: Option<f64>| {
+        if let Some(value) = value {
+            metrics.push(counter(name, value, tags.clone()));
+        }
+    };
+    push("disk_reads_completed_total", field(0));
+    push("disk_read_bytes_total", field(2).map(|s| s * SECTOR_SIZE));
+    push("disk_writes_completed_total", field(4));
+    push(
+        "disk_written_bytes_total",
+        field(6).map(|s| s * SECTOR_SIZE),
+    );
+    push("disk_io_time_seconds_total", field(9).map(|ms| ms / 1000.0));
+    metrics
+}
+
+#[derive(Debug, PartialEq)]
+struct Mount {
+    device: String,
+    mountpoint: String,
+    filesystem: String,
+}
+
+/// Parses `/proc/mounts`, keeping only filesystems backed by a device.
+fn parse_mounts(mounts: &str) -> Vec<Mount> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            let mountpoint = fields.next()?;
+            let filesystem = fields.next()?;
+
+            if !device.starts_with('/') {
+                return None;
+            }
+
+            Some(Mount {
+                device: unescape_mount_field(device),
+                mountpoint: unescape_mount_field(mountpoint),
+                filesystem: filesystem.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whitespace and backslashes in mount fields are escaped as octal, such as
+/// `\040` for a space.
+fn unescape_mount_field(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        let escaped = rest.get(index + 1..index + 4);
+        match escaped.and_then(|octal| u8::from_str_radix(octal, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn parse_loadavg(loadavg: &str) -> Vec<Metric> {
+    ["load1", "load5", "load15"]
+        .iter()
+        .zip(loadavg.split_whitespace())
+        .filter_map(|(name, value)| Some(gauge(name, value.parse().ok()?, None)))
+        .collect()
+}
+
+/// Parses `/proc/meminfo`, which lists sizes in kibibytes.
+fn parse_meminfo(meminfo: &str) -> Vec<Metric> {
+    meminfo
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = match fields.next()? {
+                "MemTotal:" => "memory_total_bytes",
+                "MemFree:" => "memory_free_bytes",
+                "MemAvailable:" => "memory_available_bytes",
+                "Buffers:" => "memory_buffers_bytes",
+                "Cached:" => "memory_cached_bytes",
+                "SwapTotal:" => "memory_swap_total_bytes",
+                "SwapFree:" => "memory_swap_free_bytes",
+                _ => return None,
+            };
+            let kibibytes = fields.next()?.parse::<f64>().ok()?;
+            Some(gauge(name, kibibytes * 1024.0, None))
+        })
+        .collect()
+}
+
+/// Parses `/proc/net/dev`, which has two header lines followed by one line of
+/// receive and transmit counters per interface.
+fn parse_net_dev(dev: &str) -> Vec<Metric> {
+    const COUNTERS: &[(usize, &str)] = &[
+        (0, "network_receive_bytes_total"),
+        (1, "network_receive_packets_total"),
+        (2, "network_receive_errs_total"),
+        (3, "network_receive_drop_total"),
+        (8, "network_transmit_bytes_total"),
+        (9, "network_transmit_packets_total"),
+        (10, "network_transmit_errs_total"),
+        (11, "network_transmit_drop_total"),
+    ];
+
+    let mut metrics = Vec::new();
+    for line in dev.lines().skip(2) {
+        let mut parts = line.splitn(2, ':');
+        let (device, counters) = match (parts.next(), parts.next()) {
+            (Some(device), Some(counters)) => (device.trim(), counters),
+            _ => continue,
+        };
+
+        let fields = counters.split_whitespace().collect::<Vec<_>>();
+        for (index, name) in COUNTERS {
+            if let Some(value) = fields.get(*index).and_then(|v| v.parse::<f64>().ok()) {
+                metrics.push(counter(name, value, tags(&[("device", device)])));
+            }
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn find<'a>(metrics: &'a [Metric], name: &str, tags: &[(&str, &str)]) -> &'a MetricValue {
+        &metrics
+            .iter()
+            .find(|metric| {
+                metric.name == name
+                    && tags.iter().all(|(key, value)| {
+                        metric.tags.as_ref().and_then(|tags| tags.get(*key))
+                            == Some(&value.to_string())
+                    })
+            })
+            .unwrap_or_else(|| panic!("metric {} {:?} not found", name, tags))
+            .value
+    }
+
+    #[test]
+    fn parses_cpu_stat() {
+        let stat = "cpu  2255 34 2290 22625563 6290 127 456 0 0 0\n\
+                    cpu0 1132 34 1441 11311718 3675 127 438 0 0 0\n\
+                    cpu1 1123 0 849 11313845 2614 0 18 0 0 0\n\
+                    intr 114930548 113199788 3 0 5 263 0 4 [...]\n\
+                    ctxt 1990473\n";
+
+        let metrics = parse_cpu_stat(stat, 100.0);
+        assert_eq!(metrics.len(), 16);
+        assert_eq!(
+            find(
+                &metrics,
+                "cpu_seconds_total",
+                &[("cpu", "0"), ("mode", "user")]
+            ),
+            &MetricValue::Counter { value: 11.32 }
+        );
+        assert_eq!(
+            find(
+                &metrics,
+                "cpu_seconds_total",
+                &[("cpu", "1"), ("mode", "idle")]
+            ),
+            &MetricValue::Counter { value: 113138.45 }
+        );
+    }
+
+    #[test]
+    fn parses_block_stat() {
+        let stat = "  214523    72781 15311522   104764   397318   386476 16436856   555672        0   364976   672252\n";
+
+        let metrics = parse_block_stat("sda", stat);
+        assert_eq!(metrics.len(), 5);
+        assert_eq!(
+            find(&metrics, "disk_read_bytes_total", &[("device", "sda")]),
+            &MetricValue::Counter {
+                value: 15_311_522.0 * 512.0
+            }
+        );
+        assert_eq!(
+            find(
+                &metrics,
+                "disk_writes_completed_total",
+                &[("device", "sda")]
+            ),
+            &MetricValue::Counter { value: 397_318.0 }
+        );
+        assert_eq!(
+            find(&metrics, "disk_io_time_seconds_total", &[("device", "sda")]),
+            &MetricValue::Counter { value: 364.976 }
+        );
+    }
+
+    #[test]
+    fn parses_mounts() {
+        let mounts = "sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0\n\
+                      /dev/sda1 / ext4 rw,relatime 0 0\n\
+                      /dev/sdb1 /mnt/my\\040disk xfs rw,relatime 0 0\n";
+
+        assert_eq!(
+            parse_mounts(mounts),
+            vec![
+                Mount {
+                    device: "/dev/sda1".to_string(),
+                    mountpoint: "/".to_string(),
+                    filesystem: "ext4".to_string(),
+                },
+                Mount {
+                    device: "/dev/sdb1".to_string(),
+                    mountpoint: "/mnt/my disk".to_string(),
+                    filesystem: "xfs".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_net_dev() {
+        let dev = "Inter-|   Receive                                                |  Transmit\n \
+                   face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+                   lo:  123456     789    0    0    0     0          0         0   123456     789    0    0    0     0       0          0\n  \
+                   eth0: 9876543   6543    1    2    0     0          0         0  1234567   4321    3    4    0     0       0          0\n";
+
+        let metrics = parse_net_dev(dev);
+        assert_eq!(metrics.len(), 16);
+        assert_eq!(
+            find(
+                &metrics,
+                "network_receive_bytes_total",
+                &[("device", "eth0")]
+            ),
+            &MetricValue::Counter { value: 9_876_543.0 }
+        );
+        assert_eq!(
+            find(
+                &metrics,
+                "network_transmit_drop_total",
+                &[("device", "eth0")]
+            ),
+            &MetricValue::Counter { value: 4.0 }
+        );
+    }
+
+    #[test]
+    fn captures_metrics_from_procfs_root() {
+        let procfs_root = temp_dir();
+        fs::create_dir_all(&procfs_root).unwrap();
+        fs::write(procfs_root.join("loadavg"), "0.52 0.58 0.59 1/467 12345\n").unwrap();
+        fs::write(
+            procfs_root.join("meminfo"),
+            "MemTotal:       16303428 kB\nMemFree:         1187044 kB\nHugePages_Total:       0\n",
+        )
+        .unwrap();
+
+        let host_metrics = HostMetrics {
+            config: HostMetricsConfig {
+                collectors: vec![Collector::Load, Collector::Memory, Collector::Network],
+                procfs_root,
+                ..Default::default()
+            },
+            hostname: Some("my-host".to_string()),
+            ticks_per_second: 100.0,
+        };
+
+        // The missing `net/dev` file fails the network collector only.
+        let metrics = host_metrics.capture_metrics();
+        assert_eq!(metrics.len(), 5);
+        assert!(metrics.iter().all(|metric| metric.timestamp.is_some()));
+        assert_eq!(
+            find(&metrics, "host_load5", &[("host", "my-host")]),
+            &MetricValue::Gauge { value: 0.58 }
+        );
+        assert_eq!(
+            find(&metrics, "host_memory_total_bytes", &[("host", "my-host")]),
+            &MetricValue::Gauge {
+                value: 16_303_428.0 * 1024.0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_zero_scrape_interval() {
+        let config = HostMetricsConfig {
+            scrape_interval_secs: 0,
+            ..Default::default()
+        };
+        let (tx, _rx) = Pipeline::new_test();
+
+        assert!(config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn skips_unreadable_disks() {
+        let sysfs_root = temp_dir();
+        let stat = "  214523    72781 15311522   104764   397318   386476 16436856   555672        0   364976   672252\n";
+        fs::create_dir_all(sysfs_root.join("block/sda")).unwrap();
+        fs::write(sysfs_root.join("block/sda/stat"), stat).unwrap();
+        fs::create_dir_all(sysfs_root.join("block/sdb")).unwrap();
+
+        let host_metrics = HostMetrics {
+            config: HostMetricsConfig {
+                sysfs_root,
+                ..Default::default()
+            },
+            hostname: None,
+            ticks_per_second: 100.0,
+        };
+
+        let metrics = host_metrics.disk_metrics().unwrap();
+        assert_eq!(metrics.len(), 5);
+        assert!(metrics.iter().all(|metric| {
+            metric.tags.as_ref().unwrap().get("device") == Some(&"sda".to_string())
+        }));
+    }
+}
@@ -5,10 +5,14 @@ use snafu::Snafu;
 pub mod apache_metrics;
 #[cfg(feature = "sources-docker")]
 pub mod docker;
+#[cfg(all(unix, feature = "sources-exec"))]
+pub mod exec;
 #[cfg(feature = "sources-file")]
 pub mod file;
 #[cfg(feature = "sources-generator")]
 pub mod generator;
+#[cfg(all(target_os = "linux", feature = "sources-host_metrics"))]
+pub mod host_metrics;
 #[cfg(feature = "sources-http")]
 pub mod http;
 #[cfg(feature = "sources-internal_metrics")]
@@ -36,7 +40,7 @@ pub mod syslog;
 #[cfg(feature = "sources-vector")]
 pub mod vector;
 
-mod util;
+pub(crate) mod util;
 
 pub type Source = Box<dyn Future<Item = (), Error = ()> + Send>;
 
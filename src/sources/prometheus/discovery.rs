@@ -0,0 +1,265 @@
+use super::Target;
+use crate::internal_events::PrometheusFileSdError;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FileSdConfig {
+    pub files: Vec<String>,
+    #[serde(default = "default_scheme")]
+    pub scheme: String,
+    #[serde(default = "default_metrics_path")]
+    pub metrics_path: String,
+}
+
+fn default_scheme() -> String {
+    "http".to_string()
+}
+
+fn default_metrics_path() -> String {
+    "/metrics".to_string()
+}
+
+/// A group of targets sharing the same labels, as listed in a Prometheus
+/// target file.
+#[derive(Deserialize, Debug)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Snafu)]
+pub enum FileSdError {
+    #[snafu(display("Invalid file pattern {:?}: {}", pattern, source))]
+    InvalidPattern {
+        pattern: String,
+        source: glob::PatternError,
+    },
+    #[snafu(display("Could not read target file: {}", source))]
+    ReadFile { source: std::io::Error },
+    #[snafu(display("Could not parse JSON target file: {}", source))]
+    ParseJson { source: serde_json::Error },
+    #[snafu(display("Could not parse YAML target file: {}", source))]
+    ParseYaml { source: serde_yaml::Error },
+    #[snafu(display("Invalid target {:?}: {}", target, source))]
+    InvalidTarget {
+        target: String,
+        source: http::uri::InvalidUri,
+    },
+}
+
+/// Finds scrape targets in the files matching a `FileSdConfig`, keeping track
+/// of the modification time and size of each file when it was last read, so
+/// that only changed files are read again.
+pub struct FileDiscovery {
+    config: FileSdConfig,
+    files: BTreeMap<PathBuf, ((SystemTime, u64), Vec<Target>)>,
+}
+
+impl FileDiscovery {
+    pub fn new(config: FileSdConfig) -> Result<Self, FileSdError> {
+        for pattern in &config.files {
+            glob::Pattern::new(pattern).context(InvalidPattern { pattern })?;
+        }
+
+        Ok(Self {
+            config,
+            files: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the targets listed in all matching files. A file that can no
+    /// longer be read or parsed keeps the targets it last listed.
+    pub fn targets(&mut self) -> Vec<Target> {
+        let mut files = BTreeMap::new();
+
+        let paths = self
+            .config
+            .files
+            .iter()
+            .filter_map(|pattern| glob::glob(pattern).ok())
+            .flatten()
+            .filter_map(Result::ok);
+        for path in paths {
+            let previous = self.files.remove(&path);
+            let version = match fs::metadata(&path)
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+            {
+                Ok(version) => version,
+                Err(error) => {
+                    emit!(PrometheusFileSdError {
+                        path: &path,
+                        error: FileSdError::ReadFile { source: error },
+                    });
+                    files.extend(previous.map(|previous| (path, previous)));
+                    continue;
+                }
+            };
+
+            match previous {
+                Some((read, targets)) if read == version => {
+                    files.insert(path, (read, targets));
+                }
+                previous => match self.read_file(&path) {
+                    Ok(targets) => {
+                        files.insert(path, (version, targets));
+                    }
+                    Err(error) => {
+                        emit!(PrometheusFileSdError { path: &path, error });
+                        files.extend(previous.map(|previous| (path, previous)));
+                    }
+                },
+            }
+        }
+
+        self.files = files;
+        self.files
+            .values()
+            .flat_map(|(_, targets)| targets.iter().cloned())
+            .collect()
+    }
+
+    /// Reads the targets listed in a file. As with Prometheus, files ending in
+    /// `.json` are JSON, and any other file is YAML.
+    fn read_file(&self, path: &Path) -> Result<Vec<Target>, FileSdError> {
+        let contents = fs::read(path).context(ReadFile)?;
+        let groups: Vec<TargetGroup> = match path.extension() {
+            Some(extension) if extension == "json" => {
+                serde_json::from_slice(&contents).context(ParseJson)?
+            }
+            _ => serde_yaml::from_slice(&contents).context(ParseYaml)?,
+        };
+
+        let mut targets = Vec::new();
+        for group in groups {
+            for address in group.targets {
+                targets.push(self.target(address, &group.labels)?);
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Builds a target from its address and labels. Labels starting with `__`
+    /// are not attached to metrics, though `__scheme__` and `__metrics_path__`
+    /// override how the target is scraped.
+    fn target(
+        &self,
+        address: String,
+        labels: &BTreeMap<String, String>,
+    ) -> Result<Target, FileSdError> {
+        let scheme = labels.get("__scheme__").unwrap_or(&self.config.scheme);
+        let metrics_path = labels
+            .get("__metrics_path__")
+            .unwrap_or(&self.config.metrics_path);
+
+        let url = format!("{}://{}{}", scheme, address, metrics_path);
+        url.parse::<http::Uri>()
+            .context(InvalidTarget { target: &address })?;
+
+        let mut target_labels = BTreeMap::new();
+        target_labels.insert("instance".to_string(), address);
+        target_labels.extend(
+            labels
+                .iter()
+                .filter(|(name, _)| !name.starts_with("__"))
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+
+        Ok(Target {
+            url,
+            labels: target_labels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn discovery(dir: &Path) -> FileDiscovery {
+        FileDiscovery::new(FileSdConfig {
+            files: vec![format!("{}/*", dir.display())],
+            scheme: default_scheme(),
+            metrics_path: default_metrics_path(),
+        })
+        .unwrap()
+    }
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn discovers_json_and_yaml_targets() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a.json"),
+            r#"[{"targets": ["10.0.0.1:9100", "10.0.0.2:9100"], "labels": {"env": "prod"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.yml"),
+            "- targets: ['10.0.0.3:8443']\n  labels:\n    __scheme__: https\n    __metrics_path__: /stats\n",
+        )
+        .unwrap();
+
+        let targets = discovery(&dir).targets();
+        assert_eq!(
+            targets,
+            vec![
+                Target {
+                    url: "http://10.0.0.1:9100/metrics".into(),
+                    labels: labels(&[("env", "prod"), ("instance", "10.0.0.1:9100")]),
+                },
+                Target {
+                    url: "http://10.0.0.2:9100/metrics".into(),
+                    labels: labels(&[("env", "prod"), ("instance", "10.0.0.2:9100")]),
+                },
+                Target {
+                    url: "https://10.0.0.3:8443/stats".into(),
+                    labels: labels(&[("instance", "10.0.0.3:8443")]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rereads_changed_files() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("targets.json");
+        let mut discovery = discovery(&dir);
+
+        // Each write changes the file size, so changes are noticed even where
+        // modification times are coarse.
+        fs::write(&path, r#"[{"targets": ["10.0.0.1:9100"]}]"#).unwrap();
+        assert_eq!(discovery.targets().len(), 1);
+
+        // Broken files keep their previous targets.
+        fs::write(&path, "[{").unwrap();
+        assert_eq!(discovery.targets().len(), 1);
+
+        fs::write(
+            &path,
+            r#"[{"targets": ["10.0.0.1:9100", "10.0.0.2:9100"]}]"#,
+        )
+        .unwrap();
+        assert_eq!(discovery.targets().len(), 2);
+
+        fs::remove_file(&path).unwrap();
+        assert!(discovery.targets().is_empty());
+    }
+}
@@ -1,38 +1,74 @@
 use crate::{
     config::{self, GlobalOptions, SourceConfig, SourceDescription},
+    dns::Resolver,
+    event::metric::Metric,
     internal_events::{
         PrometheusErrorResponse, PrometheusEventReceived, PrometheusHttpError,
-        PrometheusParseError, PrometheusRequestCompleted,
+        PrometheusParseError, PrometheusRequestCompleted, PrometheusScrapeTimeout,
     },
     shutdown::ShutdownSignal,
+    sinks::util::http::{Auth, HttpClient},
+    tls::{TlsOptions, TlsSettings},
     Event, Pipeline,
 };
 use futures::{
     compat::{Future01CompatExt, Sink01CompatExt},
-    future, stream, FutureExt, StreamExt, TryFutureExt,
+    stream::{self, FuturesUnordered},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
 };
 use futures01::Sink;
-use hyper::{Body, Client, Request};
-use hyper_openssl::HttpsConnector;
+use hyper::{Body, Request};
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
+use tower::Service;
 
+pub mod discovery;
 pub mod parser;
+mod remote_write;
+
+use discovery::{FileDiscovery, FileSdConfig};
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
-    endpoints: Vec<String>,
+    #[serde(alias = "hosts", default)]
+    endpoints: Vec<Endpoint>,
+    #[serde(default)]
+    file_sd_configs: Vec<FileSdConfig>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
+    #[serde(default = "default_scrape_timeout_secs")]
+    scrape_timeout_secs: u64,
+    job: Option<String>,
+    #[serde(default)]
+    honor_labels: bool,
+    auth: Option<Auth>,
+    tls: Option<TlsOptions>,
+}
+
+/// An endpoint to scrape, given either as a bare URL or along with extra
+/// labels for the metrics scraped from it.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(untagged)]
+enum Endpoint {
+    Url(String),
+    Labeled {
+        url: String,
+        #[serde(default)]
+        labels: BTreeMap<String, String>,
+    },
 }
 
 pub fn default_scrape_interval_secs() -> u64 {
     15
 }
 
+pub fn default_scrape_timeout_secs() -> u64 {
+    10
+}
+
 inventory::submit! {
     SourceDescription::new_without_default::<PrometheusConfig>("prometheus")
 }
@@ -41,17 +77,43 @@ inventory::submit! {
 impl SourceConfig for PrometheusConfig {
     fn build(
         &self,
-        _name: &str,
+        name: &str,
         _globals: &GlobalOptions,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        let mut urls = Vec::new();
-        for host in self.endpoints.iter() {
-            let base_uri = host.parse::<http::Uri>().context(super::UriParseError)?;
-            urls.push(format!("{}metrics", base_uri));
+        if self.endpoints.is_empty() && self.file_sd_configs.is_empty() {
+            return Err("at least one of 'endpoints' or 'file_sd_configs' must be set".into());
         }
-        Ok(prometheus(urls, self.scrape_interval_secs, shutdown, out))
+
+        let targets = self
+            .endpoints
+            .iter()
+            .map(Endpoint::target)
+            .collect::<crate::Result<Vec<_>>>()?;
+        let discoveries = self
+            .file_sd_configs
+            .iter()
+            .map(|config| FileDiscovery::new(config.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let scraper = Scraper {
+            client: HttpClient::new(Resolver, tls)?,
+            auth: self.auth.clone(),
+            job: self.job.clone().unwrap_or_else(|| name.to_string()),
+            honor_labels: self.honor_labels,
+            timeout: Duration::from_secs(self.scrape_timeout_secs),
+        };
+
+        Ok(prometheus(
+            targets,
+            discoveries,
+            scraper,
+            self.scrape_interval_secs,
+            shutdown,
+            out,
+        ))
     }
 
     fn output_type(&self) -> crate::config::DataType {
@@ -63,110 +125,311 @@ impl SourceConfig for PrometheusConfig {
     }
 }
 
+impl Endpoint {
+    fn target(&self) -> crate::Result<Target> {
+        let (url, labels) = match self {
+            Endpoint::Url(url) => (url, None),
+            Endpoint::Labeled { url, labels } => (url, Some(labels)),
+        };
+        let base_uri = url.parse::<http::Uri>().context(super::UriParseError)?;
+
+        // Like Prometheus, the instance is identified by its host and port,
+        // even when the port is left implicit.
+        let port = base_uri.port_u16().unwrap_or_else(|| {
+            if base_uri.scheme_str() == Some("https") {
+                443
+            } else {
+                80
+            }
+        });
+        let instance = format!("{}:{}", base_uri.host().unwrap_or_default(), port);
+
+        let mut target_labels = BTreeMap::new();
+        target_labels.insert("instance".to_string(), instance);
+        target_labels.extend(labels.cloned().unwrap_or_default());
+
+        Ok(Target {
+            url: format!("{}metrics", base_uri),
+            labels: target_labels,
+        })
+    }
+}
+
+/// A URL to scrape, and the labels to attach to the metrics scraped from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub url: String,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Clone)]
+struct Scraper {
+    client: HttpClient,
+    auth: Option<Auth>,
+    job: String,
+    honor_labels: bool,
+    timeout: Duration,
+}
+
+impl Scraper {
+    async fn scrape(mut self, target: Target) -> Vec<Metric> {
+        let mut request = Request::get(&target.url)
+            .body(Body::empty())
+            .expect("error creating request");
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let start = Instant::now();
+        let response = self.client.call(request).and_then(|response| async move {
+            let (header, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            Ok((header, body))
+        });
+
+        match tokio::time::timeout(self.timeout, response).await {
+            Ok(Ok((header, body))) if header.status == hyper::StatusCode::OK => {
+                emit!(PrometheusRequestCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let byte_size = body.len();
+                let body = String::from_utf8_lossy(&body);
+
+                match parser::parse(&body) {
+                    Ok(mut metrics) => {
+                        emit!(PrometheusEventReceived {
+                            byte_size,
+                            count: metrics.len(),
+                        });
+
+                        let mut labels = BTreeMap::new();
+                        labels.insert("job".to_string(), self.job.clone());
+                        labels.extend(target.labels);
+                        for metric in &mut metrics {
+                            apply_labels(metric, &labels, self.honor_labels);
+                        }
+                        metrics
+                    }
+                    Err(error) => {
+                        emit!(PrometheusParseError {
+                            error,
+                            url: target.url,
+                            body,
+                        });
+                        Vec::new()
+                    }
+                }
+            }
+            Ok(Ok((header, _))) => {
+                emit!(PrometheusErrorResponse {
+                    code: header.status,
+                    url: target.url,
+                });
+                Vec::new()
+            }
+            Ok(Err(error)) => {
+                emit!(PrometheusHttpError {
+                    error,
+                    url: target.url,
+                });
+                Vec::new()
+            }
+            Err(_) => {
+                emit!(PrometheusScrapeTimeout {
+                    timeout: self.timeout,
+                    url: target.url,
+                });
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Attaches the labels of its target to a scraped metric. As with Prometheus,
+/// a label the metric already has is kept as is when `honor_labels` is set, and
+/// is otherwise renamed to `exported_<label>` to make way for the target's.
+fn apply_labels(metric: &mut Metric, labels: &BTreeMap<String, String>, honor_labels: bool) {
+    let tags = metric.tags.get_or_insert_with(BTreeMap::new);
+    for (name, value) in labels {
+        if let Some(exported) = tags.get(name).cloned() {
+            if honor_labels {
+                continue;
+            }
+
+            let mut exported_name = format!("exported_{}", name);
+            while tags.contains_key(&exported_name) {
+                exported_name = format!("exported_{}", exported_name);
+            }
+            tags.insert(exported_name, exported);
+        }
+        tags.insert(name.clone(), value.clone());
+    }
+}
+
 fn prometheus(
-    urls: Vec<String>,
+    targets: Vec<Target>,
+    mut discoveries: Vec<FileDiscovery>,
+    scraper: Scraper,
     interval: u64,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> super::Source {
-    let out = out
+    let mut out = out
         .sink_map_err(|e| error!("error sending metric: {:?}", e))
         .sink_compat();
-    let task = tokio::time::interval(Duration::from_secs(interval))
-        .take_until(shutdown.compat())
-        .map(move |_| stream::iter(urls.clone()))
-        .flatten()
-        .map(move |url| {
-            let https = HttpsConnector::new().expect("TLS initialization failed");
-            let client = Client::builder().build(https);
-
-            let request = Request::get(&url)
-                .body(Body::empty())
-                .expect("error creating request");
-
-            let start = Instant::now();
-            client
-                .request(request)
-                .and_then(|response| async move {
-                    let (header, body) = response.into_parts();
-                    let body = hyper::body::to_bytes(body).await?;
-                    Ok((header, body))
-                })
-                .into_stream()
-                .filter_map(move |response| {
-                    future::ready(match response {
-                        Ok((header, body)) if header.status == hyper::StatusCode::OK => {
-                            emit!(PrometheusRequestCompleted {
-                                start,
-                                end: Instant::now()
-                            });
-
-                            let byte_size = body.len();
-                            let body = String::from_utf8_lossy(&body);
-
-                            match parser::parse(&body) {
-                                Ok(metrics) => {
-                                    emit!(PrometheusEventReceived {
-                                        byte_size,
-                                        count: metrics.len(),
-                                    });
-                                    Some(stream::iter(metrics).map(Event::Metric).map(Ok))
-                                }
-                                Err(error) => {
-                                    emit!(PrometheusParseError {
-                                        error,
-                                        url: url.clone(),
-                                        body,
-                                    });
-                                    None
-                                }
-                            }
-                        }
-                        Ok((header, _)) => {
-                            emit!(PrometheusErrorResponse {
-                                code: header.status,
-                                url: url.clone(),
-                            });
-                            None
-                        }
-                        Err(error) => {
-                            emit!(PrometheusHttpError {
-                                error,
-                                url: url.clone(),
-                            });
-                            None
-                        }
-                    })
-                })
-                .flatten()
-        })
-        .flatten()
-        .forward(out)
-        .inspect(|_| info!("finished sending"));
+    let task = async move {
+        let mut ticks =
+            tokio::time::interval(Duration::from_secs(interval)).take_until(shutdown.compat());
+
+        while ticks.next().await.is_some() {
+            // Target files are checked for changes before every scrape.
+            let mut scrapes = targets
+                .iter()
+                .cloned()
+                .chain(discoveries.iter_mut().flat_map(FileDiscovery::targets))
+                .map(|target| scraper.clone().scrape(target))
+                .collect::<FuturesUnordered<_>>();
 
-    Box::new(task.boxed().compat())
+            while let Some(metrics) = scrapes.next().await {
+                let mut metrics = stream::iter(metrics).map(Event::Metric).map(Ok);
+                if out.send_all(&mut metrics).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        info!("finished sending");
+    };
+
+    Box::new(task.unit_error().boxed().compat())
 }
 
-#[cfg(feature = "sinks-prometheus")]
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{
-        config,
-        sinks::prometheus::PrometheusSinkConfig,
-        test_util::{next_addr, start_topology},
+        event::metric::{MetricKind, MetricValue},
+        test_util::{collect_n, next_addr},
         Error,
     };
-    use futures::compat::Future01CompatExt;
     use hyper::{
         service::{make_service_fn, service_fn},
-        {Body, Client, Response, Server},
+        {Body, Response, Server, StatusCode},
     };
     use pretty_assertions::assert_eq;
-    use tokio::time::{delay_for, Duration};
+
+    fn tags(tags: &[(&str, &str)]) -> BTreeMap<String, String> {
+        tags.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn apply_labels_exports_conflicting_labels() {
+        let metric = Metric {
+            name: "requests_total".into(),
+            timestamp: None,
+            tags: Some(tags(&[
+                ("code", "200"),
+                ("job", "app"),
+                ("exported_job", "x"),
+            ])),
+            kind: MetricKind::Absolute,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+        let labels = tags(&[("job", "prometheus"), ("instance", "localhost:9090")]);
+
+        let mut exported = metric.clone();
+        apply_labels(&mut exported, &labels, false);
+        assert_eq!(
+            exported.tags,
+            Some(tags(&[
+                ("code", "200"),
+                ("exported_exported_job", "app"),
+                ("exported_job", "x"),
+                ("instance", "localhost:9090"),
+                ("job", "prometheus"),
+            ]))
+        );
+
+        let mut honored = metric;
+        apply_labels(&mut honored, &labels, true);
+        assert_eq!(
+            honored.tags,
+            Some(tags(&[
+                ("code", "200"),
+                ("exported_job", "x"),
+                ("instance", "localhost:9090"),
+                ("job", "app"),
+            ]))
+        );
+    }
 
+    #[tokio::test]
+    async fn scrapes_with_auth_and_labels() {
+        let in_addr = next_addr();
+
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|request: Request<Body>| async move {
+                let response = match request.headers().get("Authorization") {
+                    Some(auth) if auth == "Bearer s3cr3t" => {
+                        Response::new(Body::from("up{job=\"app\"} 1\n"))
+                    }
+                    _ => {
+                        let mut response = Response::new(Body::empty());
+                        *response.status_mut() = StatusCode::UNAUTHORIZED;
+                        response
+                    }
+                };
+                Ok::<_, Error>(response)
+            }))
+        });
+
+        tokio::spawn(async move {
+            if let Err(e) = Server::bind(&in_addr).serve(make_svc).await {
+                error!("server error: {:?}", e);
+            }
+        });
+
+        let config: PrometheusConfig = toml::from_str(&format!(
+            r#"
+            endpoints = [{{ url = "http://{}", labels = {{ env = "test" }} }}]
+            scrape_interval_secs = 1
+            auth = {{ strategy = "bearer", token = "s3cr3t" }}
+            "#,
+            in_addr
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config
+            .build("in", &GlobalOptions::default(), ShutdownSignal::noop(), tx)
+            .unwrap();
+        tokio::spawn(source.compat());
+
+        let events = collect_n(rx, 1).await.unwrap();
+        let metric = events[0].as_metric();
+        assert_eq!(metric.name, "up");
+        assert_eq!(
+            metric.tags,
+            Some(tags(&[
+                ("env", "test"),
+                ("exported_job", "app"),
+                ("instance", &in_addr.to_string()),
+                ("job", "in"),
+            ]))
+        );
+    }
+
+    #[cfg(feature = "sinks-prometheus")]
     #[tokio::test]
     async fn test_prometheus_routing() {
+        use crate::{sinks::prometheus::PrometheusSinkConfig, test_util::start_topology};
+        use hyper::Client;
+        use tokio::time::delay_for;
+
         let in_addr = next_addr();
         let out_addr = next_addr();
 
@@ -215,8 +478,14 @@ mod test {
         config.add_source(
             "in",
             PrometheusConfig {
-                endpoints: vec![format!("http://{}", in_addr)],
+                endpoints: vec![Endpoint::Url(format!("http://{}", in_addr))],
+                file_sd_configs: vec![],
                 scrape_interval_secs: 1,
+                scrape_timeout_secs: default_scrape_timeout_secs(),
+                job: None,
+                honor_labels: false,
+                auth: None,
+                tls: None,
             },
         );
         config.add_sink(
@@ -245,33 +514,34 @@ mod test {
             .lines()
             .collect::<Vec<_>>();
 
+        let labels = format!("instance=\"{}\",job=\"in\"", in_addr);
         assert_eq!(lines, vec![
-            "# HELP vector_promhttp_metric_handler_requests_total promhttp_metric_handler_requests_total",
-            "# TYPE vector_promhttp_metric_handler_requests_total counter",
-            "vector_promhttp_metric_handler_requests_total{code=\"200\"} 100",
-            "vector_promhttp_metric_handler_requests_total{code=\"404\"} 7",
-            "# HELP vector_prometheus_remote_storage_samples_in_total prometheus_remote_storage_samples_in_total",
-            "# TYPE vector_prometheus_remote_storage_samples_in_total gauge",
-            "vector_prometheus_remote_storage_samples_in_total 57011636",
-            "# HELP vector_http_request_duration_seconds http_request_duration_seconds",
-            "# TYPE vector_http_request_duration_seconds histogram",
-            "vector_http_request_duration_seconds_bucket{le=\"0.05\"} 24054",
-            "vector_http_request_duration_seconds_bucket{le=\"0.1\"} 33444",
-            "vector_http_request_duration_seconds_bucket{le=\"0.2\"} 100392",
-            "vector_http_request_duration_seconds_bucket{le=\"0.5\"} 129389",
-            "vector_http_request_duration_seconds_bucket{le=\"1\"} 133988",
-            "vector_http_request_duration_seconds_bucket{le=\"+Inf\"} 144320",
-            "vector_http_request_duration_seconds_sum 53423",
-            "vector_http_request_duration_seconds_count 144320",
-            "# HELP vector_rpc_duration_seconds rpc_duration_seconds",
-            "# TYPE vector_rpc_duration_seconds summary",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.01\"} 3102",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.05\"} 3272",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.5\"} 4773",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.9\"} 9001",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.99\"} 76656",
-            "vector_rpc_duration_seconds_sum{code=\"200\"} 17560473",
-            "vector_rpc_duration_seconds_count{code=\"200\"} 2693",
+            "# HELP vector_promhttp_metric_handler_requests_total promhttp_metric_handler_requests_total".to_string(),
+            "# TYPE vector_promhttp_metric_handler_requests_total counter".to_string(),
+            format!("vector_promhttp_metric_handler_requests_total{{code=\"200\",{}}} 100", labels),
+            format!("vector_promhttp_metric_handler_requests_total{{code=\"404\",{}}} 7", labels),
+            "# HELP vector_prometheus_remote_storage_samples_in_total prometheus_remote_storage_samples_in_total".to_string(),
+            "# TYPE vector_prometheus_remote_storage_samples_in_total gauge".to_string(),
+            format!("vector_prometheus_remote_storage_samples_in_total{{{}}} 57011636", labels),
+            "# HELP vector_http_request_duration_seconds http_request_duration_seconds".to_string(),
+            "# TYPE vector_http_request_duration_seconds histogram".to_string(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.05\"}} 24054", labels),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.1\"}} 33444", labels),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.2\"}} 100392", labels),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.5\"}} 129389", labels),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"1\"}} 133988", labels),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} 144320", labels),
+            format!("vector_http_request_duration_seconds_sum{{{}}} 53423", labels),
+            format!("vector_http_request_duration_seconds_count{{{}}} 144320", labels),
+            "# HELP vector_rpc_duration_seconds rpc_duration_seconds".to_string(),
+            "# TYPE vector_rpc_duration_seconds summary".to_string(),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.01\"}} 3102", labels),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.05\"}} 3272", labels),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.5\"}} 4773", labels),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.9\"}} 9001", labels),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.99\"}} 76656", labels),
+            format!("vector_rpc_duration_seconds_sum{{code=\"200\",{}}} 17560473", labels),
+            format!("vector_rpc_duration_seconds_count{{code=\"200\",{}}} 2693", labels),
             ],
         );
 
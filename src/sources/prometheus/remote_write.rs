@@ -0,0 +1,464 @@
+use super::parser::proto::{self, metric_metadata::MetricType};
+use crate::{
+    config::{self, GlobalOptions, SourceConfig, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    shutdown::ShutdownSignal,
+    sources::util::{ErrorMessage, HttpSource},
+    tls::TlsConfig,
+    Event, Pipeline,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use warp::http::{HeaderMap, StatusCode};
+
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// Suffixes given to the names of counters, and to the series making up
+/// histograms and summaries, which are counters as well.
+const COUNTER_SUFFIXES: &[&str] = &["_total", "_bucket", "_sum", "_count"];
+
+/// The most metric families whose type is kept. Families seen once this many
+/// are known fall back to naming conventions, so that writers can't grow the
+/// metadata without bounds.
+const MAX_METADATA_FAMILIES: usize = 10_000;
+
+/// The value Prometheus marks series which went stale with, a NaN that is
+/// distinct from any NaN sample.
+const STALE_NAN: u64 = 0x7ff0_0000_0000_0002;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct PrometheusRemoteWriteConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new_without_default::<PrometheusRemoteWriteConfig>("prometheus_remote_write")
+}
+
+#[typetag::serde(name = "prometheus_remote_write")]
+#[async_trait]
+impl SourceConfig for PrometheusRemoteWriteConfig {
+    fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        _shutdown: ShutdownSignal,
+        _out: Pipeline,
+    ) -> crate::Result<crate::sources::Source> {
+        Err("the prometheus_remote_write source can only be built asynchronously".into())
+    }
+
+    async fn build_async(
+        &self,
+        _: &str,
+        _: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<crate::sources::Source> {
+        let source = RemoteWriteSource::default();
+        source.run(self.address, "", &self.tls, out, shutdown)
+    }
+
+    fn output_type(&self) -> config::DataType {
+        config::DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "prometheus_remote_write"
+    }
+}
+
+/// Prometheus sends metadata in write requests of their own rather than along
+/// with the samples, so the type of each metric family seen so far is kept.
+#[derive(Clone, Default)]
+struct RemoteWriteSource {
+    metadata: Arc<Mutex<HashMap<String, MetricType>>>,
+}
+
+impl HttpSource for RemoteWriteSource {
+    fn build_event(&self, body: Bytes, _header_map: HeaderMap) -> Result<Vec<Event>, ErrorMessage> {
+        let body = snap::raw::Decoder::new()
+            .decompress_vec(&body)
+            .map_err(|error| {
+                ErrorMessage::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Could not decompress write request: {}", error),
+                )
+            })?;
+        let request = proto::WriteRequest::decode(body.as_slice()).map_err(|error| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Could not decode write request: {}", error),
+            )
+        })?;
+
+        let mut metadata = self.metadata.lock().unwrap();
+        decode_write_request(request, &mut metadata)
+    }
+}
+
+/// Converts each sample of a write request into a metric, after recording the
+/// types of the metric families found in its metadata. Staleness markers are
+/// skipped, as they aren't actual values.
+fn decode_write_request(
+    request: proto::WriteRequest,
+    metadata: &mut HashMap<String, MetricType>,
+) -> Result<Vec<Event>, ErrorMessage> {
+    for family in request.metadata {
+        match MetricType::from_i32(family.r#type) {
+            Some(MetricType::Unknown) | None => (),
+            Some(r#type) => {
+                if metadata.len() < MAX_METADATA_FAMILIES
+                    || metadata.contains_key(&family.metric_family_name)
+                {
+                    metadata.insert(family.metric_family_name, r#type);
+                }
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for series in request.timeseries {
+        let mut name = None;
+        let mut tags = BTreeMap::new();
+        for label in series.labels {
+            if label.name == METRIC_NAME_LABEL {
+                name = Some(label.value);
+            } else {
+                tags.insert(label.name, label.value);
+            }
+        }
+        let name = name.ok_or_else(|| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Time series is missing the {:?} label", METRIC_NAME_LABEL),
+            )
+        })?;
+        let tags = if tags.is_empty() { None } else { Some(tags) };
+        let is_counter = is_counter(&name, metadata);
+
+        for sample in series.samples {
+            if sample.value.to_bits() == STALE_NAN {
+                continue;
+            }
+
+            let value = if is_counter {
+                MetricValue::Counter {
+                    value: sample.value,
+                }
+            } else {
+                MetricValue::Gauge {
+                    value: sample.value,
+                }
+            };
+
+            events.push(Event::Metric(Metric {
+                name: name.clone(),
+                timestamp: Some(Utc.timestamp_millis(sample.timestamp)),
+                tags: tags.clone(),
+                kind: MetricKind::Absolute,
+                value,
+            }));
+        }
+    }
+
+    Ok(events)
+}
+
+/// Remote write only carries the type of a metric in its metadata, so samples
+/// are counters when the metadata of their family says so. The series making up
+/// histograms and summaries belong to a family named without their suffix. Lacking
+/// metadata, the naming conventions for counters are followed instead.
+fn is_counter(name: &str, metadata: &HashMap<String, MetricType>) -> bool {
+    if let Some(r#type) = metadata.get(name) {
+        return *r#type == MetricType::Counter;
+    }
+
+    for suffix in &["_bucket", "_sum", "_count"] {
+        if name.ends_with(suffix) {
+            let family = &name[..name.len() - suffix.len()];
+            if let Some(r#type) = metadata.get(family) {
+                return matches!(r#type, MetricType::Histogram | MetricType::Summary);
+            }
+        }
+    }
+
+    COUNTER_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{collect_n, next_addr, trace_init, wait_for_tcp};
+    use futures::compat::Future01CompatExt;
+    use pretty_assertions::assert_eq;
+
+    fn label(name: &str, value: &str) -> proto::Label {
+        proto::Label {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    async fn send(address: SocketAddr, body: Vec<u8>) -> u16 {
+        reqwest::Client::new()
+            .post(&format!("http://{}/", address))
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", "application/x-protobuf")
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn receives_write_requests() {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let address = next_addr();
+        let source = PrometheusRemoteWriteConfig { address, tls: None }
+            .build_async(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source.compat());
+        wait_for_tcp(address).await;
+
+        let request = proto::WriteRequest {
+            timeseries: vec![
+                proto::TimeSeries {
+                    labels: vec![
+                        label(METRIC_NAME_LABEL, "requests_total"),
+                        label("code", "200"),
+                    ],
+                    samples: vec![
+                        proto::Sample {
+                            value: 10.0,
+                            timestamp: 1_600_000_000_000,
+                        },
+                        proto::Sample {
+                            value: 12.0,
+                            timestamp: 1_600_000_015_000,
+                        },
+                    ],
+                },
+                proto::TimeSeries {
+                    labels: vec![label(METRIC_NAME_LABEL, "temperature")],
+                    samples: vec![proto::Sample {
+                        value: 21.5,
+                        timestamp: 1_600_000_000_000,
+                    }],
+                },
+            ],
+            metadata: vec![proto::MetricMetadata {
+                r#type: proto::metric_metadata::MetricType::Counter as i32,
+                metric_family_name: "requests_total".into(),
+                help: String::new(),
+                unit: String::new(),
+            }],
+        };
+        let mut body = Vec::new();
+        request.encode(&mut body).unwrap();
+        let body = snap::raw::Encoder::new().compress_vec(&body).unwrap();
+
+        assert_eq!(send(address, body).await, 200);
+
+        let events = collect_n(rx, 3).await.unwrap();
+        let code = Some(
+            vec![("code".to_string(), "200".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(
+            events.iter().map(Event::as_metric).collect::<Vec<_>>(),
+            vec![
+                &Metric {
+                    name: "requests_total".into(),
+                    timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+                    tags: code.clone(),
+                    kind: MetricKind::Absolute,
+                    value: MetricValue::Counter { value: 10.0 },
+                },
+                &Metric {
+                    name: "requests_total".into(),
+                    timestamp: Some(Utc.timestamp(1_600_000_015, 0)),
+                    tags: code,
+                    kind: MetricKind::Absolute,
+                    value: MetricValue::Counter { value: 12.0 },
+                },
+                &Metric {
+                    name: "temperature".into(),
+                    timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+                    tags: None,
+                    kind: MetricKind::Absolute,
+                    value: MetricValue::Gauge { value: 21.5 },
+                },
+            ]
+        );
+    }
+
+    fn series(name: &str) -> proto::TimeSeries {
+        proto::TimeSeries {
+            labels: vec![label(METRIC_NAME_LABEL, name)],
+            samples: vec![proto::Sample {
+                value: 1.0,
+                timestamp: 1_600_000_000_000,
+            }],
+        }
+    }
+
+    fn metadata(name: &str, r#type: MetricType) -> proto::MetricMetadata {
+        proto::MetricMetadata {
+            r#type: r#type as i32,
+            metric_family_name: name.into(),
+            help: String::new(),
+            unit: String::new(),
+        }
+    }
+
+    fn decode_kinds(
+        names: &[&str],
+        metadata: &mut HashMap<String, MetricType>,
+    ) -> Vec<&'static str> {
+        let request = proto::WriteRequest {
+            timeseries: names.iter().map(|name| series(name)).collect(),
+            metadata: vec![],
+        };
+        decode_write_request(request, metadata)
+            .unwrap()
+            .iter()
+            .map(|event| match event.as_metric().value {
+                MetricValue::Counter { .. } => "counter",
+                MetricValue::Gauge { .. } => "gauge",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_samples_without_metadata_by_name() {
+        let kinds = decode_kinds(
+            &[
+                "requests_total",
+                "latency_bucket",
+                "latency_sum",
+                "latency_count",
+                "temperature",
+            ],
+            &mut HashMap::new(),
+        );
+        assert_eq!(
+            kinds,
+            vec!["counter", "counter", "counter", "counter", "gauge"]
+        );
+    }
+
+    #[test]
+    fn decodes_samples_with_metadata_from_earlier_requests() {
+        let mut known = HashMap::new();
+        let request = proto::WriteRequest {
+            timeseries: vec![],
+            metadata: vec![
+                metadata("requests", MetricType::Counter),
+                metadata("queue_total", MetricType::Gauge),
+                metadata("latency", MetricType::Histogram),
+                metadata("queue_size", MetricType::Gaugehistogram),
+            ],
+        };
+        assert!(decode_write_request(request, &mut known)
+            .unwrap()
+            .is_empty());
+
+        let kinds = decode_kinds(
+            &[
+                "requests",
+                "queue_total",
+                "latency_bucket",
+                "latency_count",
+                "queue_size_bucket",
+            ],
+            &mut known,
+        );
+        assert_eq!(
+            kinds,
+            vec!["counter", "gauge", "counter", "counter", "gauge"]
+        );
+    }
+
+    #[test]
+    fn skips_staleness_markers() {
+        let mut stale = series("requests_total");
+        stale.samples.push(proto::Sample {
+            value: f64::from_bits(STALE_NAN),
+            timestamp: 1_600_000_015_000,
+        });
+        let request = proto::WriteRequest {
+            timeseries: vec![stale],
+            metadata: vec![],
+        };
+
+        let events = decode_write_request(request, &mut HashMap::new()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_metric().value,
+            MetricValue::Counter { value: 1.0 }
+        );
+    }
+
+    #[test]
+    fn limits_metadata_families() {
+        let mut known = (0..MAX_METADATA_FAMILIES)
+            .map(|i| (format!("family_{}", i), MetricType::Gauge))
+            .collect::<HashMap<_, _>>();
+        let request = proto::WriteRequest {
+            timeseries: vec![],
+            metadata: vec![
+                metadata("family_0", MetricType::Counter),
+                metadata("requests", MetricType::Counter),
+            ],
+        };
+        decode_write_request(request, &mut known).unwrap();
+
+        assert_eq!(known.len(), MAX_METADATA_FAMILIES);
+        assert_eq!(known["family_0"], MetricType::Counter);
+        assert!(!known.contains_key("requests"));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        trace_init();
+
+        let (tx, _rx) = Pipeline::new_test();
+        let address = next_addr();
+        let source = PrometheusRemoteWriteConfig { address, tls: None }
+            .build_async(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source.compat());
+        wait_for_tcp(address).await;
+
+        assert_eq!(send(address, b"not snappy".to_vec()).await, 400);
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(all(feature = "sources-tls", feature = "warp"))]
 mod http;
 pub mod multiline_config;
+pub mod syslog;
 #[cfg(all(feature = "sources-tls", feature = "listenfd"))]
 mod tcp;
 #[cfg(all(unix, any(feature = "sources-socket", feature = "sources-syslog")))]
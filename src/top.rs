@@ -0,0 +1,270 @@
+use crate::api::{
+    self,
+    client::subscription::{Receiver, SubscriptionClient},
+};
+use colored::*;
+use futures::StreamExt;
+use graphql_client::GraphQLQuery;
+use std::{collections::BTreeMap, net::SocketAddr};
+use structopt::StructOpt;
+use tokio::select;
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_metrics.graphql",
+    response_derives = "Debug"
+)]
+struct ComponentMetricsSubscription;
+
+type ComponentMetrics =
+    component_metrics_subscription::ComponentMetricsSubscriptionComponentMetrics;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Address of the API of the running Vector instance to inspect.
+    #[structopt(short, long, default_value = "127.0.0.1:8686")]
+    api: SocketAddr,
+
+    /// Interval in milliseconds at which the table is refreshed, from 100 to 60000.
+    #[structopt(short, long, default_value = "1000", parse(try_from_str = parse_interval))]
+    interval: u32,
+
+    /// Only show the named components. Shows every component if none are given.
+    #[structopt(short, long)]
+    components: Vec<String>,
+}
+
+/// Parses the refresh interval, rejecting the values the API wouldn't accept.
+fn parse_interval(value: &str) -> Result<u32, String> {
+    let interval = value.parse::<u32>().map_err(|error| error.to_string())?;
+    if (100..=60_000).contains(&interval) {
+        Ok(interval)
+    } else {
+        Err("must be between 100 and 60000 milliseconds".to_owned())
+    }
+}
+
+/// A row of the table, keeping the previous totals of a component around to work out
+/// its rates.
+struct Row {
+    metrics: ComponentMetrics,
+    events_in_rate: f64,
+    events_out_rate: f64,
+    bytes_rate: f64,
+}
+
+impl Row {
+    fn new(metrics: ComponentMetrics) -> Self {
+        Self {
+            metrics,
+            events_in_rate: 0.0,
+            events_out_rate: 0.0,
+            bytes_rate: 0.0,
+        }
+    }
+
+    /// Replaces the totals with the latest ones, and the rates with those between them.
+    fn update(&mut self, metrics: ComponentMetrics) {
+        let elapsed = (metrics.timestamp - self.metrics.timestamp).num_milliseconds() as f64;
+        if elapsed > 0.0 {
+            let rate = |new: f64, old: f64| (new - old).max(0.0) * 1000.0 / elapsed;
+            self.events_in_rate = rate(metrics.events_in, self.metrics.events_in);
+            self.events_out_rate = rate(metrics.events_out, self.metrics.events_out);
+            self.bytes_rate = rate(metrics.bytes_processed, self.metrics.bytes_processed);
+        }
+        self.metrics = metrics;
+    }
+}
+
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let client = match api::make_subscription_client(opts.api).await {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!(
+                "Couldn't connect to the Vector API at {}: {}. Is the API enabled?",
+                opts.api, error
+            );
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    match run(&client, opts).await {
+        Ok(()) => exitcode::OK,
+        Err(error) => {
+            eprintln!("{}", error);
+            exitcode::UNAVAILABLE
+        }
+    }
+}
+
+async fn run(client: &SubscriptionClient, opts: &Opts) -> Result<(), String> {
+    let names = if opts.components.is_empty() {
+        None
+    } else {
+        Some(opts.components.clone())
+    };
+    let request_body =
+        ComponentMetricsSubscription::build_query(component_metrics_subscription::Variables {
+            names,
+            interval: opts.interval as i64,
+        });
+
+    let subscription = client
+        .start::<ComponentMetricsSubscription>(&request_body)
+        .await
+        .map_err(|error| format!("Couldn't subscribe to component metrics: {}", error))?;
+    let mut updates = subscription.stream();
+
+    let mut rows = BTreeMap::<String, Row>::new();
+    let mut redraw =
+        tokio::time::interval(tokio::time::Duration::from_millis(opts.interval as u64));
+
+    loop {
+        select! {
+            response = updates.next() => {
+                let response = response
+                    .flatten()
+                    .ok_or_else(|| "Lost the connection to the Vector API.".to_owned())?;
+
+                let metrics = match (response.data, response.errors) {
+                    (Some(data), _) => data.component_metrics,
+                    (None, Some(errors)) => {
+                        let messages = errors
+                            .into_iter()
+                            .map(|error| error.message)
+                            .collect::<Vec<_>>();
+                        return Err(format!(
+                            "The Vector API returned an error: {}",
+                            messages.join(", ")
+                        ));
+                    }
+                    (None, None) => continue,
+                };
+
+                match rows.get_mut(&metrics.name) {
+                    Some(row) => row.update(metrics),
+                    None => {
+                        rows.insert(metrics.name.clone(), Row::new(metrics));
+                    }
+                }
+            }
+            _ = redraw.tick() => draw(opts.api, &rows),
+        }
+    }
+}
+
+/// Clears the terminal and draws the table of components.
+fn draw(api: SocketAddr, rows: &BTreeMap<String, Row>) {
+    let width = rows
+        .keys()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+
+    // Clear the screen and move the cursor to its top left corner.
+    print!("\x1B[2J\x1B[H");
+    println!("Vector at {} ({} components)\n", api, rows.len());
+    println!(
+        "{}",
+        format!(
+            "{:<width$}  {:>12}  {:>12}  {:>12}  {:>14}  {:>12}  {:>10}  {:>10}",
+            "NAME",
+            "EVENTS IN/S",
+            "EVENTS OUT/S",
+            "BYTES/S",
+            "EVENTS TOTAL",
+            "BYTES TOTAL",
+            "BUFFERED",
+            "ERRORS",
+            width = width
+        )
+        .bold()
+    );
+
+    for (name, row) in rows {
+        // Events dropped by a full buffer are lost too
+        let errors = row.metrics.errors + row.metrics.buffer_discarded_events;
+        let errors_text = format!("{:>10}", errors as u64);
+        println!(
+            "{:<width$}  {:>12.1}  {:>12.1}  {:>12.1}  {:>14}  {:>12}  {:>10}  {}",
+            name,
+            row.events_in_rate,
+            row.events_out_rate,
+            row.bytes_rate,
+            row.metrics.events_out.max(row.metrics.events_in) as u64,
+            row.metrics.bytes_processed as u64,
+            row.metrics.buffer_events as u64,
+            if errors > 0.0 {
+                errors_text.red()
+            } else {
+                errors_text.normal()
+            },
+            width = width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn metrics(millis: i64, events_in: f64, events_out: f64, bytes: f64) -> ComponentMetrics {
+        ComponentMetrics {
+            name: "component".to_owned(),
+            timestamp: Utc.timestamp_millis(millis),
+            events_in,
+            events_out,
+            bytes_processed: bytes,
+            errors: 0.0,
+            buffer_events: 0.0,
+            buffer_discarded_events: 0.0,
+        }
+    }
+
+    #[test]
+    fn rates_are_per_second() {
+        let mut row = Row::new(metrics(0, 10.0, 5.0, 1000.0));
+        row.update(metrics(500, 20.0, 15.0, 1500.0));
+
+        assert_eq!(row.events_in_rate, 20.0);
+        assert_eq!(row.events_out_rate, 20.0);
+        assert_eq!(row.bytes_rate, 1000.0);
+        assert_eq!(row.metrics.events_in, 20.0);
+    }
+
+    #[test]
+    fn totals_going_down_give_zero_rates() {
+        // Totals start over when a component is reloaded
+        let mut row = Row::new(metrics(0, 100.0, 100.0, 100.0));
+        row.update(metrics(1000, 10.0, 10.0, 10.0));
+
+        assert_eq!(row.events_in_rate, 0.0);
+        assert_eq!(row.events_out_rate, 0.0);
+        assert_eq!(row.bytes_rate, 0.0);
+    }
+
+    #[test]
+    fn keeps_rates_without_elapsed_time() {
+        let mut row = Row::new(metrics(0, 0.0, 0.0, 0.0));
+        row.update(metrics(1000, 10.0, 10.0, 10.0));
+        row.update(metrics(1000, 50.0, 50.0, 50.0));
+
+        assert_eq!(row.events_in_rate, 10.0);
+        assert_eq!(row.metrics.events_in, 50.0);
+    }
+
+    #[test]
+    fn rejects_out_of_range_intervals() {
+        assert_eq!(parse_interval("100"), Ok(100));
+        assert_eq!(parse_interval("60000"), Ok(60_000));
+        assert!(parse_interval("99").is_err());
+        assert!(parse_interval("60001").is_err());
+        assert!(parse_interval("fast").is_err());
+    }
+}
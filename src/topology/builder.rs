@@ -8,12 +8,14 @@ use crate::{
     config::{DataType, SinkContext, TransformContext},
     dns::Resolver,
     event::Event,
+    internal_events::{EventIn, EventOut},
     shutdown::SourceShutdownCoordinator,
     Pipeline,
 };
 use futures::{
     compat::{Future01CompatExt, Stream01CompatExt},
-    future, FutureExt, StreamExt,
+    future::{self, Either},
+    FutureExt, StreamExt,
 };
 use futures01::{sync::mpsc, Future, Stream};
 use std::collections::HashMap;
@@ -70,7 +72,11 @@ pub async fn build_pieces(
         };
 
         let (output, control) = Fanout::new();
-        let pump = rx.forward(output).map(|_| ()).compat();
+        let pump = rx
+            .inspect(|_| emit!(EventOut))
+            .forward(output)
+            .map(|_| ())
+            .compat();
         let pump = Task::new(name, typetag, pump);
 
         // The force_shutdown_tripwire is a Future that when it resolves means that this source
@@ -100,7 +106,10 @@ pub async fn build_pieces(
 
         let typetag = transform.inner.transform_type();
 
-        let cx = TransformContext { resolver };
+        let cx = TransformContext {
+            name: name.clone(),
+            resolver,
+        };
 
         let input_type = transform.inner.input_type();
         let transform = match transform.inner.build_async(cx).await {
@@ -112,12 +121,18 @@ pub async fn build_pieces(
         };
 
         let (input_tx, input_rx) = futures01::sync::mpsc::channel(100);
-        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block);
+        let input_tx = buffers::BufferInputCloner::Memory(
+            input_tx,
+            buffers::WhenFull::Block,
+            buffers::BufferUsage::untracked(),
+        );
 
         let (output, control) = Fanout::new();
 
+        let input_rx = filter_event_type(input_rx, input_type).inspect(|_| emit!(EventIn));
         let transform = transform
-            .transform_stream(filter_event_type(input_rx, input_type))
+            .transform_stream(Box::new(input_rx))
+            .inspect(|_| emit!(EventOut))
             .forward(output)
             .map(|_| debug!("Finished"))
             .compat();
@@ -162,11 +177,20 @@ pub async fn build_pieces(
         let sink = sink
             .run(
                 filter_event_type(rx, input_type)
+                    .inspect(|_| emit!(EventIn))
                     .compat()
                     .take_while(|e| future::ready(e.is_ok()))
                     .map(|x| x.unwrap()),
             )
             .inspect(|_| debug!("Finished"));
+        let usage = tx.usage().clone();
+        let sink = async move {
+            // Report on the buffer for as long as the sink runs
+            match future::select(Box::pin(sink), Box::pin(usage.report())).await {
+                Either::Left((result, _)) => result,
+                Either::Right(((), sink)) => sink.await,
+            }
+        };
         let task = Task::new(name, typetag, sink);
 
         let healthcheck_task = async move {
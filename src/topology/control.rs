@@ -0,0 +1,153 @@
+//! Control over the components of the running topology from outside of it, e.g. by
+//! the API.
+
+use super::fanout::{ControlChannel, ControlMessage};
+use crate::{
+    config::Config,
+    signal::{self, SignalTo},
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+use tokio::sync::watch;
+
+/// Handle on a running topology, shared with whatever controls it from outside, e.g. an
+/// API server. The topology keeps it up to date as it (re)connects its components. One
+/// that isn't attached to any topology has no components.
+#[derive(Clone)]
+pub struct TopologyControl {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: RwLock<State>,
+    // Number of reloads requested through `reload` so far
+    reloads_requested: AtomicUsize,
+    // Outcome of the last reload handled, along with the number of reloads requested
+    // by the time it started, all of which it answers
+    reloaded_tx: watch::Sender<(usize, bool)>,
+    reloaded_rx: watch::Receiver<(usize, bool)>,
+}
+
+#[derive(Default)]
+struct State {
+    config: Arc<Config>,
+    outputs: HashMap<String, ControlChannel>,
+}
+
+impl Default for TopologyControl {
+    fn default() -> Self {
+        let (reloaded_tx, reloaded_rx) = watch::channel((0, false));
+
+        Self {
+            inner: Arc::new(Inner {
+                state: Default::default(),
+                reloads_requested: AtomicUsize::new(0),
+                reloaded_tx,
+                reloaded_rx,
+            }),
+        }
+    }
+}
+
+impl TopologyControl {
+    /// Replaces the config of the running topology. Called whenever it starts or
+    /// successfully reloads a config.
+    pub(super) fn update_config(&self, config: Arc<Config>) {
+        self.inner
+            .state
+            .write()
+            .expect("Couldn't write topology state")
+            .config = config;
+    }
+
+    /// Replaces the controllable outputs with those of the running topology. Called
+    /// whenever its outputs have been (re)connected.
+    pub(super) fn update_outputs(&self, outputs: &HashMap<String, ControlChannel>) {
+        self.inner
+            .state
+            .write()
+            .expect("Couldn't write topology state")
+            .outputs = outputs.clone();
+    }
+
+    /// The config of the running topology.
+    pub fn config(&self) -> Arc<Config> {
+        let state = self
+            .inner
+            .state
+            .read()
+            .expect("Couldn't read topology state");
+        Arc::clone(&state.config)
+    }
+
+    /// Returns the control channel of the output of the named source or transform.
+    pub(super) fn output(&self, component_name: &str) -> Option<ControlChannel> {
+        let state = self
+            .inner
+            .state
+            .read()
+            .expect("Couldn't read topology state");
+        state.outputs.get(component_name).cloned()
+    }
+
+    /// Stops the named source from emitting events, applying backpressure to it until
+    /// it's resumed or changed by a reload. Returns whether there's such a source in the
+    /// running topology.
+    pub fn pause_source(&self, name: &str) -> bool {
+        self.send_to_source(name, ControlMessage::Pause)
+    }
+
+    /// Lets a paused source emit events again. Returns whether there's such a source in
+    /// the running topology.
+    pub fn resume_source(&self, name: &str) -> bool {
+        self.send_to_source(name, ControlMessage::Resume)
+    }
+
+    fn send_to_source(&self, name: &str, message: ControlMessage) -> bool {
+        let state = self
+            .inner
+            .state
+            .read()
+            .expect("Couldn't read topology state");
+        state.config.sources.contains_key(name)
+            && state
+                .outputs
+                .get(name)
+                .map(|control| control.unbounded_send(message).is_ok())
+                .unwrap_or(false)
+    }
+
+    /// Raises a reload signal and waits for a reload started after it to be handled.
+    /// Returns whether the config was reloaded, which is `false` if it failed to load or
+    /// validate, or if nothing is listening for signals.
+    pub async fn reload(&self) -> bool {
+        let requested = self.inner.reloads_requested.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut reloaded = self.inner.reloaded_rx.clone();
+
+        if !signal::raise(SignalTo::Reload) {
+            return false;
+        }
+        while let Some((answered, outcome)) = reloaded.recv().await {
+            if answered >= requested {
+                return outcome;
+            }
+        }
+        false
+    }
+
+    /// Called as a reload signal starts being handled. Returns the number of reloads
+    /// requested by then, to be passed on to `report_reload`.
+    pub fn reload_started(&self) -> usize {
+        self.inner.reloads_requested.load(Ordering::SeqCst)
+    }
+
+    /// Reports the outcome of handling a reload signal to those waiting on `reload`.
+    pub fn report_reload(&self, started: usize, reloaded: bool) {
+        let _ = self.inner.reloaded_tx.broadcast((started, reloaded));
+    }
+}
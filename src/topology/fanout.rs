@@ -7,6 +7,7 @@ type RouterSink = Box<dyn Sink<SinkItem = Event, SinkError = ()> + 'static + Sen
 pub struct Fanout {
     sinks: Vec<(String, RouterSink)>,
     i: usize,
+    paused: bool,
     control_channel: mpsc::UnboundedReceiver<ControlMessage>,
 }
 
@@ -14,6 +15,9 @@ pub enum ControlMessage {
     Add(String, RouterSink),
     Remove(String),
     Replace(String, RouterSink),
+    /// Stop accepting events, applying backpressure upstream until resumed.
+    Pause,
+    Resume,
 }
 
 pub type ControlChannel = mpsc::UnboundedSender<ControlMessage>;
@@ -25,6 +29,7 @@ impl Fanout {
         let fanout = Self {
             sinks: vec![],
             i: 0,
+            paused: false,
             control_channel: control_rx,
         };
 
@@ -67,6 +72,8 @@ impl Fanout {
                 ControlMessage::Add(name, sink) => self.add(name, sink),
                 ControlMessage::Remove(name) => self.remove(&name),
                 ControlMessage::Replace(name, sink) => self.replace(name, sink),
+                ControlMessage::Pause => self.paused = true,
+                ControlMessage::Resume => self.paused = false,
             }
         }
     }
@@ -92,6 +99,12 @@ impl Sink for Fanout {
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
         self.process_control_messages();
 
+        // Only pause between events, so that none is sent to just some of the sinks. Having
+        // polled the control channel, this task is woken up once it's resumed.
+        if self.paused && self.i == 0 {
+            return Ok(AsyncSink::NotReady(item));
+        }
+
         if self.sinks.is_empty() {
             return Ok(AsyncSink::Ready);
         }
@@ -278,6 +291,37 @@ mod tests {
         assert_eq!(collect_ready(rx_b).await.unwrap(), vec![rec1, rec2]);
     }
 
+    #[tokio::test]
+    async fn fanout_pause_resume() {
+        let (tx_a, mut rx_a) = mpsc::unbounded();
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+
+        let (mut fanout, fanout_control) = Fanout::new();
+
+        fanout.add("a".to_string(), tx_a);
+
+        let rec1 = Event::from("line 1".to_string());
+        let rec2 = Event::from("line 2".to_string());
+
+        let fanout = fanout.send(rec1.clone()).compat().await.unwrap();
+
+        fanout_control
+            .unbounded_send(ControlMessage::Pause)
+            .unwrap();
+        let send = tokio::spawn(fanout.send(rec2.clone()).compat());
+
+        delay_for(Duration::from_millis(50)).await;
+        // The send task will be blocked on the paused fanout right now.
+        assert_eq!(collect_ready(rx_a.by_ref()).await.unwrap(), vec![rec1]);
+
+        fanout_control
+            .unbounded_send(ControlMessage::Resume)
+            .unwrap();
+        let _fanout = send.await.unwrap().unwrap();
+
+        assert_eq!(collect_ready(rx_a).await.unwrap(), vec![rec2]);
+    }
+
     #[tokio::test]
     async fn fanout_shrink_after_notready() {
         let (tx_a, rx_a) = mpsc::channel(1);
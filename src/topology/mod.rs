@@ -7,7 +7,11 @@
 //! each type of component.
 
 pub mod builder;
+#[cfg(feature = "api")]
+pub mod control;
 mod fanout;
+#[cfg(feature = "api")]
+pub mod tap;
 mod task;
 
 use crate::{
@@ -21,6 +25,7 @@ use futures01::{sync::mpsc, Future};
 use std::{
     collections::{HashMap, HashSet},
     panic::AssertUnwindSafe,
+    sync::Arc,
 };
 use tokio::time::{delay_until, interval, Duration, Instant};
 use tracing_futures::Instrument;
@@ -35,8 +40,10 @@ pub struct RunningTopology {
     source_tasks: HashMap<String, TaskHandle>,
     tasks: HashMap<String, TaskHandle>,
     shutdown_coordinator: SourceShutdownCoordinator,
-    config: Config,
+    config: Arc<Config>,
     abort_tx: mpsc::UnboundedSender<()>,
+    #[cfg(feature = "api")]
+    control: control::TopologyControl,
 }
 
 pub async fn start_validated(
@@ -50,11 +57,13 @@ pub async fn start_validated(
     let mut running_topology = RunningTopology {
         inputs: HashMap::new(),
         outputs: HashMap::new(),
-        config: Config::default(),
+        config: Arc::new(Config::default()),
         shutdown_coordinator: SourceShutdownCoordinator::default(),
         source_tasks: HashMap::new(),
         tasks: HashMap::new(),
         abort_tx,
+        #[cfg(feature = "api")]
+        control: Default::default(),
     };
 
     if !running_topology
@@ -65,7 +74,11 @@ pub async fn start_validated(
     }
     running_topology.connect_diff(&diff, &mut pieces);
     running_topology.spawn_diff(&diff, pieces);
-    running_topology.config = config;
+    running_topology.config = Arc::new(config);
+    #[cfg(feature = "api")]
+    running_topology
+        .control
+        .update_config(Arc::clone(&running_topology.config));
 
     Some((running_topology, abort_rx))
 }
@@ -95,6 +108,12 @@ pub fn take_healthchecks(diff: &ConfigDiff, pieces: &mut Pieces) -> Vec<(String,
 }
 
 impl RunningTopology {
+    /// Returns a handle through which the API controls this topology.
+    #[cfg(feature = "api")]
+    pub fn control(&self) -> control::TopologyControl {
+        self.control.clone()
+    }
+
     /// Returned future will finish once all current sources have finished.
     pub fn sources_finished(&self) -> impl Future<Item = (), Error = ()> {
         self.shutdown_coordinator.shutdown_tripwire()
@@ -109,6 +128,14 @@ impl RunningTopology {
     /// returned future is dropped then everything from this RunningTopology instance is fully
     /// dropped.
     pub fn stop(self) -> impl Future<Item = (), Error = ()> {
+        // Paused sources couldn't shut down gracefully, so resume them and keep them from
+        // being paused again.
+        for output in self.outputs.values() {
+            let _ = output.unbounded_send(fanout::ControlMessage::Resume);
+        }
+        #[cfg(feature = "api")]
+        self.control.update_outputs(&HashMap::new());
+
         // Create handy handles collections of all tasks for the subsequent operations.
         let mut wait_handles = Vec::new();
         // We need a Vec here since source components have two tasks. One for pump in self.tasks,
@@ -228,7 +255,9 @@ impl RunningTopology {
             {
                 self.connect_diff(&diff, &mut new_pieces);
                 self.spawn_diff(&diff, new_pieces);
-                self.config = new_config;
+                self.config = Arc::new(new_config);
+                #[cfg(feature = "api")]
+                self.control.update_config(Arc::clone(&self.config));
                 // We have successfully changed to new config.
                 return Ok(true);
             }
@@ -396,6 +425,9 @@ impl RunningTopology {
         for name in &diff.sinks.to_add {
             self.setup_inputs(&name, new_pieces);
         }
+
+        #[cfg(feature = "api")]
+        self.control.update_outputs(&self.outputs);
     }
 
     /// Starts new and changed pieces of topology.
@@ -486,7 +518,10 @@ impl RunningTopology {
     }
 
     fn remove_outputs(&mut self, name: &str) {
-        self.outputs.remove(name);
+        // A paused source couldn't shut down gracefully, so resume it.
+        if let Some(output) = self.outputs.remove(name) {
+            let _ = output.unbounded_send(fanout::ControlMessage::Resume);
+        }
     }
 
     fn remove_inputs(&mut self, name: &str) {
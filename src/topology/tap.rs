@@ -0,0 +1,123 @@
+//! Taps allow the events leaving a source or transform in the running topology to be
+//! observed from outside of it, e.g. by the API, without affecting the pipeline.
+
+use super::{
+    control::TopologyControl,
+    fanout::{ControlChannel, ControlMessage},
+};
+use crate::Event;
+use futures01::{Async, AsyncSink, Poll, Sink, StartSend};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Fanout output which never applies backpressure: events are dropped whenever the
+/// tap can't keep up, or has gone away.
+struct TapSink(mpsc::Sender<Event>);
+
+impl Sink for TapSink {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let _ = self.0.try_send(item);
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        Ok(Async::Ready(()))
+    }
+}
+
+/// A tap on the output of a component. The tap is removed from the component's output
+/// once this is dropped.
+pub struct Tap {
+    output_name: String,
+    control: ControlChannel,
+}
+
+impl Tap {
+    /// Taps into the output of the named component. Returns the tap along with a
+    /// receiver buffering up to `capacity` events, or `None` if there's no such source
+    /// or transform in the running topology.
+    pub fn new(
+        control: &TopologyControl,
+        component_name: &str,
+        capacity: usize,
+    ) -> Option<(Self, mpsc::Receiver<Event>)> {
+        let control = control.output(component_name)?;
+
+        Self::attach(control, capacity)
+    }
+
+    fn attach(control: ControlChannel, capacity: usize) -> Option<(Self, mpsc::Receiver<Event>)> {
+        let output_name = format!("_tap_{}", Uuid::new_v4());
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+
+        control
+            .unbounded_send(ControlMessage::Add(
+                output_name.clone(),
+                Box::new(TapSink(tx)),
+            ))
+            .ok()?;
+
+        Some((
+            Self {
+                output_name,
+                control,
+            },
+            rx,
+        ))
+    }
+}
+
+impl Drop for Tap {
+    fn drop(&mut self) {
+        // The component may already be gone, in which case so is the tap.
+        let _ = self
+            .control
+            .unbounded_send(ControlMessage::Remove(self.output_name.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::fanout::Fanout;
+    use futures::compat::Future01CompatExt;
+    use futures01::Sink;
+
+    #[test]
+    fn tap_missing_component() {
+        let control = TopologyControl::default();
+        assert!(Tap::new(&control, "tap_test_missing", 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn tap_receives_events_without_blocking() {
+        let (fanout, control) = Fanout::new();
+        let (tap, mut rx) = Tap::attach(control, 1).unwrap();
+
+        let fanout = fanout
+            .send(Event::from("line 1".to_string()))
+            .compat()
+            .await
+            .unwrap();
+        // The tap is full, so this one is dropped instead of blocking the fanout.
+        let fanout = fanout
+            .send(Event::from("line 2".to_string()))
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(rx.recv().await, Some(Event::from("line 1".to_string())));
+        assert!(rx.try_recv().is_err());
+
+        drop(tap);
+        let _fanout = fanout
+            .send(Event::from("line 3".to_string()))
+            .compat()
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await, None);
+    }
+}
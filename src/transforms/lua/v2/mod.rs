@@ -1,7 +1,7 @@
 mod interop;
 
 use crate::{
-    config::CONFIG_PATHS,
+    config::{ConfigPath, CONFIG_PATHS},
     config::{DataType, TransformContext},
     event::Event,
     internal_events::{LuaBuildError, LuaEventProcessed, LuaGcTriggered},
@@ -57,11 +57,14 @@ pub struct LuaConfig {
 fn default_config_paths() -> Vec<PathBuf> {
     match CONFIG_PATHS.get() {
         Some(config_paths) => config_paths
-            .clone()
-            .into_iter()
-            .map(|mut path_buf| {
-                path_buf.pop();
-                path_buf
+            .iter()
+            .map(|config_path| match config_path {
+                ConfigPath::File(path, _) => {
+                    let mut path_buf = path.clone();
+                    path_buf.pop();
+                    path_buf
+                }
+                ConfigPath::Dir(path) => path.clone(),
             })
             .collect(),
         None => vec![],
@@ -1,11 +1,36 @@
 use super::Transform;
 use crate::{
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
-    event::Event,
-    internal_events::{RemapEventProcessed, RemapFailedMapping},
-    mapping::{parser::parse as parse_mapping, Mapping},
+    event::{Event, Value},
+    internal_events::{RemapEventDropped, RemapEventProcessed, RemapFailedMapping},
+    mapping::{parser::parse as parse_mapping, Mapping, Outcome},
 };
-use serde::{Deserialize, Serialize};
+use futures01::{sync::mpsc, task::AtomicTask, try_ready, Async, AsyncSink, Poll, Sink, Stream};
+use indexmap::IndexMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Field of log events, or tag of metric events, holding the error of a failed mapping
+/// in the errors output.
+const ERROR_KEY: &str = "error";
+
+/// Number of failed events waiting for the errors output before the transform stops
+/// taking in more.
+const ERRORS_BUFFER: usize = 100;
+
+lazy_static::lazy_static! {
+    /// The errors outputs of remap transforms, by name. They outlive the transforms and
+    /// the outputs themselves, so that either can be rebuilt on reload without the other.
+    static ref ERRORS_OUTPUTS: Mutex<HashMap<String, Arc<ErrorsOutput>>> = Default::default();
+}
+
+static NEXT_ERRORS_ID: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
 #[serde(deny_unknown_fields, default)]
@@ -13,6 +38,7 @@ use serde::{Deserialize, Serialize};
 pub struct RemapConfig {
     pub mapping: String,
     pub drop_on_err: bool,
+    pub reroute_errors: bool,
 }
 
 inventory::submit! {
@@ -21,27 +47,55 @@ inventory::submit! {
 
 #[typetag::serde(name = "remap")]
 impl TransformConfig for RemapConfig {
-    fn build(&self, _cx: TransformContext) -> crate::Result<Box<dyn Transform>> {
-        Ok(Box::new(Remap::new(self.clone())?))
+    fn build(&self, cx: TransformContext) -> crate::Result<Box<dyn Transform>> {
+        let mut remap = Remap::new(self.clone())?;
+        if self.reroute_errors {
+            remap.errors_output = Some(ErrorsOutput::get(&errors_output_name(cx.name())));
+        }
+        Ok(Box::new(remap))
+    }
+
+    fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        if !self.reroute_errors {
+            return Ok(None);
+        }
+
+        // The transform keeps its name for the events it mapped, while those it
+        // failed to map go to `<name>.errors`.
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        map.insert(String::new(), Box::new(self.clone()));
+        map.insert("errors".to_owned(), Box::new(RemapErrorsConfig {}));
+        Ok(Some(map))
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn transform_type(&self) -> &'static str {
         "remap"
     }
+
+    fn check(&self) -> crate::Result<()> {
+        parse_mapping(&self.mapping)?;
+        Ok(())
+    }
+}
+
+fn errors_output_name(name: &str) -> String {
+    format!("{}.errors", name)
 }
 
 #[derive(Debug)]
 pub struct Remap {
     mapping: Mapping,
     drop_on_err: bool,
+    reroute_errors: bool,
+    errors_output: Option<Arc<ErrorsOutput>>,
 }
 
 impl Remap {
@@ -49,32 +103,302 @@ impl Remap {
         Ok(Remap {
             mapping: parse_mapping(&config.mapping)?,
             drop_on_err: config.drop_on_err,
+            reroute_errors: config.reroute_errors,
+            errors_output: None,
         })
     }
+
+    /// Maps an event, returning it with the error it failed with if `reroute` is set.
+    fn remap(&mut self, mut event: Event, reroute: bool) -> Result<Option<Event>, Event> {
+        emit!(RemapEventProcessed);
+
+        // Failed events are rerouted as they were before the mapping started changing them.
+        let original = if reroute { Some(event.clone()) } else { None };
+
+        match self.mapping.execute(&mut event) {
+            Ok(Outcome::Keep) => Ok(Some(event)),
+            Ok(Outcome::Drop) => {
+                emit!(RemapEventDropped);
+                Ok(None)
+            }
+            Err(error) => {
+                emit!(RemapFailedMapping {
+                    event_dropped: self.drop_on_err,
+                    event_rerouted: self.reroute_errors,
+                    error: error.clone()
+                });
+
+                if let Some(original) = original {
+                    Err(with_error(original, error))
+                } else if self.drop_on_err || self.reroute_errors {
+                    Ok(None)
+                } else {
+                    Ok(Some(event))
+                }
+            }
+        }
+    }
 }
 
 impl Transform for Remap {
-    fn transform(&mut self, mut event: Event) -> Option<Event> {
-        emit!(RemapEventProcessed);
+    fn transform(&mut self, event: Event) -> Option<Event> {
+        self.remap(event, false).unwrap_or(None)
+    }
+
+    fn transform_stream(
+        mut self: Box<Self>,
+        input_rx: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn Stream<Item = Event, Error = ()> + Send> {
+        match self.errors_output.take() {
+            Some(errors_output) => Box::new(RerouteErrors {
+                remap: self,
+                input_rx,
+                errors_output,
+                errors_tx: None,
+                failed: None,
+            }),
+            None => Box::new(input_rx.filter_map(move |event| self.transform(event))),
+        }
+    }
+}
+
+fn with_error(mut event: Event, error: String) -> Event {
+    match &mut event {
+        Event::Log(log) => {
+            log.insert(ERROR_KEY, Value::from(error));
+        }
+        Event::Metric(metric) => {
+            metric
+                .tags
+                .get_or_insert_with(Default::default)
+                .insert(ERROR_KEY.to_owned(), error);
+        }
+    }
+    event
+}
+
+/// Output of a remap transform whose failed events are passed on to its errors output,
+/// which holds back further input while that's full or not running.
+struct RerouteErrors {
+    remap: Box<Remap>,
+    input_rx: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    errors_output: Arc<ErrorsOutput>,
+    // Taken from the errors output on the first failed event, and again whenever that's
+    // been rebuilt.
+    errors_tx: Option<mpsc::Sender<Event>>,
+    failed: Option<Event>,
+}
+
+impl Stream for RerouteErrors {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(event) = self.failed.take() {
+                if self.errors_tx.is_none() {
+                    self.errors_tx = self.errors_output.sender();
+                }
+
+                let errors_tx = match &mut self.errors_tx {
+                    Some(errors_tx) => errors_tx,
+                    None => {
+                        self.failed = Some(event);
+                        return Ok(Async::NotReady);
+                    }
+                };
+
+                match errors_tx.start_send(event) {
+                    Ok(AsyncSink::Ready) => {}
+                    Ok(AsyncSink::NotReady(event)) => {
+                        self.failed = Some(event);
+                        return Ok(Async::NotReady);
+                    }
+                    Err(error) => {
+                        // The errors output was shut down, so the event goes to the one
+                        // replacing it.
+                        self.failed = Some(error.into_inner());
+                        self.errors_tx = None;
+                        continue;
+                    }
+                }
+            }
+
+            match try_ready!(self.input_rx.poll()) {
+                Some(event) => match self.remap.remap(event, true) {
+                    Ok(Some(event)) => return Ok(Async::Ready(Some(event))),
+                    Ok(None) => {}
+                    Err(failed) => self.failed = Some(failed),
+                },
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+/// Where the events failing the mapping of a remap transform are sent, held by the
+/// running errors output.
+#[derive(Debug)]
+struct ErrorsOutput {
+    tx: Mutex<Option<(usize, mpsc::Sender<Event>)>>,
+    // The transform waiting for an errors output to start running
+    waiting: AtomicTask,
+}
+
+impl ErrorsOutput {
+    fn get(name: &str) -> Arc<ErrorsOutput> {
+        let mut outputs = ERRORS_OUTPUTS.lock().unwrap();
+        let output = outputs.entry(name.to_owned()).or_insert_with(|| {
+            Arc::new(ErrorsOutput {
+                tx: Mutex::new(None),
+                waiting: AtomicTask::new(),
+            })
+        });
+        Arc::clone(output)
+    }
+
+    /// A sender to the running errors output. Without one, the current task is notified
+    /// once an errors output starts running.
+    fn sender(&self) -> Option<mpsc::Sender<Event>> {
+        self.waiting.register();
+        let tx = self.tx.lock().unwrap();
+        tx.as_ref().map(|(_, tx)| tx.clone())
+    }
+
+    fn attach(&self, id: usize, tx: mpsc::Sender<Event>) {
+        *self.tx.lock().unwrap() = Some((id, tx));
+        self.waiting.notify();
+    }
+
+    fn detach(&self, id: usize) {
+        let mut tx = self.tx.lock().unwrap();
+        if matches!(&*tx, Some((attached, _)) if *attached == id) {
+            *tx = None;
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The errors output of a remap transform, made of the events it failed to map,
+/// unchanged except for the error they failed with. It's fed by the transform itself
+/// rather than by its inputs, and is only ever created by expanding the transform.
+#[derive(Serialize, Debug)]
+pub struct RemapErrorsConfig {}
+
+impl<'de> Deserialize<'de> for RemapErrorsConfig {
+    fn deserialize<D: Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error> {
+        Err(de::Error::custom(
+            "`remap_errors` is only created by remap transforms with `reroute_errors` set",
+        ))
+    }
+}
+
+#[typetag::serde(name = "remap_errors")]
+impl TransformConfig for RemapErrorsConfig {
+    fn build(&self, cx: TransformContext) -> crate::Result<Box<dyn Transform>> {
+        let (errors_tx, errors_rx) = mpsc::channel(ERRORS_BUFFER);
+        Ok(Box::new(RemapErrors {
+            output: ErrorsOutput::get(cx.name()),
+            id: NEXT_ERRORS_ID.fetch_add(1, Ordering::Relaxed),
+            errors_tx: Some(errors_tx),
+            errors_rx,
+            input_done: false,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "remap_errors"
+    }
+
+    fn sibling_input(&self) -> Option<&str> {
+        // Takes the events the transform mapped, only to discard them, so that it runs
+        // for as long as the transform does.
+        Some("")
+    }
+}
+
+#[derive(Debug)]
+pub struct RemapErrors {
+    output: Arc<ErrorsOutput>,
+    id: usize,
+    // Handed to the transform once running, so that one built but never run, as when a
+    // reload fails, doesn't take the failed events.
+    errors_tx: Option<mpsc::Sender<Event>>,
+    errors_rx: mpsc::Receiver<Event>,
+    input_done: bool,
+}
+
+impl Transform for RemapErrors {
+    fn transform(&mut self, _event: Event) -> Option<Event> {
+        None
+    }
+
+    fn transform_stream(
+        self: Box<Self>,
+        input_rx: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn Stream<Item = Event, Error = ()> + Send> {
+        Box::new(RemapErrorsStream {
+            errors: self,
+            input_rx,
+        })
+    }
+}
+
+impl Drop for RemapErrors {
+    fn drop(&mut self) {
+        self.output.detach(self.id);
+    }
+}
+
+struct RemapErrorsStream {
+    errors: Box<RemapErrors>,
+    input_rx: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+}
 
-        if let Err(error) = self.mapping.execute(&mut event) {
-            emit!(RemapFailedMapping {
-                event_dropped: self.drop_on_err,
-                error
-            });
+impl Stream for RemapErrorsStream {
+    type Item = Event;
+    type Error = ();
 
-            if self.drop_on_err {
-                return None;
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let errors = &mut self.errors;
+        if let Some(errors_tx) = errors.errors_tx.take() {
+            errors.output.attach(errors.id, errors_tx);
+        }
+
+        while !errors.input_done {
+            match self.input_rx.poll()? {
+                Async::Ready(Some(_discarded)) => {}
+                Async::Ready(None) => errors.input_done = true,
+                Async::NotReady => break,
             }
         }
 
-        Some(event)
+        // The transform has stopped once its output has, so whatever it failed to map
+        // is already waiting here.
+        match errors.errors_rx.poll()? {
+            Async::NotReady if errors.input_done => Ok(Async::Ready(None)),
+            polled => Ok(polled),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        event::{Metric, MetricKind, MetricValue},
+        test_util::collect_ready,
+    };
+    use futures01::Future;
     use string_cache::DefaultAtom as Atom;
 
     fn get_field_string(event: &Event, field: &str) -> String {
@@ -100,6 +424,8 @@ mod tests {
 "#
             .to_string(),
             drop_on_err: true,
+            reroute_errors: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -110,4 +436,193 @@ mod tests {
         assert_eq!(get_field_string(&result, "bar"), "baz");
         assert_eq!(get_field_string(&result, "copy"), "buz");
     }
+
+    #[test]
+    fn check_remap_metric() {
+        let event = Event::Metric(Metric {
+            name: "requests".into(),
+            timestamp: None,
+            tags: Some(
+                vec![("host".to_owned(), "foo".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        });
+
+        let conf = RemapConfig {
+            mapping: r#"  .name = "http_" + .name
+  .tags.code = 200
+  del(.tags.host)
+  .counter.value = .counter.value * 2
+  .kind = "absolute"
+"#
+            .to_string(),
+            drop_on_err: true,
+            reroute_errors: false,
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let result = tform.transform(event).unwrap();
+        assert_eq!(
+            result,
+            Event::Metric(Metric {
+                name: "http_requests".into(),
+                timestamp: None,
+                tags: Some(
+                    vec![("code".to_owned(), "200".to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                kind: MetricKind::Absolute,
+                value: MetricValue::Counter { value: 2.0 },
+            })
+        );
+    }
+
+    #[test]
+    fn check_remap_metric_invalid() {
+        let event = Event::Metric(Metric {
+            name: "requests".into(),
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Gauge { value: 1.0 },
+        });
+
+        let conf = RemapConfig {
+            mapping: ".kind = \"sometimes\"".to_string(),
+            drop_on_err: false,
+            reroute_errors: false,
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let result = tform.transform(event.clone()).unwrap();
+        assert_eq!(result, event);
+    }
+
+    #[test]
+    fn check_remap_drop() {
+        let conf = RemapConfig {
+            mapping: r#"if .level == "debug" { drop() }"#.to_string(),
+            drop_on_err: false,
+            reroute_errors: false,
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let mut event = Event::from("noisy");
+        event.as_mut_log().insert("level", "debug");
+        assert_eq!(tform.transform(event), None);
+
+        let mut event = Event::from("important");
+        event.as_mut_log().insert("level", "error");
+        assert_eq!(tform.transform(event.clone()), Some(event));
+    }
+
+    fn build_rerouting(name: &str) -> (Box<dyn Transform>, Box<dyn Transform>) {
+        let mut conf = RemapConfig {
+            mapping: ".total = .count + 1".to_string(),
+            reroute_errors: true,
+            ..Default::default()
+        };
+        let expanded = conf.expand().unwrap().unwrap();
+        assert_eq!(expanded.keys().collect::<Vec<_>>(), vec!["", "errors"]);
+        assert_eq!(expanded["errors"].sibling_input(), Some(""));
+
+        let errors_name = errors_output_name(name);
+        (
+            expanded[""]
+                .build(TransformContext::new_test_named(name))
+                .unwrap(),
+            expanded["errors"]
+                .build(TransformContext::new_test_named(&errors_name))
+                .unwrap(),
+        )
+    }
+
+    fn failing(message: &str) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert("count", "one");
+        event
+    }
+
+    #[tokio::test]
+    async fn check_remap_reroute_errors() {
+        let (tform, errors) = build_rerouting("check_remap_reroute_errors");
+
+        let mut good = Event::from("good");
+        good.as_mut_log().insert("count", 1);
+
+        let input = futures01::stream::iter_ok(vec![good, failing("bad")]);
+        let mut mapped = tform.transform_stream(Box::new(input));
+        let (errors_in, errors_in_rx) = mpsc::channel(10);
+        let mut errors = errors.transform_stream(Box::new(errors_in_rx));
+
+        // The failed event waits for the errors output to start running
+        let output = collect_ready(&mut mapped).await.unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(get_field_string(&output[0], "total"), "2");
+        assert!(collect_ready(&mut errors).await.unwrap().is_empty());
+        assert!(collect_ready(&mut mapped).await.unwrap().is_empty());
+
+        // The errors output only gets the failed events, and ends along with its input
+        drop(errors_in);
+        let rerouted = errors.collect().wait().unwrap();
+        assert_eq!(rerouted.len(), 1);
+        assert_eq!(get_field_string(&rerouted[0], "message"), "bad");
+        assert!(rerouted[0].as_log().get(&Atom::from("total")).is_none());
+        assert!(get_field_string(&rerouted[0], "error").starts_with("failed to apply mapping 0"));
+    }
+
+    #[tokio::test]
+    async fn check_remap_errors_rebuilt() {
+        let name = "check_remap_errors_rebuilt";
+        let (tform, errors) = build_rerouting(name);
+        let rebuild = || {
+            RemapErrorsConfig {}
+                .build(TransformContext::new_test_named(&errors_output_name(name)))
+                .unwrap()
+        };
+
+        let (mut input, input_rx) = mpsc::channel(10);
+        let mut mapped = tform.transform_stream(Box::new(input_rx));
+        let (errors_in, errors_in_rx) = mpsc::channel(10);
+        let mut errors = errors.transform_stream(Box::new(errors_in_rx));
+        assert!(collect_ready(&mut errors).await.unwrap().is_empty());
+
+        input.try_send(failing("first")).unwrap();
+        assert!(collect_ready(&mut mapped).await.unwrap().is_empty());
+
+        // The replacing errors output takes over once running, while the one it
+        // replaces ends with what it was sent.
+        let (_rebuilt_in, rebuilt_in_rx) = mpsc::channel(10);
+        let mut rebuilt = rebuild().transform_stream(Box::new(rebuilt_in_rx));
+        assert!(collect_ready(&mut rebuilt).await.unwrap().is_empty());
+        drop(errors_in);
+        let rerouted = errors.collect().wait().unwrap();
+        assert_eq!(rerouted.len(), 1);
+        assert_eq!(get_field_string(&rerouted[0], "message"), "first");
+
+        // Nor does one built but never run
+        drop(rebuild());
+
+        input.try_send(failing("second")).unwrap();
+        assert!(collect_ready(&mut mapped).await.unwrap().is_empty());
+        let rerouted = collect_ready(&mut rebuilt).await.unwrap();
+        assert_eq!(rerouted.len(), 1);
+        assert_eq!(get_field_string(&rerouted[0], "message"), "second");
+    }
+
+    #[test]
+    fn check_remap_errors_not_configurable() {
+        let error =
+            toml::from_str::<Box<dyn TransformConfig>>(r#"type = "remap_errors""#).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("only created by remap transforms"));
+    }
 }
@@ -6,21 +6,51 @@ use structopt::StructOpt;
 #[derive(StructOpt, Debug)]
 #[structopt(rename_all = "kebab-case")]
 pub struct Opts {
-    /// Any number of Vector config files to test. If none are specified the
-    /// default config path `/etc/vector/vector.toml` will be targeted.
+    /// Any number of Vector config files to test.
+    /// Format is detected from the file name.
+    /// If none are specified the default config path `/etc/vector/vector.toml`
+    /// will be targeted.
     paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format to test.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format to test.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format to test.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Directories with Vector config files to test.
+    #[structopt(name = "config-dir", short = "C", long)]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::paths_with_formats(
+            &self.paths,
+            &self.paths_toml,
+            &self.paths_json,
+            &self.paths_yaml,
+            &self.config_dirs,
+        )
+    }
 }
 
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
     let mut failed_files: Vec<(String, Vec<(String, Vec<String>)>)> = Vec::new();
     let mut inspected_files: Vec<(String, Vec<(String, Vec<String>)>)> = Vec::new();
 
-    let paths = config::process_paths(&opts.paths).unwrap_or_else(|| {
+    let paths = config::process_paths(&opts.paths_with_formats()).unwrap_or_else(|| {
         std::process::exit(exitcode::CONFIG);
     });
 
     for (i, path) in paths.iter().enumerate() {
-        let path_str = path.to_str().unwrap_or("");
+        let path_str = path.as_path().to_str().unwrap_or("");
         if i > 0 {
             println!();
         }
@@ -18,9 +18,39 @@ pub struct Opts {
     #[structopt(short, long)]
     deny_warnings: bool,
 
-    /// Any number of Vector config files to validate. If none are specified the
-    /// default config path `/etc/vector/vector.toml` will be targeted.
+    /// Any number of Vector config files to validate.
+    /// Format is detected from the file name.
+    /// If none are specified the default config path `/etc/vector/vector.toml`
+    /// will be targeted.
     paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format to validate.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format to validate.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format to validate.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Directories with Vector config files to validate.
+    #[structopt(name = "config-dir", short = "C", long)]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::paths_with_formats(
+            &self.paths,
+            &self.paths_toml,
+            &self.paths_json,
+            &self.paths_yaml,
+            &self.config_dirs,
+        )
+    }
 }
 
 /// Performs topology, component, and health checks.
@@ -50,7 +80,7 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
 /// Err Some contains only successfully validated configs.
 fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     // Prepare paths
-    let paths = if let Some(paths) = config::process_paths(&opts.paths) {
+    let paths = if let Some(paths) = config::process_paths(&opts.paths_with_formats()) {
         paths
     } else {
         fmt.error("No config file paths");
@@ -6,19 +6,26 @@ mod support;
 
 #[cfg(feature = "api")]
 mod tests {
-    use crate::support::{sink, source};
+    use crate::support::{sink, source, transform};
     use chrono::Utc;
-    use futures::StreamExt;
+    use futures::{
+        compat::{Future01CompatExt, Stream01CompatExt},
+        StreamExt,
+    };
+    use futures01::Sink;
     use graphql_client::*;
     use std::{sync::Once, time::Duration};
     use tokio::{select, sync::oneshot};
     use vector::{
         self,
         api::{self, client::subscription::SubscriptionClient},
-        config::Config,
+        config::{self, Config},
+        event::Event,
         heartbeat,
         internal_events::{emit, GeneratorEventProcessed},
-        test_util::{next_addr, retry_until},
+        signal::{self, SignalTo},
+        test_util::{next_addr, retry_until, start_topology},
+        topology::control::TopologyControl,
     };
 
     static METRIC_INIT: Once = Once::new();
@@ -31,6 +38,46 @@ mod tests {
     )]
     struct HealthQuery;
 
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/queries/components.graphql",
+        response_derives = "Debug"
+    )]
+    struct ComponentsQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/queries/component_metrics.graphql",
+        response_derives = "Debug"
+    )]
+    struct ComponentMetricsQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/mutations/pause_source.graphql",
+        response_derives = "Debug"
+    )]
+    struct PauseSourceMutation;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/mutations/resume_source.graphql",
+        response_derives = "Debug"
+    )]
+    struct ResumeSourceMutation;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/mutations/reload_config.graphql",
+        response_derives = "Debug"
+    )]
+    struct ReloadConfigMutation;
+
     type DateTime = chrono::DateTime<chrono::Utc>;
 
     #[derive(GraphQLQuery)]
@@ -57,9 +104,19 @@ mod tests {
     )]
     struct EventsProcessedMetricsSubscription;
 
-    // Initialize the metrics system. Idempotent.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "graphql/schema.json",
+        query_path = "graphql/subscriptions/output_events.graphql",
+        response_derives = "Debug"
+    )]
+    struct OutputEventsSubscription;
+
+    // Initialize the metrics system. Idempotent. Component spans are logged at the error
+    // level, which must be enabled for counters to be labelled with their component
     fn init_metrics() {
         METRIC_INIT.call_once(|| {
+            vector::trace::init(false, false, "error");
             let _ = vector::metrics::init();
         })
     }
@@ -82,7 +139,7 @@ mod tests {
         let addr = config.api.bind.unwrap();
         let url = format!("http://{}:{}/{}", addr.ip(), addr.port(), url);
 
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(config.api, TopologyControl::default());
 
         // Build the request
         let client = reqwest::Client::new();
@@ -98,11 +155,25 @@ mod tests {
     async fn query<T: GraphQLQuery>(
         request_body: &graphql_client::QueryBody<T::Variables>,
     ) -> graphql_client::Response<T::ResponseData> {
-        let config = api_enabled_config();
-        let addr = config.api.bind.unwrap();
+        query_api::<T>(
+            api_enabled_config().api,
+            TopologyControl::default(),
+            request_body,
+        )
+        .await
+    }
+
+    // Starts an API server with the provided options, on top of the topology `control` is a
+    // handle on, and runs a single query against it
+    async fn query_api<T: GraphQLQuery>(
+        options: config::api::Options,
+        control: TopologyControl,
+        request_body: &graphql_client::QueryBody<T::Variables>,
+    ) -> graphql_client::Response<T::ResponseData> {
+        let addr = options.bind.unwrap();
         let url = format!("http://{}:{}/graphql", addr.ip(), addr.port());
 
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(options, control);
         let client = reqwest::Client::new();
 
         retry_until(
@@ -316,7 +387,7 @@ mod tests {
     /// Tests that the heartbeat subscription returns a UTC payload every 1/2 second
     async fn api_graphql_heartbeat() {
         let config = api_enabled_config();
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(config.api, TopologyControl::default());
         let bind = config.api.bind.unwrap();
         let client = new_subscription_client(bind).await;
 
@@ -327,7 +398,7 @@ mod tests {
     /// Tests for Vector instance uptime in seconds
     async fn api_graphql_uptime_metrics() {
         let config = api_enabled_config();
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(config.api, TopologyControl::default());
         let bind = config.api.bind.unwrap();
         let client = new_subscription_client(bind).await;
 
@@ -341,7 +412,7 @@ mod tests {
     /// Tests for events processed metrics, using fake generator events
     async fn api_graphql_event_processed_metrics() {
         let config = api_enabled_config();
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(config.api, TopologyControl::default());
         let bind = config.api.bind.unwrap();
         let client = new_subscription_client(bind).await;
 
@@ -354,7 +425,7 @@ mod tests {
     /// Tests whether 2 disparate subscriptions can run against a single client
     async fn api_graphql_combined_heartbeat_uptime() {
         let config = api_enabled_config();
-        let _server = api::Server::start(config.api);
+        let _server = api::Server::start(config.api, TopologyControl::default());
         let bind = config.api.bind.unwrap();
         let client = new_subscription_client(bind).await;
 
@@ -366,4 +437,362 @@ mod tests {
             new_heartbeat_subscription(&client, 3, 500),
         };
     }
+
+    #[tokio::test]
+    /// Tests that the components queries reflect the running topology
+    async fn api_graphql_components() {
+        let mut config = Config::builder();
+        config.add_source("components_in", source().1);
+        config.add_transform(
+            "components_transform",
+            &["components_in"],
+            transform("", 1.0),
+        );
+        config.add_sink("components_out", &["components_transform"], sink(10).1);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        let (topology, _crash) = start_topology(config, false).await;
+
+        let request_body = ComponentsQuery::build_query(components_query::Variables);
+        let data = query_api::<ComponentsQuery>(options, topology.control(), &request_body)
+            .await
+            .data
+            .unwrap();
+
+        let source = data
+            .sources
+            .iter()
+            .find(|s| s.name == "components_in")
+            .unwrap();
+        assert_eq!(source.component_type, "mock");
+
+        let transform = data
+            .transforms
+            .iter()
+            .find(|t| t.name == "components_transform")
+            .unwrap();
+        assert_eq!(transform.inputs, vec!["components_in"]);
+        assert_matches!(transform.input_type, components_query::DataType::ANY);
+
+        let sink = data
+            .sinks
+            .iter()
+            .find(|s| s.name == "components_out")
+            .unwrap();
+        assert_eq!(sink.inputs, vec!["components_transform"]);
+    }
+
+    #[tokio::test]
+    /// Tests that the componentMetrics query only returns the requested components
+    async fn api_graphql_component_metrics() {
+        let (mut tx, source) = source();
+        let mut config = Config::builder();
+        config.add_source("metrics_in", source);
+        let (rx, sink) = sink(10);
+        config.add_sink("metrics_out", &["metrics_in"], sink);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        init_metrics();
+        let (topology, _crash) = start_topology(config, false).await;
+
+        for i in 0..5 {
+            tx = tx
+                .send(Event::from(format!("metered {}", i)))
+                .compat()
+                .await
+                .unwrap();
+        }
+
+        // Events are counted as they enter the sink, so they've all been counted once
+        // the sink has passed them on
+        let received = rx.compat().take(5).collect::<Vec<_>>().await;
+        assert_eq!(received.len(), 5);
+
+        let request_body = ComponentMetricsQuery::build_query(component_metrics_query::Variables {
+            names: Some(vec!["metrics_out".to_owned()]),
+        });
+        let res =
+            query_api::<ComponentMetricsQuery>(options, topology.control(), &request_body).await;
+
+        assert!(res.errors.is_none());
+        let metrics = res.data.unwrap().component_metrics;
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].name, "metrics_out");
+        assert_eq!(metrics[0].events_in, 5.0);
+        assert_eq!(metrics[0].errors, 0.0);
+    }
+
+    #[tokio::test]
+    /// Tests that events leaving a source can be observed through the outputEvents subscription
+    async fn api_graphql_output_events() {
+        let (mut tx, source) = source();
+        let mut config = Config::builder();
+        config.add_source("tap_in", source);
+        config.add_sink("tap_out", &["tap_in"], sink(100).1);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        let (topology, _crash) = start_topology(config, false).await;
+        let _server = api::Server::start(options, topology.control());
+        let client = new_subscription_client(options.bind.unwrap()).await;
+
+        let request_body =
+            OutputEventsSubscription::build_query(output_events_subscription::Variables {
+                component_names: vec!["tap_in".to_owned()],
+                limit: 10,
+                interval: 100,
+            });
+        let subscription = client
+            .start::<OutputEventsSubscription>(&request_body)
+            .await
+            .unwrap();
+
+        // The tap only sees events sent after it's attached, so keep sending until then
+        tokio::spawn(async move {
+            for i in 0..100 {
+                tx = tx
+                    .send(Event::from(format!("tapped {}", i)))
+                    .compat()
+                    .await
+                    .unwrap();
+                tokio::time::delay_for(Duration::from_millis(50)).await;
+            }
+        });
+
+        tokio::pin! {
+            let events = subscription.stream().take(1);
+        }
+
+        let output_event = events
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .unwrap()
+            .output_events;
+
+        assert_eq!(output_event.component_name, "tap_in");
+        assert_eq!(output_event.event_type, "log");
+        assert!(output_event.json.contains("tapped"));
+    }
+
+    #[tokio::test]
+    /// Tests that a component rebuilt by a reload keeps being observed through the
+    /// outputEvents subscription
+    async fn api_graphql_output_events_reload() {
+        let (mut tx, source) = source();
+        let (_rx, sink) = sink(1000);
+        let mut config = Config::builder();
+        config.add_source("tap_reload_in", source);
+        config.add_transform(
+            "tap_reload_transform",
+            &["tap_reload_in"],
+            transform(" v1", 0.0),
+        );
+        config.add_sink("tap_reload_out", &["tap_reload_transform"], sink);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        let (mut topology, _crash) = start_topology(config, false).await;
+        let _server = api::Server::start(options, topology.control());
+        let client = new_subscription_client(options.bind.unwrap()).await;
+
+        let request_body =
+            OutputEventsSubscription::build_query(output_events_subscription::Variables {
+                component_names: vec!["tap_reload_transform".to_owned()],
+                limit: 10,
+                interval: 100,
+            });
+        let subscription = client
+            .start::<OutputEventsSubscription>(&request_body)
+            .await
+            .unwrap();
+
+        tokio::spawn(async move {
+            for i in 0..500 {
+                tx = tx
+                    .send(Event::from(format!("tapped {}", i)))
+                    .compat()
+                    .await
+                    .unwrap();
+                tokio::time::delay_for(Duration::from_millis(20)).await;
+            }
+        });
+
+        tokio::pin! {
+            let events = subscription.stream().map(|event| {
+                event.unwrap().data.unwrap().output_events.json
+            });
+        }
+
+        // Make sure the tap is attached before the transform is rebuilt
+        let json = events.next().await.unwrap();
+        assert!(json.contains(" v1"));
+
+        let mut config = Config::builder();
+        config.add_source("tap_reload_in", source().1);
+        config.add_transform(
+            "tap_reload_transform",
+            &["tap_reload_in"],
+            transform(" v2", 0.0),
+        );
+        config.add_sink("tap_reload_out", &["tap_reload_transform"], sink(1000).1);
+        assert!(topology
+            .reload_config_and_respawn(config.build().unwrap(), false)
+            .await
+            .unwrap());
+
+        let rebuilt = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(json) = events.next().await {
+                if json.contains(" v2") {
+                    return true;
+                }
+            }
+            false
+        });
+        assert!(rebuilt.await.unwrap());
+    }
+
+    #[tokio::test]
+    /// Tests that a source in the running topology can be paused and resumed
+    async fn api_graphql_pause_source() {
+        let (mut tx, source) = source();
+        let (rx, sink) = sink(10);
+        let mut config = Config::builder();
+        config.add_source("pause_in", source);
+        config.add_sink("pause_out", &["pause_in"], sink);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        let (topology, _crash) = start_topology(config, false).await;
+        let mut rx = rx.compat();
+
+        let request_body = PauseSourceMutation::build_query(pause_source_mutation::Variables {
+            name: "pause_in".to_owned(),
+        });
+        let data = query_api::<PauseSourceMutation>(options, topology.control(), &request_body)
+            .await
+            .data
+            .unwrap();
+        assert!(data.pause_source);
+
+        // The source can't pass on events while it's paused
+        tokio::spawn(async move {
+            tx.send(Event::from("paused")).compat().await.unwrap();
+        });
+        assert!(tokio::time::timeout(Duration::from_millis(500), rx.next())
+            .await
+            .is_err());
+
+        let request_body = ResumeSourceMutation::build_query(resume_source_mutation::Variables {
+            name: "pause_in".to_owned(),
+        });
+        let data = query_api::<ResumeSourceMutation>(options, topology.control(), &request_body)
+            .await
+            .data
+            .unwrap();
+        assert!(data.resume_source);
+
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event.as_log()[&config::log_schema().message_key()],
+            "paused".into()
+        );
+    }
+
+    #[tokio::test]
+    /// Tests that pausing or resuming something other than a running source fails
+    async fn api_graphql_pause_unknown_source() {
+        let options = api_enabled_config().api;
+
+        let request_body = PauseSourceMutation::build_query(pause_source_mutation::Variables {
+            name: "no_such_source".to_owned(),
+        });
+        let data =
+            query_api::<PauseSourceMutation>(options, TopologyControl::default(), &request_body)
+                .await
+                .data
+                .unwrap();
+        assert!(!data.pause_source);
+    }
+
+    #[tokio::test]
+    /// Tests that a paused source doesn't hold up shutting down the topology
+    async fn api_graphql_pause_source_shutdown() {
+        let (mut tx, source) = source();
+        let mut config = Config::builder();
+        config.add_source("pause_shutdown_in", source);
+        config.add_sink("pause_shutdown_out", &["pause_shutdown_in"], sink(10).1);
+        config.api.enabled = true;
+        config.api.bind = Some(next_addr());
+
+        let config = config.build().unwrap();
+        let options = config.api;
+        let (topology, _crash) = start_topology(config, false).await;
+
+        let request_body = PauseSourceMutation::build_query(pause_source_mutation::Variables {
+            name: "pause_shutdown_in".to_owned(),
+        });
+        let data = query_api::<PauseSourceMutation>(options, topology.control(), &request_body)
+            .await
+            .data
+            .unwrap();
+        assert!(data.pause_source);
+
+        // Leave an event waiting on the paused source
+        tokio::spawn(async move {
+            tx.send(Event::from("paused")).compat().await.unwrap();
+        });
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        assert!(
+            tokio::time::timeout(Duration::from_secs(5), topology.stop().compat())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    /// Tests that reloadConfig waits for the reload, and returns its outcome
+    async fn api_graphql_reload_config() {
+        let control = TopologyControl::default();
+
+        // Stands in for the main loop of Vector, which reports the outcome of each reload
+        let signals = signal::signals();
+        let main_control = control.clone();
+        tokio::spawn(async move {
+            tokio::pin!(signals);
+            while let Some(signal) = signals.next().await {
+                if signal == SignalTo::Reload {
+                    let requested = main_control.reload_started();
+                    main_control.report_reload(requested, true);
+                }
+            }
+        });
+
+        let request_body = ReloadConfigMutation::build_query(reload_config_mutation::Variables);
+        let data =
+            query_api::<ReloadConfigMutation>(api_enabled_config().api, control, &request_body)
+                .await
+                .data
+                .unwrap();
+        assert!(data.reload_config);
+    }
 }
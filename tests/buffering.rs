@@ -1,4 +1,4 @@
-#![cfg(feature = "leveldb")]
+#![cfg(feature = "disk-buffer")]
 
 use futures::{
     compat::{Future01CompatExt, Sink01CompatExt},
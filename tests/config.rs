@@ -4,7 +4,7 @@ use vector::{
 };
 
 async fn load(config: &str) -> Result<Vec<String>, Vec<String>> {
-    match config::load_from_str(config) {
+    match config::load_from_str(config, Some(config::Format::TOML)) {
         Ok(c) => {
             let diff = ConfigDiff::initial(&c);
             match (
@@ -255,6 +255,42 @@ async fn bad_regex() {
     assert!(err[0].contains("error: unclosed character class"));
 }
 
+#[cfg(all(
+    feature = "sources-socket",
+    feature = "transforms-remap",
+    feature = "sinks-socket"
+))]
+#[test]
+fn bad_remap_mapping() {
+    // Checked as the config is loaded, ahead of building any component.
+    let err = config::load_from_str(
+        r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [transforms.remap]
+        type = "remap"
+        inputs = ["in"]
+        mapping = ".foo = truncate(.foo, 5, limit = 3)"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["remap"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+      "#,
+        Some(config::Format::TOML),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.len(), 1);
+    assert!(err[0].starts_with("Transform \"remap\": "));
+    assert!(err[0].contains("argument 'limit' given more than once"));
+}
+
 #[cfg(all(
     feature = "sources-socket",
     feature = "transforms-regex_parser",
@@ -420,6 +420,56 @@ async fn topology_swap_transform() {
     assert_eq!(vec!["this replaced"], res1v2);
 }
 
+#[cfg(feature = "transforms-remap")]
+#[tokio::test]
+async fn topology_reload_remap_keeps_errors_output() {
+    use vector::transforms::remap::RemapConfig;
+
+    // Every event fails, going to the errors output.
+    let remap = |drop_on_err| RemapConfig {
+        mapping: ".total = .message + 1".to_owned(),
+        drop_on_err,
+        reroute_errors: true,
+    };
+
+    let (in1, source1) = source();
+    let (out1, sink1) = sink(10);
+    let (errors1, errors_sink1) = sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_transform("reroute_reload", &["in1"], remap(false));
+    config.add_sink("out1", &["reroute_reload"], sink1);
+    config.add_sink("errors1", &["reroute_reload.errors"], errors_sink1);
+
+    let (mut topology, _crash) = start_topology(config.build().unwrap(), false).await;
+
+    // Only the transform is rebuilt, its errors output keeps running.
+    let mut config = Config::builder();
+    config.add_source("in1", source().1);
+    config.add_transform("reroute_reload", &["in1"], remap(true));
+    config.add_sink("out1", &["reroute_reload"], sink(10).1);
+    config.add_sink("errors1", &["reroute_reload.errors"], sink(10).1);
+
+    assert!(topology
+        .reload_config_and_respawn(config.build().unwrap(), false)
+        .await
+        .unwrap());
+
+    // More than the errors output buffers.
+    let events = (0..150).map(|i| Event::from(format!("event {}", i)));
+    let h_out1 = tokio::spawn(out1.collect().compat());
+    let h_errors1 = tokio::spawn(errors1.map(into_message).collect().compat());
+    in1.send_all(iter_ok(events)).compat().await.unwrap();
+    topology.stop().compat().await.unwrap();
+    let res_out1 = h_out1.await.unwrap().unwrap();
+    let res_errors1 = h_errors1.await.unwrap().unwrap();
+
+    assert!(res_out1.is_empty());
+    assert_eq!(res_errors1.len(), 150);
+    assert_eq!(res_errors1[149], "event 149");
+}
+
 #[ignore] // TODO: issue #2186
 #[tokio::test]
 async fn topology_swap_transform_is_atomic() {